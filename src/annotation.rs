@@ -1,8 +1,11 @@
+use std::path::PathBuf;
+
 use pest::{iterators::Pair, Parser};
+use serde::Serialize;
 
 use crate::{
-    treesitter::FieldName,
-    types::{self, Type},
+    treesitter::{FieldName, FunctionParam},
+    types::{self, Type, TypeInner},
 };
 
 #[derive(pest_derive::Parser)]
@@ -18,12 +21,6 @@ pub fn parse_type(type_pair: Pair<Rule>) -> Type {
 
     let mut types = Vec::new();
     let mut nullable = false;
-    let is_union = type_pair
-        .clone()
-        .into_inner()
-        .filter(|pair| pair.as_rule() == Rule::single_type)
-        .count()
-        > 1;
 
     for pair in type_pair.into_inner() {
         if pair.as_rule() == Rule::nullable {
@@ -31,63 +28,19 @@ pub fn parse_type(type_pair: Pair<Rule>) -> Type {
             continue;
         }
 
-        assert_eq!(pair.as_rule(), Rule::single_type);
-        let mut ty = None;
-        for pair in pair.into_inner() {
-            match pair.as_rule() {
-                Rule::function_def => ty = Some(parse_function(pair)),
-                Rule::table_def => ty = Some(parse_table(pair)),
-                Rule::tuple_def => ty = Some(parse_tuple(pair)),
-                Rule::str_lit => ty = Some(Type::string_literal(pair.as_str())),
-                Rule::int_lit => {
-                    ty = Some(Type::integer_literal(pair.as_str().parse().unwrap()));
-                }
-                Rule::type_ident => {
-                    ty = Some(match pair.as_str() {
-                        types::NIL => Type::NIL,
-                        types::ANY => Type::ANY,
-                        types::BOOLEAN => Type::BOOLEAN,
-                        types::STRING => Type::STRING,
-                        types::NUMBER => Type::NUMBER,
-                        types::INTEGER => Type::INTEGER,
-                        types::TABLE => Type::TABLE,
-                        types::THREAD => Type::THREAD,
-                        types::USERDATA => Type::USERDATA,
-                        types::LIGHT_USERDATA => Type::LIGHT_USERDATA,
-                        types::literals::TRUE => Type::boolean_literal(true),
-                        types::literals::FALSE => Type::boolean_literal(false),
-                        user_defined => Type::user_defined(user_defined),
-                    });
-                }
-                Rule::ty => ty = Some(parse_type(pair)),
-                Rule::generics => {
-                    let Some(ty) = ty.as_mut() else {
-                        unreachable!();
-                    };
-
-                    for pair in pair.into_inner() {
-                        ty.add_generic(parse_type(pair));
-                    }
-                }
-                Rule::array => {
-                    let Some(ty) = ty.as_mut() else {
-                        unreachable!();
-                    };
+        assert_eq!(pair.as_rule(), Rule::intersection);
+        let mut intersection = pair.into_inner().map(parse_single_type).collect::<Vec<_>>();
 
-                    ty.make_array();
-                }
-                _ => unreachable!(),
-            };
-        }
-
-        types.extend(ty);
+        types.push(if intersection.len() > 1 {
+            Type::intersection(intersection)
+        } else {
+            intersection.pop().unwrap()
+        });
     }
 
-    let mut ty = if is_union {
-        assert!(types.len() > 1);
+    let mut ty = if types.len() > 1 {
         Type::union(types)
     } else {
-        assert!(types.len() == 1);
         types.pop().unwrap()
     };
 
@@ -98,6 +51,60 @@ pub fn parse_type(type_pair: Pair<Rule>) -> Type {
     ty
 }
 
+fn parse_single_type(pair: Pair<Rule>) -> Type {
+    assert_eq!(pair.as_rule(), Rule::single_type);
+
+    let mut ty = None;
+    for pair in pair.into_inner() {
+        match pair.as_rule() {
+            Rule::function_def => ty = Some(parse_function(pair)),
+            Rule::table_def => ty = Some(parse_table(pair)),
+            Rule::tuple_def => ty = Some(parse_tuple(pair)),
+            Rule::str_lit => ty = Some(Type::string_literal(pair.as_str())),
+            Rule::int_lit => {
+                ty = Some(Type::integer_literal(pair.as_str().parse().unwrap()));
+            }
+            Rule::type_ident => {
+                ty = Some(match pair.as_str() {
+                    types::NIL => Type::NIL,
+                    types::ANY => Type::ANY,
+                    types::BOOLEAN => Type::BOOLEAN,
+                    types::STRING => Type::STRING,
+                    types::NUMBER => Type::NUMBER,
+                    types::INTEGER => Type::INTEGER,
+                    types::TABLE => Type::TABLE,
+                    types::THREAD => Type::THREAD,
+                    types::USERDATA => Type::USERDATA,
+                    types::LIGHT_USERDATA => Type::LIGHT_USERDATA,
+                    types::literals::TRUE => Type::boolean_literal(true),
+                    types::literals::FALSE => Type::boolean_literal(false),
+                    user_defined => Type::user_defined(user_defined),
+                });
+            }
+            Rule::ty => ty = Some(parse_type(pair)),
+            Rule::generics => {
+                let Some(ty) = ty.as_mut() else {
+                    unreachable!();
+                };
+
+                for pair in pair.into_inner() {
+                    ty.add_generic(parse_type(pair));
+                }
+            }
+            Rule::array => {
+                let Some(ty) = ty.as_mut() else {
+                    unreachable!();
+                };
+
+                ty.make_array();
+            }
+            _ => unreachable!(),
+        };
+    }
+
+    ty.unwrap()
+}
+
 fn parse_function(pair: Pair<Rule>) -> Type {
     assert_eq!(pair.as_rule(), Rule::function_def);
 
@@ -213,6 +220,19 @@ fn parse_tuple(pair: Pair<Rule>) -> Type {
     Type::tuple(types)
 }
 
+/// Turn the rest of an annotation line into a description, trimming whitespace and a
+/// leading `#` or `--` comment marker.
+fn parse_description(rest_of_line: &str) -> Option<String> {
+    let desc = rest_of_line.trim();
+    let desc = desc
+        .strip_prefix("--")
+        .or_else(|| desc.strip_prefix('#'))
+        .unwrap_or(desc)
+        .trim();
+
+    (!desc.is_empty()).then(|| desc.to_string())
+}
+
 pub fn parse_class(class: &str, description: Option<String>) -> anyhow::Result<Class> {
     let mut class = PestParser::parse(Rule::class, class)?;
 
@@ -230,13 +250,9 @@ pub fn parse_class(class: &str, description: Option<String>) -> anyhow::Result<C
     }
 
     Ok(Class {
-        name: name.unwrap(),
-        description,
         exact,
         parent,
-        lsp_fields: Vec::new(),
-        ts_fields: Vec::new(),
-        is_module: false, // TODO:
+        ..Class::new(name.unwrap(), description)
     })
 }
 
@@ -272,9 +288,7 @@ pub fn parse_field(field: &str, description: Option<String>) -> anyhow::Result<L
                 ident_type = Some(Type::string_literal(pair.as_str()));
             }
             Rule::nullable => nullable = true,
-            Rule::rest_of_line => {
-                eol_desc = Some(pair.as_str().to_string());
-            }
+            Rule::rest_of_line => eol_desc = parse_description(pair.as_str()),
             _ => unreachable!(),
         }
     }
@@ -288,6 +302,10 @@ pub fn parse_field(field: &str, description: Option<String>) -> anyhow::Result<L
         ty: ty.unwrap(),
         description: description.or(eol_desc),
         scope,
+        default: None,
+        since: None,
+        deprecated: None,
+        location: None,
     })
 }
 
@@ -295,26 +313,39 @@ pub fn parse_alias(alias: &str, description: Option<String>) -> anyhow::Result<A
     let mut alias = PestParser::parse(Rule::alias, alias)?;
 
     let mut name = None;
+    let mut type_params = Vec::new();
     let mut eol_desc = None;
     let mut inline_alias = None;
 
     for pair in alias.next().unwrap().into_inner() {
         match pair.as_rule() {
             Rule::type_ident => name = Some(pair.as_str().to_string()),
+            Rule::alias_type_params => {
+                type_params = pair
+                    .into_inner()
+                    .map(|param| param.as_str().to_string())
+                    .collect();
+            }
             Rule::ty => inline_alias = Some(parse_type(pair)),
-            Rule::rest_of_line => eol_desc = Some(pair.as_str().to_string()),
+            Rule::rest_of_line => eol_desc = parse_description(pair.as_str()),
             _ => unreachable!(),
         }
     }
 
-    let mut aliases = Vec::new();
-    aliases.extend(inline_alias.map(|alias| (alias, eol_desc)));
-
-    Ok(Alias {
+    let mut alias = Alias {
         name: name.unwrap(),
+        type_params,
         description,
-        types: aliases,
-    })
+        types: Vec::new(),
+        since: None,
+        order: 0,
+    };
+
+    if let Some(inline_alias) = inline_alias {
+        alias.add_type(inline_alias, eol_desc);
+    }
+
+    Ok(alias)
 }
 
 pub fn parse_alias_line(
@@ -329,7 +360,7 @@ pub fn parse_alias_line(
     for pair in line.next().unwrap().into_inner() {
         match pair.as_rule() {
             Rule::ty => ty = Some(parse_type(pair)),
-            Rule::rest_of_line => eol_desc = Some(pair.as_str().to_string()),
+            Rule::rest_of_line => eol_desc = parse_description(pair.as_str()),
             _ => unreachable!(),
         }
     }
@@ -351,7 +382,7 @@ pub fn parse_param(param: &str) -> anyhow::Result<Param> {
             Rule::ident => name = Some(pair.as_str().to_string()),
             Rule::nullable => nullable = true,
             Rule::ty => ty = Some(parse_type(pair)),
-            Rule::rest_of_line => description = Some(pair.as_str().to_string()),
+            Rule::rest_of_line => description = parse_description(pair.as_str()),
             _ => unreachable!(),
         }
     }
@@ -360,36 +391,107 @@ pub fn parse_param(param: &str) -> anyhow::Result<Param> {
         ty.as_mut().unwrap().make_nullable();
     }
 
+    let (default, description) = match description.as_deref().and_then(split_inline_default) {
+        Some((default, rest)) => (Some(default), rest),
+        None => (None, description),
+    };
+
     Ok(Param {
         name: name.unwrap(),
         ty: ty.unwrap(),
         description,
+        default,
     })
 }
 
+/// Split an inline `[default=<value>]` off the start or end of a parameter description,
+/// returning the default and what's left of the description.
+///
+/// Descriptions without one, or with an empty value, aren't split.
+fn split_inline_default(description: &str) -> Option<(String, Option<String>)> {
+    const PREFIX: &str = "[default=";
+
+    let (value, rest) = if let Some(rest) = description.strip_prefix(PREFIX) {
+        let (value, rest) = rest.split_once(']')?;
+        (value, rest)
+    } else {
+        let start = description.rfind(PREFIX)?;
+        let value = description[start + PREFIX.len()..].strip_suffix(']')?;
+        (value, &description[..start])
+    };
+
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    let rest = rest.trim();
+    Some((
+        value.to_string(),
+        (!rest.is_empty()).then(|| rest.to_string()),
+    ))
+}
+
 pub fn parse_return(param: &str) -> anyhow::Result<Return> {
     let mut ret = PestParser::parse(Rule::ret, param)?;
 
     let mut name = None;
     let mut ty = None;
     let mut description = None;
+    let mut has_separator = false;
 
     for pair in ret.next().unwrap().into_inner() {
         match pair.as_rule() {
             Rule::ty => ty = Some(parse_type(pair)),
             Rule::ident => name = Some(pair.as_str().to_string()),
-            Rule::rest_of_line => description = Some(pair.as_str().to_string()),
+            Rule::rest_of_line => {
+                let rest = pair.as_str().trim_start();
+                has_separator = rest.starts_with('#') || rest.starts_with("--");
+                description = parse_description(rest);
+            }
             _ => unreachable!(),
         }
     }
 
+    // `---@return boolean the result` has no name, only a description. With LuaLS's
+    // `---@return boolean Ok # the result`, the word before the `#` is always the name.
+    if let (Some(word), Some(desc), false) =
+        (name.as_deref(), description.as_deref(), has_separator)
+    {
+        if starts_prose(word) {
+            description = Some(format!("{word} {desc}"));
+            name = None;
+        }
+    }
+
+    let mut ty = ty.unwrap();
+
+    // A bare `---@return ...` returns any number of values of any type, like a
+    // `---@param ... any`
+    if name.is_none() && matches!(&ty.inner, TypeInner::UserDefined(ty) if ty == "...") {
+        name = Some("...".to_string());
+        ty = Type::ANY;
+    }
+
     Ok(Return {
         name,
-        ty: ty.unwrap(),
+        ty,
         description,
     })
 }
 
+/// Whether the word after a return type starts a sentence instead of naming the return,
+/// like `the` or `Whether`.
+fn starts_prose(word: &str) -> bool {
+    const PROSE_WORDS: [&str; 16] = [
+        "a", "an", "and", "if", "is", "of", "or", "the", "then", "this", "to", "true", "false",
+        "when", "whether", "which",
+    ];
+
+    PROSE_WORDS.contains(&word.to_lowercase().as_str())
+        || word.chars().next().is_some_and(char::is_uppercase)
+}
+
 pub fn parse_enum(r#enum: &str, description: Option<String>) -> anyhow::Result<Enum> {
     let mut r#enum = PestParser::parse(Rule::_enum, r#enum)?;
 
@@ -410,6 +512,8 @@ pub fn parse_enum(r#enum: &str, description: Option<String>) -> anyhow::Result<E
         description,
         is_key,
         fields: Vec::new(),
+        since: None,
+        order: 0,
     })
 }
 
@@ -427,14 +531,104 @@ pub fn parse_lcat(lcat: &str) -> Lcat {
     Lcat { options: opts }
 }
 
-pub fn parse_type_annotation(ty: &str) -> anyhow::Result<Type> {
+/// Parse the free-form text of annotations like `@default` and `@since`.
+pub fn parse_text(text: &str) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// The badge types VitePress styles, which `@badge` accepts.
+pub const BADGE_TYPES: [&str; 4] = ["info", "tip", "warning", "danger"];
+
+/// Parse a `@badge <type> <text>` into its type and text.
+pub fn parse_badge(badge: &str) -> anyhow::Result<(String, String)> {
+    let badge = badge.trim();
+    let (ty, text) = badge.split_once(char::is_whitespace).unwrap_or((badge, ""));
+
+    if !BADGE_TYPES.contains(&ty) {
+        anyhow::bail!(
+            "badge type `{ty}` isn't one of {}",
+            BADGE_TYPES.map(|ty| format!("`{ty}`")).join(", ")
+        );
+    }
+
+    let Some(text) = parse_text(text) else {
+        anyhow::bail!("expected the text of the badge after its type");
+    };
+
+    Ok((ty.to_string(), text))
+}
+
+/// Parse the name of a `@module`, which LuaLS writes quoted like `---@module 'lib.window'`.
+pub fn parse_module(module: &str) -> Option<String> {
+    parse_text(module).map(|module| types::unquote(&module).to_string())
+}
+
+/// Parse the types of a `@type`, one for each target of a multiple assignment.
+pub fn parse_type_annotation(ty: &str) -> anyhow::Result<Vec<Type>> {
     let mut type_annotation = PestParser::parse(Rule::type_annotation, ty)?;
 
-    let ty = type_annotation.next().unwrap().into_inner().next().unwrap();
+    let types = type_annotation
+        .next()
+        .unwrap()
+        .into_inner()
+        .filter(|pair| pair.as_rule() == Rule::ty)
+        .map(parse_type)
+        .collect();
+
+    Ok(types)
+}
+
+/// Parse an `@error`/`@throws` annotation into the error type and its description.
+pub fn parse_error(error: &str) -> anyhow::Result<(Type, Option<String>)> {
+    let mut error = PestParser::parse(Rule::error_annotation, error)?;
+
+    let mut ty = None;
+    let mut desc = None;
+
+    for pair in error.next().unwrap().into_inner() {
+        match pair.as_rule() {
+            Rule::ty => ty = Some(parse_type(pair)),
+            Rule::rest_of_line => desc = parse_description(pair.as_str()),
+            _ => unreachable!(),
+        }
+    }
+
+    Ok((ty.unwrap(), desc))
+}
+
+/// Parse a `@generic` annotation into the names of the type parameters and
+/// the types they're constrained to.
+pub fn parse_generic(generic: &str) -> anyhow::Result<Vec<(String, Option<Type>)>> {
+    let mut generic = PestParser::parse(Rule::generic, generic)?;
 
-    assert_eq!(ty.as_rule(), Rule::ty);
+    let mut type_params = Vec::new();
 
-    Ok(parse_type(ty))
+    for pair in generic.next().unwrap().into_inner() {
+        match pair.as_rule() {
+            Rule::generic_param => {
+                let mut inner = pair.into_inner();
+                let name = inner.next().unwrap().as_str().to_string();
+                let constraint = inner.next().map(parse_type);
+                type_params.push((name, constraint));
+            }
+            Rule::rest_of_line => (),
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(type_params)
+}
+
+/// Parse an `@overload` annotation into the function type of the alternative signature.
+pub fn parse_overload(overload: &str) -> anyhow::Result<Type> {
+    let mut overload = PestParser::parse(Rule::overload, overload)?;
+
+    let function_def = overload.next().unwrap().into_inner().next().unwrap();
+
+    assert_eq!(function_def.as_rule(), Rule::function_def);
+
+    Ok(parse_function(function_def))
 }
 
 pub fn parse_see(see: &str) -> anyhow::Result<See> {
@@ -445,8 +639,8 @@ pub fn parse_see(see: &str) -> anyhow::Result<See> {
 
     for pair in see.next().unwrap().into_inner() {
         match pair.as_rule() {
-            Rule::type_ident => ident = Some(pair.as_str().to_string()),
-            Rule::rest_of_line => desc = Some(pair.as_str().to_string()),
+            Rule::see_ident | Rule::url => ident = Some(pair.as_str().to_string()),
+            Rule::rest_of_line => desc = parse_description(pair.as_str()),
             _ => unreachable!(),
         }
     }
@@ -457,15 +651,59 @@ pub fn parse_see(see: &str) -> anyhow::Result<See> {
     })
 }
 
+pub fn parse_operator(operator: &str, description: Option<String>) -> anyhow::Result<Operator> {
+    let mut operator = PestParser::parse(Rule::operator, operator)?;
+
+    let mut op = None;
+    let mut input = None;
+    let mut result = None;
+    let mut eol_desc = None;
+
+    for pair in operator.next().unwrap().into_inner() {
+        match pair.as_rule() {
+            Rule::ident => op = Some(pair.as_str().to_string()),
+            Rule::operator_input => input = Some(parse_type(pair.into_inner().next().unwrap())),
+            Rule::ty => result = Some(parse_type(pair)),
+            Rule::rest_of_line => eol_desc = parse_description(pair.as_str()),
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(Operator {
+        op: op.unwrap(),
+        input,
+        result: result.unwrap(),
+        description: eol_desc.or(description),
+    })
+}
+
 #[derive(Debug, Clone)]
 pub struct Alias {
     pub name: String,
+    /// The type parameters of a generic alias, like `T` in `Result<T>`.
+    pub type_params: Vec<String>,
     pub description: Option<String>,
+    /// The aliased types and their descriptions in source order: the type on the
+    /// `@alias` line, if any, followed by each `---|` line.
     pub types: Vec<(Type, Option<String>)>,
+    pub since: Option<String>,
+    /// The position of the block declaring this among every processed block, for
+    /// keeping source order. Set by [`Processor`](crate::processor::Processor).
+    pub order: usize,
 }
 
 impl Alias {
-    pub fn add_type(&mut self, ty: Type, desc: Option<String>) {
+    /// The alias's name followed by its type parameters, like `Result<T>`.
+    pub fn name_with_type_params(&self) -> String {
+        if self.type_params.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}<{}>", self.name, self.type_params.join(", "))
+        }
+    }
+
+    pub fn add_type(&mut self, mut ty: Type, desc: Option<String>) {
+        ty.resolve_type_params(&self.type_params);
         self.types.push((ty, desc));
     }
 }
@@ -478,7 +716,26 @@ pub struct Class {
     pub parent: Option<Type>,
     pub lsp_fields: Vec<LspField>,
     pub ts_fields: Vec<TsField>,
+    /// Operator overloads declared with `@operator`.
+    ///
+    /// An operator can appear more than once, like `sub` for both negation and subtraction.
+    pub operators: Vec<Operator>,
+    /// Whether this class is the module declared with `@module` in the same block.
     pub is_module: bool,
+    /// The module declared with `@module` earlier in the class's file, if any.
+    ///
+    /// Set by [`Processor`](crate::processor::Processor).
+    pub module: Option<String>,
+    pub since: Option<String>,
+    /// The deprecation message from `@deprecated`, which is empty if none was given.
+    pub deprecated: Option<String>,
+    /// The type and text of each `@badge`, like `("warning", "experimental")`.
+    pub badges: Vec<(String, String)>,
+    /// Where the class is declared with `@class`. Set by the processor.
+    pub location: Option<Location>,
+    /// The position of the block declaring this among every processed block, for
+    /// keeping source order. Set by [`Processor`](crate::processor::Processor).
+    pub order: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -487,6 +744,11 @@ pub struct LspField {
     pub ty: Type,
     pub description: Option<String>,
     pub scope: Option<Scope>,
+    pub default: Option<String>,
+    pub since: Option<String>,
+    pub deprecated: Option<String>,
+    /// Where the field is declared with `@field`. Set by the processor.
+    pub location: Option<Location>,
 }
 
 #[derive(Debug, Clone)]
@@ -495,6 +757,9 @@ pub struct TsField {
     pub ty: Option<Type>,
     pub description: Option<String>,
     pub value: String,
+    /// The fields of a table constructor value, like the `quit` in `keybinds = { quit = "q" }`.
+    pub fields: Vec<TsField>,
+    pub location: Location,
 }
 
 #[derive(Debug, Clone)]
@@ -504,9 +769,79 @@ pub struct ClassField {
     pub description: Option<String>,
     pub scope: Option<Scope>,
     pub value: Option<String>,
+    pub default: Option<String>,
+    pub since: Option<String>,
+    pub deprecated: Option<String>,
+    /// The fields of the table the field is assigned, if any.
+    pub fields: Vec<TsField>,
+    /// Where the field is assigned, or where it's declared with `@field` if it isn't.
+    pub location: Option<Location>,
 }
 
 impl Class {
+    /// A class with no fields, parent, or other annotations.
+    pub fn new(name: String, description: Option<String>) -> Self {
+        Self {
+            name,
+            description,
+            exact: false,
+            parent: None,
+            lsp_fields: Vec::new(),
+            ts_fields: Vec::new(),
+            operators: Vec::new(),
+            is_module: false,
+            module: None,
+            since: None,
+            deprecated: None,
+            badges: Vec::new(),
+            location: None,
+            order: 0,
+        }
+    }
+
+    /// Merge another definition of this class into it.
+    ///
+    /// Fields, operators, and badges are appended, while the first description, parent,
+    /// `@since`, and deprecation win.
+    pub fn merge(&mut self, other: Class) {
+        let Class {
+            name: _,
+            description,
+            exact,
+            parent,
+            lsp_fields,
+            ts_fields,
+            operators,
+            is_module,
+            module,
+            since,
+            deprecated,
+            badges,
+            location,
+            order: _,
+        } = other;
+
+        if self.description.as_deref().unwrap_or_default().is_empty() && description.is_some() {
+            self.description = description;
+        }
+
+        self.exact |= exact;
+        self.parent = self.parent.take().or(parent);
+        self.lsp_fields.extend(lsp_fields);
+        self.ts_fields.extend(ts_fields);
+        self.operators.extend(operators);
+        self.is_module |= is_module;
+        self.module = self.module.take().or(module);
+        self.since = self.since.take().or(since);
+        self.deprecated = self.deprecated.take().or(deprecated);
+        for badge in badges {
+            if !self.badges.contains(&badge) {
+                self.badges.push(badge);
+            }
+        }
+        self.location = self.location.take().or(location);
+    }
+
     pub fn fields(&self) -> Vec<ClassField> {
         let mut fields = Vec::new();
 
@@ -517,6 +852,11 @@ impl Class {
                 description: lsp_field.description.clone(),
                 scope: lsp_field.scope,
                 value: None,
+                default: lsp_field.default.clone(),
+                since: lsp_field.since.clone(),
+                deprecated: lsp_field.deprecated.clone(),
+                fields: Vec::new(),
+                location: lsp_field.location.clone(),
             };
 
             fields.push(class_field);
@@ -538,6 +878,8 @@ impl Class {
                 }
 
                 class_field.value = Some(ts_field.value.clone());
+                class_field.fields = ts_field.fields.clone();
+                class_field.location = Some(ts_field.location.clone());
             } else {
                 let Some(FieldName::Ident(ident)) = ts_field.name.as_ref() else {
                     continue;
@@ -549,6 +891,11 @@ impl Class {
                     description: ts_field.description.clone(),
                     scope: None,
                     value: Some(ts_field.value.clone()),
+                    default: None,
+                    since: None,
+                    deprecated: None,
+                    fields: ts_field.fields.clone(),
+                    location: Some(ts_field.location.clone()),
                 };
 
                 fields.push(class_field);
@@ -559,7 +906,8 @@ impl Class {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Scope {
     Public,
     Private,
@@ -572,6 +920,7 @@ pub struct Param {
     pub name: String,
     pub ty: Type,
     pub description: Option<String>,
+    pub default: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -587,9 +936,49 @@ pub struct Function {
     pub table: Option<String>,
     pub params: Vec<Param>,
     pub returns: Vec<Return>,
+    /// The errors the function may raise and when, from `@error` or `@throws`.
+    pub errors: Vec<(Type, Option<String>)>,
+    /// Type parameters from `@generic` and the types they're constrained to.
+    pub generics: Vec<(String, Option<Type>)>,
+    /// Alternative signatures from `@overload`, as function types.
+    pub overloads: Vec<Type>,
     pub sees: Vec<See>,
     pub is_method: bool,
+    /// Whether the function is annotated with `@async`, meaning it yields and must be
+    /// called from a coroutine.
+    pub is_async: bool,
+    /// Whether the function is declared `local`. Locals are left out of the documentation
+    /// unless `--include-locals` is passed.
+    pub is_local: bool,
+    /// The visibility from `@public`, `@private`, `@protected`, or `@package`, if any.
+    pub scope: Option<Scope>,
     pub description: Option<String>,
+    /// The Lua source of the function.
+    pub source: String,
+    pub since: Option<String>,
+    /// The deprecation message from `@deprecated`, which is empty if none was given.
+    pub deprecated: Option<String>,
+    /// The type and text of each `@badge`, like `("warning", "experimental")`.
+    pub badges: Vec<(String, String)>,
+    /// The parameters in the function's Lua definition.
+    pub declared_params: Vec<FunctionParam>,
+    pub location: Location,
+    /// The position of the block declaring this among every processed block, for
+    /// keeping source order. Set by [`Processor`](crate::processor::Processor).
+    pub order: usize,
+}
+
+impl Function {
+    /// The function's name qualified with its table, like `Table.name` or `Table:name`.
+    pub fn qualified_name(&self) -> String {
+        match self.table.as_ref() {
+            Some(table) => {
+                let connector = if self.is_method { ":" } else { "." };
+                format!("{table}{connector}{}", self.name)
+            }
+            None => self.name.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -598,6 +987,24 @@ pub struct Enum {
     pub description: Option<String>,
     pub is_key: bool,
     pub fields: Vec<TsField>,
+    pub since: Option<String>,
+    /// The position of the block declaring this among every processed block, for
+    /// keeping source order. Set by [`Processor`](crate::processor::Processor).
+    pub order: usize,
+}
+
+/// Where a symbol is defined in the Lua source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Location {
+    pub file: PathBuf,
+    /// The 1-based line number.
+    pub line: usize,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file.display(), self.line)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -610,12 +1017,59 @@ pub enum LcatOption {
     Nodoc,
 }
 
+/// A metamethod overload, like `---@operator add(Vector): Vector`.
+#[derive(Debug, Clone)]
+pub struct Operator {
+    /// The metamethod name without the leading `__`, like `add`.
+    pub op: String,
+    /// The type of the other operand, or `None` for unary forms.
+    pub input: Option<Type>,
+    pub result: Type,
+    pub description: Option<String>,
+}
+
+impl Operator {
+    /// The Lua syntax of the operator, like `+` for `add`.
+    ///
+    /// Returns `None` for `call`, `index`, and metamethods that aren't operators.
+    pub fn symbol(&self) -> Option<&'static str> {
+        Some(match self.op.as_str() {
+            "add" => "+",
+            "sub" | "unm" => "-",
+            "mul" => "*",
+            "div" => "/",
+            "mod" => "%",
+            "pow" => "^",
+            "idiv" => "//",
+            "band" => "&",
+            "bor" => "|",
+            "bxor" | "bnot" => "~",
+            "shl" => "<<",
+            "shr" => ">>",
+            "concat" => "..",
+            "len" => "#",
+            "eq" => "==",
+            "lt" => "<",
+            "le" => "<=",
+            _ => return None,
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct See {
+    /// The referenced symbol, or a URL like `https://example.com`.
     pub ident: String,
     pub description: Option<String>,
 }
 
+impl See {
+    /// Whether this refers to an external URL instead of a symbol.
+    pub fn is_url(&self) -> bool {
+        self.ident.contains("://")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use pest::Parser;
@@ -628,9 +1082,20 @@ mod tests {
         Ok(())
     }
 
+    /// Parse a whole string as a single type.
+    fn parse_ty(input: &str) -> anyhow::Result<Type> {
+        let mut types = parse_type_annotation(input)?;
+        anyhow::ensure!(types.len() == 1, "expected a single type");
+        Ok(types.remove(0))
+    }
+
     mod types {
+        use std::collections::HashMap;
+
         use super::*;
 
+        use crate::types::{LinkOptions, Metatype};
+
         #[test]
         fn single_type_parses() {
             parse(Rule::ty, "string").unwrap();
@@ -737,6 +1202,116 @@ mod tests {
 
             Ok(())
         }
+
+        #[test]
+        fn varargs_in_function_types_parse() -> anyhow::Result<()> {
+            let ty = parse_ty("fun(...)")?;
+            assert_eq!(
+                ty,
+                Type::function(vec![("...".to_string(), Type::ANY)], Vec::new())
+            );
+            assert_eq!(ty.to_string(), "fun(...: any)");
+
+            let ty = parse_ty("fun(...: string): boolean")?;
+            assert_eq!(
+                ty,
+                Type::function(
+                    vec![("...".to_string(), Type::STRING)],
+                    vec![(None, Type::BOOLEAN)]
+                )
+            );
+            assert_eq!(ty.to_string(), "fun(...: string): boolean");
+
+            let ty = parse_ty("fun(a, ...: integer)")?;
+            assert_eq!(
+                ty,
+                Type::function(
+                    vec![
+                        ("a".to_string(), Type::ANY),
+                        ("...".to_string(), Type::INTEGER)
+                    ],
+                    Vec::new()
+                )
+            );
+            assert_eq!(
+                ty.format_with_links(&HashMap::new(), "/", LinkOptions::default()),
+                "fun(a: any, ...: integer)"
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn unions_of_functions_and_tables_round_trip() -> anyhow::Result<()> {
+            let cleanup = Type::table(vec![(
+                Type::string_literal("cleanup"),
+                Type::function(Vec::new(), vec![(None, Type::NIL)]),
+            )]);
+
+            // Without parentheses, the union is the function's return type
+            let ty = parse_ty("fun(): nil | { cleanup: fun(): nil }")?;
+            assert_eq!(
+                ty,
+                Type::function(
+                    Vec::new(),
+                    vec![(None, Type::union([Type::NIL, cleanup.clone()]))]
+                )
+            );
+            assert_eq!(ty.to_string(), "fun(): nil | { cleanup: fun(): nil }");
+
+            let ty = parse_ty("(fun(): nil) | { cleanup: fun(): nil }")?;
+            assert_eq!(
+                ty,
+                Type::union([Type::function(Vec::new(), vec![(None, Type::NIL)]), cleanup])
+            );
+            assert_eq!(ty.to_string(), "(fun(): nil) | { cleanup: fun(): nil }");
+            assert_eq!(parse_ty(&ty.to_string())?, ty);
+
+            let ty = parse_ty("(fun(): nil)[]")?;
+            assert_eq!(ty.to_string(), "(fun(): nil)[]");
+
+            Ok(())
+        }
+
+        #[test]
+        fn intersections_bind_tighter_than_unions() -> anyhow::Result<()> {
+            let a = Type::user_defined("A");
+            let b = Type::user_defined("B");
+            let c = Type::user_defined("C");
+
+            let ty = parse_ty("A & B | C")?;
+            assert_eq!(
+                ty,
+                Type::union([Type::intersection([a.clone(), b.clone()]), c.clone()])
+            );
+            assert_eq!(ty.to_string(), "A & B | C");
+
+            let ty = parse_ty("A | B & C")?;
+            assert_eq!(
+                ty,
+                Type::union([a.clone(), Type::intersection([b.clone(), c.clone()])])
+            );
+            assert_eq!(ty.to_string(), "A | B & C");
+
+            let ty = parse_ty("A & (B | C)")?;
+            assert_eq!(
+                ty,
+                Type::intersection([a.clone(), Type::union([b.clone(), c.clone()])])
+            );
+            assert_eq!(ty.to_string(), "A & (B | C)");
+
+            let ty = parse_ty("(A & B)[]?")?;
+            assert_eq!(ty.to_string(), "(A & B)[]");
+            assert_eq!(ty.format_nullable(ty.to_string()), "(A & B)[]?");
+
+            let ident_lookup = HashMap::from([("A".to_string(), Metatype::Class)]);
+            assert_eq!(
+                parse_ty("A & B")?.format_with_links(&ident_lookup, "/", LinkOptions::default()),
+                r#"<a href="/classes/A">A</a> & B"#
+            );
+
+            Ok(())
+        }
     }
 
     mod annotations {
@@ -749,6 +1324,260 @@ mod tests {
                 Rule::alias,
                 r#"Rectangle "square" | "mongus" The description"#,
             )?;
+            parse(Rule::alias, "Result<T> T | string")?;
+            parse(Rule::alias, "Pair<K, V>")?;
+
+            Ok(())
+        }
+
+        #[test]
+        fn class_parents_can_be_generic() -> anyhow::Result<()> {
+            let class = parse_class("Registry : table<string, Handler>", None)?;
+
+            let mut parent = Type::TABLE;
+            parent.add_generic(Type::STRING);
+            parent.add_generic(Type::user_defined("Handler"));
+
+            assert_eq!(class.name, "Registry");
+            assert_eq!(class.parent, Some(parent));
+
+            Ok(())
+        }
+
+        #[test]
+        fn params_can_be_varargs() -> anyhow::Result<()> {
+            let param = parse_param("... integer the numbers")?;
+            assert_eq!(param.name, "...");
+            assert_eq!(param.ty, Type::INTEGER);
+            assert_eq!(param.description.as_deref(), Some("the numbers"));
+
+            Ok(())
+        }
+
+        #[test]
+        fn returns_can_be_varargs() -> anyhow::Result<()> {
+            let ret = parse_return("string ... the captures")?;
+            assert_eq!(ret.name.as_deref(), Some("..."));
+            assert_eq!(ret.ty, Type::STRING);
+            assert_eq!(ret.description.as_deref(), Some("the captures"));
+
+            let ret = parse_return("...")?;
+            assert_eq!(ret.name.as_deref(), Some("..."));
+            assert_eq!(ret.ty, Type::ANY);
+
+            let ret = parse_return("... the values")?;
+            assert_eq!(ret.name.as_deref(), Some("..."));
+            assert_eq!(ret.ty, Type::ANY);
+            assert_eq!(ret.description.as_deref(), Some("the values"));
+
+            Ok(())
+        }
+
+        #[test]
+        fn params_can_have_inline_defaults() -> anyhow::Result<()> {
+            let param = parse_param("width integer [default=800] The width")?;
+            assert_eq!(param.default.as_deref(), Some("800"));
+            assert_eq!(param.description.as_deref(), Some("The width"));
+
+            let param = parse_param(r#"mode? string The mode [default="auto"]"#)?;
+            assert_eq!(param.default.as_deref(), Some(r#""auto""#));
+            assert_eq!(param.description.as_deref(), Some("The mode"));
+
+            let param = parse_param("height integer [default=]")?;
+            assert_eq!(param.default, None);
+            assert_eq!(param.description.as_deref(), Some("[default=]"));
+
+            let param = parse_param("depth integer [default=3")?;
+            assert_eq!(param.default, None);
+            assert_eq!(param.description.as_deref(), Some("[default=3"));
+
+            Ok(())
+        }
+
+        #[test]
+        fn see_parses_urls() -> anyhow::Result<()> {
+            let see = parse_see("https://example.com/a?b=c The docs")?;
+            assert_eq!(see.ident, "https://example.com/a?b=c");
+            assert_eq!(see.description.as_deref(), Some("The docs"));
+            assert!(see.is_url());
+
+            let see = parse_see("Window.new The constructor")?;
+            assert_eq!(see.ident, "Window.new");
+            assert!(!see.is_url());
+
+            let see = parse_see("Window:close")?;
+            assert_eq!(see.ident, "Window:close");
+            assert_eq!(see.description, None);
+
+            Ok(())
+        }
+
+        #[test]
+        fn generic_parses_constraints() -> anyhow::Result<()> {
+            let generics = parse_generic("K, V: table")?;
+            assert_eq!(
+                generics,
+                vec![
+                    ("K".to_string(), None),
+                    ("V".to_string(), Some(Type::TABLE))
+                ]
+            );
+
+            let generics = parse_generic("T : Window")?;
+            assert_eq!(
+                generics,
+                vec![("T".to_string(), Some(Type::user_defined("Window")))]
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn overload_parses_function_types() -> anyhow::Result<()> {
+            let overload = parse_overload("fun(x: integer): string")?;
+            assert_eq!(
+                overload,
+                Type::function(
+                    vec![("x".to_string(), Type::INTEGER)],
+                    vec![(None, Type::STRING)]
+                )
+            );
+
+            assert!(parse_overload("integer").is_err());
+
+            Ok(())
+        }
+
+        #[test]
+        fn returns_are_named_unless_the_description_starts_right_away() -> anyhow::Result<()> {
+            let ret = parse_return("boolean ok whether it worked")?;
+            assert_eq!(ret.ty, Type::BOOLEAN);
+            assert_eq!(ret.name.as_deref(), Some("ok"));
+            assert_eq!(ret.description.as_deref(), Some("whether it worked"));
+
+            let ret = parse_return("boolean the result")?;
+            assert_eq!(ret.name, None);
+            assert_eq!(ret.description.as_deref(), Some("the result"));
+
+            let ret = parse_return("string Whether it worked")?;
+            assert_eq!(ret.name, None);
+            assert_eq!(ret.description.as_deref(), Some("Whether it worked"));
+
+            let ret = parse_return("string name")?;
+            assert_eq!(ret.name.as_deref(), Some("name"));
+            assert_eq!(ret.description, None);
+
+            Ok(())
+        }
+
+        #[test]
+        fn returns_named_before_a_hash_are_always_named() -> anyhow::Result<()> {
+            let ret = parse_return("boolean Whether # whether it worked")?;
+            assert_eq!(ret.name.as_deref(), Some("Whether"));
+            assert_eq!(ret.description.as_deref(), Some("whether it worked"));
+
+            let ret = parse_return("boolean the # the result")?;
+            assert_eq!(ret.name.as_deref(), Some("the"));
+            assert_eq!(ret.description.as_deref(), Some("the result"));
+
+            let ret = parse_return("boolean # The result")?;
+            assert_eq!(ret.name, None);
+            assert_eq!(ret.description.as_deref(), Some("The result"));
+
+            Ok(())
+        }
+
+        #[test]
+        fn type_annotations_can_have_multiple_types() -> anyhow::Result<()> {
+            parse(Rule::type_annotation, "string, integer")?;
+            parse(
+                Rule::type_annotation,
+                "string, fun(a: integer, b: string): nil the callback",
+            )?;
+
+            assert_eq!(
+                parse_type_annotation("string, integer? the values")?,
+                vec![Type::STRING, {
+                    let mut ty = Type::INTEGER;
+                    ty.make_nullable();
+                    ty
+                }]
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn trailing_whitespace_is_not_a_description() -> anyhow::Result<()> {
+            let param = parse_param("x integer   ")?;
+            assert_eq!(param.ty, Type::INTEGER);
+            assert_eq!(param.description, None);
+
+            let ret = parse_return("integer count   ")?;
+            assert_eq!(ret.name.as_deref(), Some("count"));
+            assert_eq!(ret.description, None);
+
+            let field = parse_field("x integer \t ", None)?;
+            assert_eq!(field.description, None);
+
+            Ok(())
+        }
+
+        #[test]
+        fn trailing_comments_become_descriptions() -> anyhow::Result<()> {
+            let param = parse_param("x integer -- legacy  ")?;
+            assert_eq!(param.ty, Type::INTEGER);
+            assert_eq!(param.description.as_deref(), Some("legacy"));
+
+            let param = parse_param("x integer --")?;
+            assert_eq!(param.description, None);
+
+            let ret = parse_return("integer count -- the count")?;
+            assert_eq!(ret.name.as_deref(), Some("count"));
+            assert_eq!(ret.description.as_deref(), Some("the count"));
+
+            let ret = parse_return("integer count # the count ")?;
+            assert_eq!(ret.description.as_deref(), Some("the count"));
+
+            let field = parse_field("x fun(): integer -- the callback", None)?;
+            assert_eq!(field.description.as_deref(), Some("the callback"));
+
+            Ok(())
+        }
+    }
+
+    mod indexer_fields {
+        use super::*;
+
+        #[test]
+        fn fields_can_be_indexed_by_a_type() -> anyhow::Result<()> {
+            let field = parse_field("[string] number The counts", None)?;
+            assert_eq!(field.ident_type, Type::STRING);
+            assert_eq!(field.ty, Type::NUMBER);
+            assert_eq!(field.description.as_deref(), Some("The counts"));
+            assert_eq!(field.ident_type.format_as_table_field_name(), "[string]");
+
+            let field = parse_field("[integer] string", None)?;
+            assert_eq!(field.ident_type, Type::INTEGER);
+            assert_eq!(field.ident_type.format_as_table_field_name(), "[integer]");
+
+            let field = parse_field(r#"["a" | "b"] boolean"#, None)?;
+            assert_eq!(
+                field.ident_type.format_as_table_field_name(),
+                r#"["a" | "b"]"#
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn class_keys_are_bracketed() -> anyhow::Result<()> {
+            let field = parse_field("private [Window] boolean", None)?;
+            assert!(matches!(field.scope, Some(Scope::Private)));
+            assert_eq!(field.ident_type.format_as_table_field_name(), "[Window]");
+
+            let field = parse_field("title string", None)?;
+            assert_eq!(field.ident_type.format_as_table_field_name(), "title");
 
             Ok(())
         }
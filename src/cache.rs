@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use sha2::{Digest, Sha256};
+
+use crate::treesitter::ParsedSource;
+
+/// The directory parsed blocks are cached in by default.
+pub const DEFAULT_CACHE_DIR: &str = ".lcat-cache";
+
+/// A cache of the parsed blocks of Lua sources, keyed by a hash of the source
+/// and the lcat version that parsed it.
+///
+/// Changing a file or upgrading lcat changes the key, so stale entries are never read.
+pub struct BlockCache {
+    dir: PathBuf,
+}
+
+impl BlockCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Get the cached parse of a source, if it exists.
+    pub fn get(&self, contents: &str) -> Option<ParsedSource> {
+        let cached = std::fs::read_to_string(self.entry_path(contents)).ok()?;
+        serde_json::from_str(&cached).ok()
+    }
+
+    /// Cache the parse of a source.
+    ///
+    /// Failing to write the cache only warns, as parsing can still continue without it.
+    pub fn insert(&self, contents: &str, parsed: &ParsedSource) {
+        let result = std::fs::create_dir_all(&self.dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|()| Ok(serde_json::to_string(parsed)?))
+            .and_then(|json| Ok(std::fs::write(self.entry_path(contents), json)?));
+
+        if let Err(err) = result {
+            log::warn!("failed to write to cache `{}`: {err}", self.dir.display());
+        }
+    }
+
+    fn entry_path(&self, contents: &str) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION"));
+        hasher.update([0]);
+        hasher.update(contents);
+
+        let hash = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+
+        self.dir.join(format!("{hash}.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::state::parse_files;
+
+    mod reuse {
+        use super::*;
+
+        #[test]
+        fn unchanged_files_are_read_from_the_cache() {
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("window.lua");
+            std::fs::write(&file, "---@class Window\nlocal Window = {}\n").unwrap();
+
+            let cache = BlockCache::new(dir.path().join(DEFAULT_CACHE_DIR));
+
+            let (processor, _) = parse_files(vec![file.clone()], Some(&cache)).unwrap();
+            assert_eq!(processor.classes[0].name, "Window");
+
+            // Tamper with the cached blocks so a cache hit is observable
+            let entries = std::fs::read_dir(dir.path().join(DEFAULT_CACHE_DIR))
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .collect::<Vec<_>>();
+            assert_eq!(entries.len(), 1);
+            let cached = std::fs::read_to_string(&entries[0]).unwrap();
+            std::fs::write(&entries[0], cached.replace("Window", "Cached")).unwrap();
+
+            let (processor, _) = parse_files(vec![file.clone()], Some(&cache)).unwrap();
+            assert_eq!(processor.classes[0].name, "Cached");
+
+            std::fs::write(&file, "---@class Changed\nlocal Changed = {}\n").unwrap();
+
+            let (processor, _) = parse_files(vec![file], Some(&cache)).unwrap();
+            assert_eq!(processor.classes[0].name, "Changed");
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use lcat::render::Format;
+
+/// The config file lcat reads from the current directory if no `--config` is given.
+pub const DEFAULT_CONFIG_FILE: &str = "lcat.toml";
+
+/// Defaults for command line options, read from an `lcat.toml`.
+///
+/// Options passed on the command line override these.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub dir: Option<PathBuf>,
+    #[serde(default)]
+    pub files: Vec<PathBuf>,
+    /// Globs relative to `dir`, which aren't affected by where the config is.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    pub out_dir: Option<PathBuf>,
+    pub base_url: Option<String>,
+    pub source_url_template: Option<String>,
+    pub format: Option<Format>,
+    pub classes_dir: Option<String>,
+    pub aliases_dir: Option<String>,
+    pub enums_dir: Option<String>,
+}
+
+impl Config {
+    /// Load the config at `path`, or `lcat.toml` in the current directory if it exists.
+    ///
+    /// Relative paths in the config are relative to the config file.
+    pub fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        let path = match path {
+            Some(path) => path,
+            None if Path::new(DEFAULT_CONFIG_FILE).is_file() => Path::new(DEFAULT_CONFIG_FILE),
+            None => return Ok(Self::default()),
+        };
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config `{}`", path.display()))?;
+        let config = Self::parse(&contents)
+            .with_context(|| format!("invalid config `{}`", path.display()))?;
+
+        Ok(config.relative_to(path.parent().unwrap_or(Path::new(""))))
+    }
+
+    fn parse(contents: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    fn relative_to(mut self, dir: &Path) -> Self {
+        self.dir = self.dir.map(|path| dir.join(path));
+        self.files = self.files.into_iter().map(|path| dir.join(path)).collect();
+        self.out_dir = self.out_dir.map(|path| dir.join(path));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod config {
+        use super::*;
+
+        #[test]
+        fn fields_mirror_the_cli() {
+            let config = Config::parse(
+                r#"
+dir = "lua"
+files = ["extra.lua"]
+exclude = ["tests/**"]
+out_dir = "docs"
+base_url = "/repo/"
+source_url_template = "https://example.com/{file}#L{line}"
+format = "vimdoc"
+classes_dir = "api/classes"
+aliases_dir = "api/aliases"
+enums_dir = "api/enums"
+"#,
+            )
+            .unwrap();
+
+            assert_eq!(config.dir, Some(PathBuf::from("lua")));
+            assert_eq!(config.files, [PathBuf::from("extra.lua")]);
+            assert_eq!(config.exclude, ["tests/**"]);
+            assert_eq!(config.out_dir, Some(PathBuf::from("docs")));
+            assert_eq!(config.base_url.as_deref(), Some("/repo/"));
+            assert_eq!(
+                config.source_url_template.as_deref(),
+                Some("https://example.com/{file}#L{line}")
+            );
+            assert_eq!(config.format, Some(Format::Vimdoc));
+            assert_eq!(config.classes_dir.as_deref(), Some("api/classes"));
+            assert_eq!(config.aliases_dir.as_deref(), Some("api/aliases"));
+            assert_eq!(config.enums_dir.as_deref(), Some("api/enums"));
+        }
+
+        #[test]
+        fn unknown_fields_are_rejected() {
+            assert!(Config::parse("out-dir = \"docs\"\n").is_err());
+        }
+
+        #[test]
+        fn paths_are_relative_to_the_config() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join(DEFAULT_CONFIG_FILE);
+            std::fs::write(&path, "dir = \"lua\"\nfiles = [\"extra.lua\"]\n").unwrap();
+
+            let config = Config::load(Some(&path)).unwrap();
+
+            assert_eq!(config.dir, Some(dir.path().join("lua")));
+            assert_eq!(config.files, [dir.path().join("extra.lua")]);
+            assert_eq!(config.out_dir, None);
+        }
+
+        #[test]
+        fn missing_explicit_configs_are_an_error() {
+            assert!(Config::load(Some(Path::new("does/not/exist.toml"))).is_err());
+        }
+    }
+}
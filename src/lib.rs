@@ -0,0 +1,17 @@
+//! Parse LuaLS annotations from Lua sources with [`state::parse_files`], modify the
+//! result with [`transform::Transform`]s, and render it with a [`render::Renderer`].
+
+// `flag.then_some(..).unwrap_or_default()` is used throughout for optional suffixes and badges
+#![allow(clippy::obfuscated_if_else)]
+
+pub mod annotation;
+pub mod cache;
+mod node_types;
+pub mod processor;
+pub mod render;
+pub mod report;
+pub mod schema;
+pub mod state;
+pub mod transform;
+pub mod treesitter;
+pub mod types;
@@ -1,62 +1,347 @@
-use std::path::PathBuf;
+use std::{io::Write, path::PathBuf, time::Instant};
 
-use clap::{Parser, ValueHint};
-use render::{vitepress::VitePressRenderer, Renderer};
-use state::parse_files;
+use clap::{parser::ValueSource, ArgAction, ArgMatches, CommandFactory, FromArgMatches, ValueHint};
+use config::Config;
+use lcat::{
+    cache::{BlockCache, DEFAULT_CACHE_DIR},
+    processor::ProcessErrorKind,
+    render::{
+        html::HtmlRenderer,
+        json::JsonRenderer,
+        markdown::MarkdownRenderer,
+        vimdoc::VimdocRenderer,
+        vitepress::{AliasLayout, MethodStyle, VitePressRenderer, DEFAULT_MAX_VALUE_LEN},
+        Format, OutputEncoding, Renderer,
+    },
+    report::{self, Report, ReportFormat},
+    schema,
+    state::{exclude_globs, find_lua_files, parse_files, Input},
+    transform::{PublicOnly, StripLocals, StripPrivate, Transforms, Visibility},
+    types::SectionDirs,
+};
 
-mod annotation;
-mod node_types;
-mod processor;
-mod render;
-mod state;
-mod treesitter;
-mod types;
+mod config;
 
 fn main() {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+
+    init_logger(cli.quiet, cli.verbose);
+
+    match Config::load(cli.config.as_deref()) {
+        Ok(config) => cli.apply_config(config, &matches),
+        Err(err) => {
+            log::error!("{err:#}");
+            std::process::exit(1);
+        }
+    }
 
     let mut files = Vec::new();
 
     if let Some(dir) = cli.dir {
-        let walkdir = walkdir::WalkDir::new(dir);
-
-        for dir in walkdir {
-            let dir = match dir {
-                Ok(dir) => dir,
-                Err(err) => {
-                    eprintln!("{err}");
-                    continue;
-                }
-            };
+        let exclude = match exclude_globs(&cli.exclude) {
+            Ok(exclude) => exclude,
+            Err(err) => {
+                log::error!("{err:#}");
+                std::process::exit(1);
+            }
+        };
+
+        files.extend(find_lua_files(&dir, &exclude, &cli.ext));
+    }
+
+    files.extend(cli.files);
+
+    let inputs = files
+        .into_iter()
+        .map(|path| Input::from_arg(path, &cli.stdin_name))
+        .collect::<Vec<_>>();
+
+    let cache = cli
+        .cache
+        .then(|| BlockCache::new(PathBuf::from(DEFAULT_CACHE_DIR)));
+
+    if let Some(jobs) = cli.jobs {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build_global()
+            .unwrap();
+    }
+
+    let (mut processor, file_stats) = parse_files(inputs, cache.as_ref()).unwrap();
+
+    for error in file_stats.iter().flat_map(|file| file.errors.iter()) {
+        let level = match error.kind {
+            ProcessErrorKind::InvalidAnnotation => log::Level::Error,
+            ProcessErrorKind::UnknownAnnotation | ProcessErrorKind::SyntaxError => log::Level::Warn,
+        };
+
+        // miette draws its own severity, so the diagnostic is printed as is
+        if log::log_enabled!(level) {
+            eprintln!("{:?}", miette::Report::new(error.clone()));
+        }
+    }
+
+    if cli.deny_unknown_annotations
+        && file_stats
+            .iter()
+            .flat_map(|file| file.errors.iter())
+            .any(|error| error.kind == ProcessErrorKind::UnknownAnnotation)
+    {
+        log::error!("unknown annotations are denied by `--deny-unknown-annotations`");
+        std::process::exit(1);
+    }
+
+    let mut transforms = Transforms::new();
+
+    if !cli.include_locals {
+        transforms.register(StripLocals);
+    }
+
+    if cli.strip_private {
+        transforms.register(StripPrivate);
+    }
+
+    if cli.visibility == Visibility::Public {
+        transforms.register(PublicOnly);
+    }
+
+    transforms.run(&mut processor);
+
+    // Pruned after transforms so types only used by stripped symbols are pruned too
+    if cli.prune_unused {
+        processor.prune_unused();
+    }
+
+    if cli.strict_types {
+        let errors = processor.check_strict_types();
+
+        for error in errors.iter() {
+            log::error!("{error}");
+        }
+
+        if !errors.is_empty() {
+            std::process::exit(1);
+        }
+    }
+
+    if cli.strict {
+        let mut errors = processor.check_documented();
+        errors.extend(
+            file_stats
+                .iter()
+                .flat_map(|file| file.errors.iter())
+                .filter(|error| error.kind == ProcessErrorKind::SyntaxError)
+                .map(ToString::to_string),
+        );
 
-            if dir.path().extension().is_some_and(|ext| ext == "lua") {
-                files.push(dir.into_path());
+        for error in errors.iter() {
+            log::error!("{error}");
+        }
+
+        if !errors.is_empty() {
+            std::process::exit(1);
+        }
+    }
+
+    let coverage = report::coverage_by_kind(&processor, &file_stats);
+
+    if cli.coverage_json {
+        print!("{}", coverage.to_json());
+    } else if cli.coverage {
+        println!("{coverage}");
+    }
+
+    if let Some(min_coverage) = cli.min_coverage {
+        if coverage.total.percent < min_coverage {
+            log::error!(
+                "documentation coverage is {:.1}%, below the minimum of {min_coverage}%",
+                coverage.total.percent
+            );
+
+            for symbol in coverage.undocumented.iter() {
+                eprintln!("  undocumented: {symbol}");
             }
+
+            std::process::exit(1);
         }
     }
 
-    files.extend(cli.files);
+    let out_dir = cli.out_dir.unwrap_or("./lcat_out".into());
+
+    // Files written besides the renderer's, which `--dry-run` lists instead
+    let mut extra_files = Vec::new();
+
+    if let Some(class) = cli.json_schema.as_deref() {
+        let Some(schema) = schema::json_schema(&processor, class) else {
+            log::error!("`--json-schema` class `{class}` doesn't exist");
+            std::process::exit(1);
+        };
+
+        let path = out_dir.join(format!("{class}.schema.json"));
+        let json = serde_json::to_string_pretty(&schema).unwrap() + "\n";
+
+        // `--diff` only diffs the renderer's pages and writes nothing
+        if cli.dry_run {
+            extra_files.push(path);
+        } else if !cli.diff {
+            std::fs::create_dir_all(&out_dir).unwrap();
+            std::fs::write(path, json).unwrap();
+        }
+    }
+
+    let summary = report::summary(&processor, &file_stats);
+    let mut report = cli.report.map(|_| Report::new(&processor, &file_stats));
+    let report_path = out_dir.join("report.json");
+
+    if report.is_some() {
+        extra_files.push(report_path.clone());
+    }
+    let render_start = Instant::now();
+
+    let renderer: Box<dyn Renderer> = match cli.format {
+        Format::Vitepress => {
+            let defaults = SectionDirs::default();
+            let section_dirs = SectionDirs {
+                classes: cli.classes_dir.unwrap_or(defaults.classes),
+                aliases: cli.aliases_dir.unwrap_or(defaults.aliases),
+                enums: cli.enums_dir.unwrap_or(defaults.enums),
+            };
+
+            Box::new(
+                VitePressRenderer::new(out_dir, cli.base_url)
+                    .max_value_len(cli.max_value_len)
+                    .emit_sources(cli.emit_sources)
+                    .alias_layout(cli.alias_layout)
+                    .version_matrix(cli.version_matrix)
+                    .symbols_manifest(cli.symbols_manifest)
+                    .search_index(cli.search_index)
+                    .function_pages(cli.function_pages)
+                    .no_empty_sections(cli.no_empty_sections)
+                    .inline_descriptions(cli.inline_descriptions)
+                    .group_optional_params(cli.group_optional_params)
+                    .verbose_types(cli.verbose_types)
+                    .link_literals(cli.link_literals)
+                    .method_style(cli.method_style)
+                    .show_self(cli.show_self)
+                    .section_dirs(section_dirs)
+                    .source_url_template(cli.source_url_template)
+                    .output_encoding(cli.output_encoding),
+            )
+        }
+        Format::Vimdoc => Box::new(
+            VimdocRenderer::new(out_dir, cli.vimdoc_name).output_encoding(cli.output_encoding),
+        ),
+        Format::Markdown => Box::new(
+            MarkdownRenderer::new(out_dir)
+                .search_index(cli.search_index)
+                .output_encoding(cli.output_encoding),
+        ),
+        Format::Html => Box::new(HtmlRenderer::new(out_dir).output_encoding(cli.output_encoding)),
+        Format::Json => Box::new(JsonRenderer::new(out_dir).output_encoding(cli.output_encoding)),
+    };
+
+    if cli.dry_run {
+        exit_with_dry_run(renderer.dry_run(processor), extra_files, &summary);
+    }
+
+    if cli.diff {
+        exit_with_diff(renderer.diff(processor));
+    }
 
-    let processor = parse_files(files).unwrap();
+    let written = renderer.render(processor);
 
-    VitePressRenderer::new(cli.out_dir.unwrap_or("./lcat_out".into()), cli.base_url)
-        .render(processor);
+    if let Some(report) = report.as_mut() {
+        report.set_render_time(render_start.elapsed());
+        std::fs::write(report_path, report.to_json()).unwrap();
+    }
+
+    log::info!("{summary}; {}", report::written(written));
+}
+
+/// Log warnings and the final summary by default, only errors with `quiet`, and more
+/// detail with each `verbose`. `RUST_LOG` overrides the levels.
+fn init_logger(quiet: bool, verbose: u8) {
+    let level = match (quiet, verbose) {
+        (true, _) => log::LevelFilter::Error,
+        (false, 0) => log::LevelFilter::Info,
+        (false, 1) => log::LevelFilter::Debug,
+        (false, _) => log::LevelFilter::Trace,
+    };
+
+    // Dependencies can be noisy at debug, so only lcat's own logs follow `verbose`
+    env_logger::Builder::new()
+        .filter_level(level.min(log::LevelFilter::Warn))
+        .filter_module(env!("CARGO_CRATE_NAME"), level)
+        .parse_default_env()
+        .format(|buf, record| match record.level() {
+            log::Level::Info => writeln!(buf, "{}", record.args()),
+            level => writeln!(buf, "{}: {}", level.as_str().to_lowercase(), record.args()),
+        })
+        .init();
+}
+
+/// Print every file a run would write and the run's summary, then exit without writing
+/// anything.
+fn exit_with_dry_run(mut files: Vec<PathBuf>, extra_files: Vec<PathBuf>, summary: &str) -> ! {
+    files.extend(extra_files);
+    files.sort();
+
+    for file in files.iter() {
+        println!("{}", file.display());
+    }
+
+    println!("{summary}");
+    std::process::exit(0);
+}
+
+/// Print a diff of the documentation and exit, failing if anything changed.
+fn exit_with_diff(diff: String) -> ! {
+    print!("{diff}");
+    std::process::exit(if diff.is_empty() { 0 } else { 1 });
 }
 
 #[derive(clap::Parser, Debug)]
 struct Cli {
+    /// Read defaults for these options from a TOML file (defaults to `lcat.toml` if it exists)
+    #[arg(long, value_name("PATH"), value_hint(ValueHint::FilePath))]
+    config: Option<PathBuf>,
+
     /// Set the root search directory that lcat will look for Lua files in
     #[arg(short, long, value_name("DIR"), value_hint(ValueHint::DirPath))]
     dir: Option<PathBuf>,
 
-    /// Add one or more Lua files to generate documentation for
+    /// Add one or more Lua files to generate documentation for. `-` reads Lua from stdin
     #[arg(short, long)]
     files: Vec<PathBuf>,
 
+    /// Set the file name Lua read from stdin is documented as
+    #[arg(long, value_name("NAME"), default_value = "stdin.lua")]
+    stdin_name: PathBuf,
+
+    /// Skip files and directories under `--dir` matching a glob, like `tests/**`.
+    /// Can be passed more than once
+    #[arg(long, value_name("GLOB"))]
+    exclude: Vec<String>,
+
+    /// Also search `--dir` for files with this extension, in addition to `lua` and `luau`.
+    /// Can be passed more than once
+    #[arg(long, value_name("EXT"))]
+    ext: Vec<String>,
+
     /// Set the output directory (defaults to `ldoc_gen`)
     #[arg(short, long, value_name("DIR"), value_hint(ValueHint::DirPath))]
     out_dir: Option<PathBuf>,
 
+    /// Set the format to generate documentation in
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+
+    /// Set the name of the generated help file and the prefix of its tags
+    /// when using `--format vimdoc`
+    #[arg(long, value_name("NAME"), default_value = "lcat")]
+    vimdoc_name: String,
+
     /// Set the base url.
     ///
     /// If you are using VitePress with GitHub pages, you need to add the repository as
@@ -64,4 +349,207 @@ struct Cli {
     /// you also need to specify the base url here.
     #[arg(short, long)]
     base_url: Option<String>,
+
+    /// Set the subdirectory class pages are written to and linked under when using
+    /// `--format vitepress` (defaults to `classes`)
+    #[arg(long, value_name("DIR"))]
+    classes_dir: Option<String>,
+
+    /// Set the subdirectory alias pages are written to and linked under when using
+    /// `--format vitepress` (defaults to `aliases`)
+    #[arg(long, value_name("DIR"))]
+    aliases_dir: Option<String>,
+
+    /// Set the subdirectory enum pages are written to and linked under when using
+    /// `--format vitepress` (defaults to `enums`)
+    #[arg(long, value_name("DIR"))]
+    enums_dir: Option<String>,
+
+    /// Link where each class, field, and function is defined with a URL template,
+    /// like `https://github.com/user/repo/blob/main/{file}#L{line}`
+    #[arg(long, value_name("TEMPLATE"))]
+    source_url_template: Option<String>,
+
+    /// Set the maximum length of a rendered field value.
+    ///
+    /// Values longer than this, like large inline tables, are elided in the generated
+    /// documentation.
+    #[arg(long, value_name("LEN"), default_value_t = DEFAULT_MAX_VALUE_LEN)]
+    max_value_len: usize,
+
+    /// Embed the source of each documented function in its documentation
+    #[arg(long)]
+    emit_sources: bool,
+
+    /// Set how the aliased types of an alias are laid out
+    #[arg(long, value_enum, default_value_t)]
+    alias_layout: AliasLayout,
+
+    /// Set whether methods are shown as `Class:method(...)` or `Class.method(self, ...)`
+    #[arg(long, value_enum, default_value_t)]
+    method_style: MethodStyle,
+
+    /// Keep a `---@param self` annotated on a method in its signature and parameters
+    #[arg(long)]
+    show_self: bool,
+
+    /// Don't generate documentation for aliases and enums that are never referenced
+    #[arg(long)]
+    prune_unused: bool,
+
+    /// Remove fields declared `private` from the documentation
+    #[arg(long)]
+    strip_private: bool,
+
+    /// Set which fields and functions are documented by their visibility
+    #[arg(long, value_enum, default_value_t)]
+    visibility: Visibility,
+
+    /// Document functions declared `local`, which are left out by default
+    #[arg(long)]
+    include_locals: bool,
+
+    /// Generate a page listing symbols by the `@since` version that introduced them
+    #[arg(long)]
+    version_matrix: bool,
+
+    /// Write a `symbols.json` manifest of every symbol and the page documenting it
+    #[arg(long)]
+    symbols_manifest: bool,
+
+    /// Write a `search-index.json` mapping symbol names and descriptions to their VitePress or
+    /// Markdown docs
+    #[arg(long)]
+    search_index: bool,
+
+    /// Give each class function its own page instead of inlining it in the class page
+    #[arg(long)]
+    function_pages: bool,
+
+    /// Remove section headings that have nothing under them, like an empty `## Examples` in a description
+    #[arg(long)]
+    no_empty_sections: bool,
+
+    /// Render fields and parameters with a one-line description on a single line
+    #[arg(long)]
+    inline_descriptions: bool,
+
+    /// List required parameters first and optional ones in their own subsection
+    #[arg(long)]
+    group_optional_params: bool,
+
+    /// Spell out details of types that are easy to miss, like tuples being fixed-length
+    #[arg(long)]
+    verbose_types: bool,
+
+    /// Link literal types like `"focused"` to the enum value they match
+    #[arg(long)]
+    link_literals: bool,
+
+    /// Set the encoding generated files are written in
+    #[arg(long, value_enum, default_value_t)]
+    output_encoding: OutputEncoding,
+
+    /// Cache parsed files in `.lcat-cache` so unchanged files aren't parsed again
+    #[arg(long)]
+    cache: bool,
+
+    /// Print a diff against the existing output directory instead of writing to it,
+    /// failing if the documentation changed
+    #[arg(long)]
+    diff: bool,
+
+    /// Render everything without writing it, printing the files that would be written
+    /// and the symbol counts
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Write a summary of the run with symbol counts, documentation coverage, timings,
+    /// and diagnostics to the output directory
+    #[arg(long, value_enum)]
+    report: Option<ReportFormat>,
+
+    /// Also generate a JSON Schema for the given class as `<class>.schema.json`
+    /// in the output directory, for validating files like user configs
+    #[arg(long, value_name = "CLASS")]
+    json_schema: Option<String>,
+
+    /// Fail if any parameter or field lacks an explicit type
+    #[arg(long)]
+    strict_types: bool,
+
+    /// Fail if any public class, function, or field lacks a description or any file has
+    /// Lua syntax errors, listing each with its location
+    #[arg(long)]
+    strict: bool,
+
+    /// Exit with an error if less than this percentage of the symbols counted by
+    /// `--coverage` have descriptions, listing the undocumented ones
+    #[arg(long, value_name = "PERCENT")]
+    min_coverage: Option<f64>,
+
+    /// Print the percentage of public classes, functions, fields, aliases, and enums
+    /// that have descriptions, broken down by kind
+    #[arg(long)]
+    coverage: bool,
+
+    /// Print the `--coverage` numbers as JSON instead
+    #[arg(long)]
+    coverage_json: bool,
+
+    /// Set how many files are parsed at once (defaults to the number of CPU cores)
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<usize>,
+
+    /// Exit with an error on unknown annotations, like typos of `@param`, instead of
+    /// warning about them
+    #[arg(long)]
+    deny_unknown_annotations: bool,
+
+    /// Only print errors
+    #[arg(short, long, conflicts_with("verbose"))]
+    quiet: bool,
+
+    /// Print what lcat is doing for each file, or for each comment block when passed twice
+    #[arg(short, long, action(ArgAction::Count))]
+    verbose: u8,
+}
+
+impl Cli {
+    /// Fill in options that weren't passed on the command line from the config.
+    fn apply_config(&mut self, config: Config, matches: &ArgMatches) {
+        let Config {
+            dir,
+            files,
+            exclude,
+            out_dir,
+            base_url,
+            source_url_template,
+            format,
+            classes_dir,
+            aliases_dir,
+            enums_dir,
+        } = config;
+
+        self.dir = self.dir.take().or(dir);
+        if self.files.is_empty() {
+            self.files = files;
+        }
+        if self.exclude.is_empty() {
+            self.exclude = exclude;
+        }
+        self.out_dir = self.out_dir.take().or(out_dir);
+        self.base_url = self.base_url.take().or(base_url);
+        self.source_url_template = self.source_url_template.take().or(source_url_template);
+        self.classes_dir = self.classes_dir.take().or(classes_dir);
+        self.aliases_dir = self.aliases_dir.take().or(aliases_dir);
+        self.enums_dir = self.enums_dir.take().or(enums_dir);
+
+        // `format` always has a value, so check whether it was passed explicitly
+        if matches.value_source("format") != Some(ValueSource::CommandLine) {
+            if let Some(format) = format {
+                self.format = format;
+            }
+        }
+    }
 }
@@ -1,15 +1,21 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
 
-use pest::Parser;
+use miette::{Diagnostic, LabeledSpan, NamedSource, Severity, SourceCode, SourceSpan};
+use pest::{error::InputLocation, Parser};
 
 use crate::{
     annotation::{
-        parse_alias, parse_alias_line, parse_class, parse_enum, parse_field, parse_lcat,
-        parse_param, parse_return, parse_see, parse_type_annotation, Alias, Class, Enum, Function,
-        LcatOption, Param, PestParser, Return, Rule, See, TsField,
+        parse_alias, parse_alias_line, parse_badge, parse_class, parse_enum, parse_error,
+        parse_field, parse_generic, parse_lcat, parse_module, parse_operator, parse_overload,
+        parse_param, parse_return, parse_see, parse_text, parse_type_annotation, Alias, Class,
+        Enum, Function, LcatOption, Location, Param, PestParser, Return, Rule, Scope, See, TsField,
     },
-    treesitter::Block,
-    types::Type,
+    transform::{is_public, is_public_function},
+    treesitter::{Block, Comment, FieldName, FunctionParam, SyntaxError},
+    types::{Type, TypeInner},
 };
 
 #[derive(Debug, Default)]
@@ -18,54 +24,655 @@ pub struct Processor {
     pub aliases: Vec<Alias>,
     pub functions: Vec<Function>,
     pub enums: Vec<Enum>,
+    /// How many blocks have been processed, which orders symbols by declaration.
+    blocks_processed: usize,
+    /// The module declared with `@module` in the file being processed, which the
+    /// classes declared after it belong to.
+    module: Option<String>,
+}
+
+/// An annotation that failed to parse or isn't known, which was skipped, or Lua that
+/// tree-sitter couldn't parse.
+#[derive(Debug, Clone)]
+pub struct ProcessError {
+    pub kind: ProcessErrorKind,
+    pub file: PathBuf,
+    /// The 1-based line of the annotation.
+    pub line: usize,
+    /// The annotation as written after the `---`, like `@param` or `|` for alias types.
+    /// Empty for syntax errors.
+    pub annotation: String,
+    /// Why parsing failed, like `expected single_type`, or a suggestion for unknown annotations.
+    pub message: String,
+    /// The offending text in the file.
+    pub span: Option<SourceSpan>,
+    pub source_code: Option<NamedSource<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessErrorKind {
+    InvalidAnnotation,
+    /// An annotation tag lcat doesn't know, like a typo of `@param`. This is only a warning
+    /// unless `--deny-unknown-annotations` is passed.
+    UnknownAnnotation,
+    /// Lua that didn't parse, which documentation around it may be missing from. This is
+    /// only a warning unless `--strict` is passed.
+    SyntaxError,
+}
+
+impl ProcessError {
+    /// `arg_offset` is the byte offset in the file of the text the annotation's parser
+    /// was given, which pest error positions are relative to.
+    fn new(
+        file: &Path,
+        line: usize,
+        arg_offset: usize,
+        annotation: &str,
+        error: anyhow::Error,
+    ) -> Self {
+        let (message, span) = match error.downcast_ref::<pest::error::Error<Rule>>() {
+            Some(error) => {
+                let span = match error.location {
+                    InputLocation::Pos(pos) => SourceSpan::new((arg_offset + pos).into(), 0),
+                    InputLocation::Span((start, end)) => {
+                        SourceSpan::new((arg_offset + start).into(), end - start)
+                    }
+                };
+                (error.variant.message().to_string(), Some(span))
+            }
+            None => (error.to_string(), None),
+        };
+
+        Self {
+            kind: ProcessErrorKind::InvalidAnnotation,
+            file: file.to_path_buf(),
+            line,
+            annotation: annotation.to_string(),
+            message,
+            span,
+            source_code: None,
+        }
+    }
+
+    /// `tag_offset` is the byte offset in the file of the tag after the `@`.
+    fn unknown(file: &Path, line: usize, tag_offset: usize, tag: &str) -> Self {
+        let message = match suggest_tag(tag) {
+            Some(suggestion) => format!("did you mean `@{suggestion}`?"),
+            None => "lcat doesn't know this annotation and ignores it".to_string(),
+        };
+
+        Self {
+            kind: ProcessErrorKind::UnknownAnnotation,
+            file: file.to_path_buf(),
+            line,
+            annotation: format!("@{tag}"),
+            message,
+            span: Some(SourceSpan::new(tag_offset.into(), tag.len())),
+            source_code: None,
+        }
+    }
+
+    pub fn syntax(file: &Path, error: &SyntaxError) -> Self {
+        let message = match error.missing.as_deref() {
+            Some(missing) => format!("expected `{missing}`"),
+            None => "lcat couldn't parse this Lua, so documentation around it may be missing \
+                     or wrong"
+                .to_string(),
+        };
+
+        Self {
+            kind: ProcessErrorKind::SyntaxError,
+            file: file.to_path_buf(),
+            line: error.line,
+            annotation: String::new(),
+            message,
+            span: Some(SourceSpan::new(error.offset.into(), error.len)),
+            source_code: None,
+        }
+    }
+
+    /// Attach the source of the file so the error can be shown with the offending text.
+    pub fn with_source_code(mut self, source: &str) -> Self {
+        self.source_code = Some(NamedSource::new(
+            self.file.display().to_string(),
+            source.to_string(),
+        ));
+        self
+    }
+}
+
+impl std::fmt::Display for ProcessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let file = self.file.display();
+        let line = self.line;
+        let annotation = &self.annotation;
+
+        match self.kind {
+            ProcessErrorKind::InvalidAnnotation => write!(
+                f,
+                "{file}:{line}: skipping invalid `---{annotation}` annotation"
+            ),
+            ProcessErrorKind::UnknownAnnotation => {
+                write!(f, "{file}:{line}: unknown annotation `{annotation}`")
+            }
+            ProcessErrorKind::SyntaxError => write!(f, "{file}:{line}: Lua syntax error"),
+        }
+    }
+}
+
+impl std::error::Error for ProcessError {}
+
+impl Diagnostic for ProcessError {
+    fn severity(&self) -> Option<Severity> {
+        match self.kind {
+            ProcessErrorKind::InvalidAnnotation => Some(Severity::Error),
+            ProcessErrorKind::UnknownAnnotation | ProcessErrorKind::SyntaxError => {
+                Some(Severity::Warning)
+            }
+        }
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        Some(Box::new(&self.message))
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        self.source_code
+            .as_ref()
+            .map(|source| source as &dyn SourceCode)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        // Without the source, miette would fail to render the span
+        self.source_code.as_ref()?;
+        let span = self.span?;
+        Some(Box::new(std::iter::once(LabeledSpan::new_with_span(
+            Some("here".to_string()),
+            span,
+        ))))
+    }
+}
+
+/// The class, alias, enum, or `@type` a block's annotations are currently declaring.
+enum LastDeclared {
+    Class(Box<Class>),
+    Alias(Alias),
+    Enum(Enum),
+    Type(Type),
 }
 
 #[derive(Default)]
 struct FunctionAnnotations {
     params: Vec<Param>,
     returns: Vec<Return>,
+    errors: Vec<(Type, Option<String>)>,
+    generics: Vec<(String, Option<Type>)>,
+    overloads: Vec<Type>,
     sees: Vec<See>,
+    since: Option<String>,
+    deprecated: Option<String>,
+    badges: Vec<(String, String)>,
+    is_async: bool,
+    scope: Option<Scope>,
 }
 
 impl FunctionAnnotations {
     fn clear(&mut self) {
         self.params.clear();
         self.returns.clear();
+        self.errors.clear();
+        self.generics.clear();
+        self.overloads.clear();
         self.sees.clear();
+        self.since = None;
+        self.deprecated = None;
+        self.badges.clear();
+        self.is_async = false;
+        self.scope = None;
     }
 }
 
 impl Processor {
-    pub fn process_blocks(&mut self, blocks: Vec<Block>) {
+    /// Process the blocks of a file, returning errors for annotations that failed to parse.
+    ///
+    /// Annotations that fail to parse are skipped while the rest are still documented.
+    pub fn process_blocks(&mut self, blocks: Vec<Block>, file: &Path) -> Vec<ProcessError> {
         // A map of table names to class names for mapping
         let mut table_class_map = HashMap::<String, String>::new();
+        let mut errors = Vec::new();
+        self.module = None;
 
         for block in blocks {
-            if self.process_block(block, None, None, &mut table_class_map) {
+            log::trace!("{}: {}", file.display(), describe_block(&block));
+
+            if self.process_block(block, None, None, &mut table_class_map, file, &mut errors) {
+                break;
+            }
+        }
+
+        errors
+    }
+
+    /// Merge classes that share a name, like a class declared in one file and extended
+    /// with more fields in another.
+    ///
+    /// Merged classes keep the position of their first definition.
+    pub fn merge_classes(&mut self) {
+        let mut indices = HashMap::<String, usize>::new();
+        let mut classes = Vec::<Class>::new();
+
+        for class in std::mem::take(&mut self.classes) {
+            match indices.get(&class.name) {
+                Some(&index) => classes[index].merge(class),
+                None => {
+                    indices.insert(class.name.clone(), classes.len());
+                    classes.push(class);
+                }
+            }
+        }
+
+        self.classes = classes;
+    }
+
+    /// Resolve the parent chain of a class, nearest parent first.
+    ///
+    /// The chain stops at parents that aren't documented classes and at cycles
+    /// like `A : B`, `B : A`.
+    pub fn class_parents(&self, class: &Class) -> Vec<&Class> {
+        let mut parents = Vec::<&Class>::new();
+        let mut parent = class.parent.as_ref();
+
+        while let Some(TypeInner::UserDefined(name)) = parent.map(|ty| &ty.inner) {
+            let Some(cls) = self.classes.iter().find(|cls| &cls.name == name) else {
+                break;
+            };
+
+            if cls.name == class.name || parents.iter().any(|p| p.name == cls.name) {
                 break;
             }
+
+            parents.push(cls);
+            parent = cls.parent.as_ref();
+        }
+
+        parents
+    }
+
+    /// Remove aliases and enums that no documented type refers to.
+    ///
+    /// Classes are never pruned. Aliases and enums are kept if a class or function refers
+    /// to them, directly or through other kept aliases and enums, so ones only used by
+    /// pruned aliases are pruned too.
+    pub fn prune_unused(&mut self) {
+        fn referenced_names<'a>(types: impl IntoIterator<Item = &'a Type>) -> Vec<String> {
+            let mut names = Vec::new();
+            for ty in types {
+                ty.walk(&mut |ty| {
+                    if let TypeInner::UserDefined(name) = &ty.inner {
+                        names.push(name.clone());
+                    }
+                });
+            }
+            names
+        }
+
+        let mut pending = Vec::new();
+
+        for class in self.classes.iter() {
+            let operators = class
+                .operators
+                .iter()
+                .flat_map(|operator| operator.input.iter().chain([&operator.result]));
+            pending.extend(referenced_names(class.parent.iter().chain(operators)));
+
+            for field in class.fields() {
+                pending.extend(referenced_names(
+                    [&field.ident_type].into_iter().chain(&field.ty),
+                ));
+            }
         }
+
+        for function in self.functions.iter() {
+            let params = function.params.iter().map(|param| &param.ty);
+            let returns = function.returns.iter().map(|ret| &ret.ty);
+            pending.extend(referenced_names(params.chain(returns)));
+        }
+
+        // What each alias and enum refers to, which is only used if it is
+        let references = self
+            .aliases
+            .iter()
+            .map(|alias| {
+                let types = alias.types.iter().map(|(ty, _)| ty);
+                (alias.name.as_str(), referenced_names(types))
+            })
+            .chain(self.enums.iter().map(|r#enum| {
+                let types = r#enum.fields.iter().flat_map(|field| field.ty.as_ref());
+                (r#enum.name.as_str(), referenced_names(types))
+            }))
+            .collect::<HashMap<_, _>>();
+
+        let mut used = HashSet::new();
+        while let Some(name) = pending.pop() {
+            if let Some(names) = references.get(name.as_str()) {
+                if !used.contains(&name) {
+                    pending.extend(names.iter().cloned());
+                }
+            }
+            used.insert(name);
+        }
+
+        self.aliases.retain(|alias| {
+            let used = used.contains(&alias.name);
+            if !used {
+                log::warn!("pruning unused alias `{}`", alias.name);
+            }
+            used
+        });
+
+        self.enums.retain(|r#enum| {
+            let used = used.contains(&r#enum.name);
+            if !used {
+                log::warn!("pruning unused enum `{}`", r#enum.name);
+            }
+            used
+        });
     }
 
+    /// Check that every documented parameter and field has an explicit type.
+    ///
+    /// Missing descriptions are checked by [`Processor::check_documented`]. Returns an error message for each violation.
+    pub fn check_strict_types(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        for function in self.functions.iter() {
+            let name = function.qualified_name();
+            let location = &function.location;
+
+            for param in function.declared_params.iter() {
+                let param = match param {
+                    FunctionParam::Ident(param) => param.as_str(),
+                    FunctionParam::Varargs => "...",
+                };
+
+                if !function
+                    .params
+                    .iter()
+                    .any(|annotated| annotated.name == param)
+                {
+                    errors.push(format!(
+                        "{location}: parameter `{param}` of function `{name}` has no type"
+                    ));
+                }
+            }
+        }
+
+        for class in self.classes.iter() {
+            for field in class.fields() {
+                if field.ty.is_some() {
+                    continue;
+                }
+
+                let location = field
+                    .location
+                    .map(|location| format!("{location}: "))
+                    .unwrap_or_default();
+                let field_name = field.ident_type.format_as_table_field_name();
+                errors.push(format!(
+                    "{location}field `{field_name}` of class `{}` has no type",
+                    class.name
+                ));
+            }
+        }
+
+        errors
+    }
+
+    /// Check that every public class, function, and field has a description.
+    ///
+    /// Symbols hidden with `@lcat nodoc` are never processed, so only fields and
+    /// functions that aren't public API are skipped, like `--visibility public` does.
+    /// Returns an error message for each undocumented symbol.
+    pub fn check_documented(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        for class in self.classes.iter() {
+            let location = class
+                .location
+                .as_ref()
+                .map(|location| format!("{location}: "))
+                .unwrap_or_default();
+
+            if class.description.is_none() {
+                errors.push(format!(
+                    "{location}class `{}` has no description",
+                    class.name
+                ));
+            }
+
+            for field in class.fields() {
+                if field.description.is_some() || !is_public(field.scope) {
+                    continue;
+                }
+
+                let location = field
+                    .location
+                    .map(|location| format!("{location}: "))
+                    .unwrap_or_default();
+                let field_name = field.ident_type.format_as_table_field_name();
+                errors.push(format!(
+                    "{location}field `{field_name}` of class `{}` has no description",
+                    class.name
+                ));
+            }
+        }
+
+        for function in self.functions.iter() {
+            if function.description.is_none() && is_public_function(function) {
+                errors.push(format!(
+                    "{}: function `{}` has no description",
+                    function.location,
+                    function.qualified_name()
+                ));
+            }
+        }
+
+        errors
+    }
+
+    /// Sort classes, aliases, functions, and enums in the order they were declared.
+    ///
+    /// Symbols can be finished out of order, like a class being pushed after the classes
+    /// nested in its table.
+    pub fn sort_by_declaration(&mut self) {
+        self.classes.sort_by_key(|class| class.order);
+        self.aliases.sort_by_key(|alias| alias.order);
+        self.functions.sort_by_key(|function| function.order);
+        self.enums.sort_by_key(|r#enum| r#enum.order);
+    }
+
+    /// Process a block and give the symbols it declares the block's order.
+    ///
+    /// Blocks are processed in source order, nested table fields after their table,
+    /// so the order of the blocks is the order of the declarations.
+    ///
     /// Returns true if parsing should be stopped.
     #[must_use]
     fn process_block(
+        &mut self,
+        block: Block,
+        parent_class: Option<&mut Class>,
+        parent_enum: Option<&mut Enum>,
+        table_class_map: &mut HashMap<String, String>,
+        file: &Path,
+        errors: &mut Vec<ProcessError>,
+    ) -> bool {
+        self.blocks_processed += 1;
+        let order = self.blocks_processed;
+
+        let classes = self.classes.len();
+        let aliases = self.aliases.len();
+        let functions = self.functions.len();
+        let enums = self.enums.len();
+
+        let stop = self.process_block_annotations(
+            block,
+            parent_class,
+            parent_enum,
+            table_class_map,
+            file,
+            errors,
+        );
+
+        // Symbols declared by nested blocks already have their own order
+        let orders = (self.classes[classes..]
+            .iter_mut()
+            .map(|class| &mut class.order))
+        .chain(
+            self.aliases[aliases..]
+                .iter_mut()
+                .map(|alias| &mut alias.order),
+        )
+        .chain(
+            self.functions[functions..]
+                .iter_mut()
+                .map(|func| &mut func.order),
+        )
+        .chain(
+            self.enums[enums..]
+                .iter_mut()
+                .map(|r#enum| &mut r#enum.order),
+        );
+
+        for symbol_order in orders.filter(|symbol_order| **symbol_order == 0) {
+            *symbol_order = order;
+        }
+
+        for class in self.classes[classes..].iter_mut() {
+            if class.module.is_none() {
+                class.module.clone_from(&self.module);
+            }
+        }
+
+        stop
+    }
+
+    /// Process the fields of a table constructor assigned to a class field, returning
+    /// them documented like the fields of a class.
+    ///
+    /// Functions in the table aren't documented, as they aren't called on the class.
+    fn process_nested_fields(
+        &mut self,
+        blocks: Vec<Block>,
+        file: &Path,
+        errors: &mut Vec<ProcessError>,
+    ) -> Vec<TsField> {
+        let mut table = Class::new(String::new(), None);
+
+        for block in blocks
+            .into_iter()
+            .filter(|block| matches!(block, Block::Field(_) | Block::Table(_)))
+        {
+            if self.process_block(
+                block,
+                Some(&mut table),
+                None,
+                &mut HashMap::new(),
+                file,
+                errors,
+            ) {
+                break;
+            }
+        }
+
+        // Entries of lists like `{ 1, 2, 3 }` have no names to document them by, so
+        // their table is shown as a value instead
+        table
+            .ts_fields
+            .retain(|field| matches!(field.name, Some(FieldName::Ident(_))));
+        table.ts_fields
+    }
+
+    /// End the declaration function annotations like `@param` come after, keeping track
+    /// of the class it was in `ended_class` so `@field`s after them still belong to it.
+    fn end_declaration(
+        &mut self,
+        last_declared: &mut Option<LastDeclared>,
+        ended_class: &mut Option<usize>,
+    ) {
+        match last_declared.take() {
+            Some(LastDeclared::Class(class)) => {
+                self.classes.push(*class);
+                *ended_class = Some(self.classes.len() - 1);
+            }
+            Some(LastDeclared::Alias(alias)) => {
+                self.aliases.push(alias);
+                *ended_class = None;
+            }
+            Some(LastDeclared::Enum(r#enum)) => {
+                self.enums.push(r#enum);
+                *ended_class = None;
+            }
+            Some(LastDeclared::Type(_)) => *ended_class = None,
+            None => (),
+        }
+    }
+
+    /// Returns true if parsing should be stopped.
+    #[must_use]
+    fn process_block_annotations(
         &mut self,
         mut block: Block,
         mut parent_class: Option<&mut Class>,
         parent_enum: Option<&mut Enum>,
         table_class_map: &mut HashMap<String, String>,
+        file: &Path,
+        errors: &mut Vec<ProcessError>,
     ) -> bool {
-        enum LastDeclared {
-            Class(Class),
-            Alias(Alias),
-            Enum(Enum),
-            Type(Type),
+        /// The `@param` or `@field` annotated on the previous line, which annotations
+        /// like `@default` modify.
+        #[derive(Clone, Copy)]
+        enum LastAnnotated {
+            Param,
+            Field,
         }
 
         let mut nodoc = false;
+        // The class declared alongside a `@module` is the module itself
+        let mut declares_module = false;
+
+        /// The `@param` or `@return` whose description the following lines continue.
+        #[derive(Clone, Copy)]
+        enum Continued {
+            Param,
+            Return,
+        }
+
+        let mut last_annotated: Option<LastAnnotated> = None;
+        let mut continued: Option<Continued> = None;
+        let mut pending_default: Option<String> = None;
 
         let mut last_declared: Option<LastDeclared> = None;
+        // The class that function annotations like `@param` ended the declaration of,
+        // which `@field`s after them still belong to.
+        let mut ended_class: Option<usize> = None;
+        // A table annotated with both `@class` and `@enum` is documented as both:
+        // the class gets its methods and `@field`s while the enum gets its values.
+        let mut companion_class: Option<Class> = None;
+        let mut companion_enum: Option<Enum> = None;
+
+        /// The class `@field` and the annotations modifying it attach to.
+        fn current_class<'a>(
+            last_declared: &'a mut Option<LastDeclared>,
+            classes: &'a mut [Class],
+            ended_class: Option<usize>,
+        ) -> Option<&'a mut Class> {
+            match last_declared {
+                Some(LastDeclared::Class(class)) => Some(class),
+                None => classes.get_mut(ended_class?),
+                _ => None,
+            }
+        }
 
         let mut fn_annotations = FunctionAnnotations::default();
 
@@ -78,9 +685,52 @@ impl Processor {
             Block::Free(free) => std::mem::take(&mut free.annotations),
         };
 
-        for comment in annotations {
-            match try_parse_annotation(&comment) {
+        for Comment {
+            text: comment,
+            line,
+            offset,
+        } in annotations
+        {
+            let prev_annotated = last_annotated.take();
+            let prev_continued = continued.take();
+
+            let annotation = try_parse_annotation(&comment);
+            // The argument after the annotation's tag runs to the end of the comment
+            let arg_offset = |arg: &str| offset + comment.len() - arg.len();
+            let tag_arg_offset = arg_offset(annotation.as_ref().map_or("", |(_, arg)| arg));
+
+            match annotation {
                 None => {
+                    // Lines right after a `@param` or `@return` continue its description
+                    // until an empty line or the next annotation
+                    let continued_description = match prev_continued {
+                        Some(Continued::Param) => fn_annotations
+                            .params
+                            .last_mut()
+                            .map(|param| &mut param.description),
+                        Some(Continued::Return) => fn_annotations
+                            .returns
+                            .last_mut()
+                            .map(|ret| &mut ret.description),
+                        None => None,
+                    };
+
+                    if let Some(description) =
+                        continued_description.filter(|_| !comment.trim().is_empty())
+                    {
+                        match description {
+                            Some(description) => {
+                                description.push('\n');
+                                description.push_str(comment.trim());
+                            }
+                            None => *description = Some(comment.trim().to_string()),
+                        }
+
+                        continued = prev_continued;
+                        last_annotated = prev_annotated;
+                        continue;
+                    }
+
                     if let Some(LastDeclared::Alias(alias)) = last_declared.as_mut() {
                         if let Some(alias_line) = try_parse_alias_line(&comment) {
                             if let Some(alias_line) = alias_line {
@@ -93,7 +743,13 @@ impl Processor {
 
                                         alias.add_type(ty, ty_desc);
                                     }
-                                    Err(_) => todo!(),
+                                    Err(err) => errors.push(ProcessError::new(
+                                        file,
+                                        line,
+                                        arg_offset(&alias_line),
+                                        "|",
+                                        err,
+                                    )),
                                 }
                             }
                             continue;
@@ -105,44 +761,65 @@ impl Processor {
                     let description = (!doc_comments.is_empty()).then(|| doc_comments.join("\n"));
                     let class = parse_class(&class, description);
                     match class {
-                        Ok(class) => {
+                        Ok(mut class) => {
                             doc_comments.clear();
 
+                            class.location = Some(Location {
+                                file: file.to_path_buf(),
+                                line,
+                            });
+
+                            // Annotations above the `@class` that were waiting for a
+                            // declaration belong to it
+                            class.since = fn_annotations.since.take();
+                            class.deprecated = fn_annotations.deprecated.take();
+                            class.badges = std::mem::take(&mut fn_annotations.badges);
+
                             if nodoc {
                                 nodoc = false;
                                 continue;
                             }
 
-                            let last_declared = last_declared.replace(LastDeclared::Class(class));
+                            let last_declared =
+                                last_declared.replace(LastDeclared::Class(Box::new(class)));
 
                             match last_declared {
                                 Some(LastDeclared::Class(class)) => {
-                                    self.classes.push(class);
+                                    self.classes.push(*class);
                                 }
                                 Some(LastDeclared::Alias(alias)) => {
                                     self.aliases.push(alias);
                                 }
                                 Some(LastDeclared::Enum(r#enum)) => {
-                                    self.enums.push(r#enum);
+                                    if let Some(r#enum) = companion_enum.replace(r#enum) {
+                                        self.enums.push(r#enum);
+                                    }
                                 }
                                 _ => (),
                             }
 
                             fn_annotations.clear();
                         }
-                        Err(_) => {
-                            // TODO: miette error here
+                        Err(err) => {
+                            errors.push(ProcessError::new(
+                                file,
+                                line,
+                                tag_arg_offset,
+                                "@class",
+                                err,
+                            ));
                         }
                     }
                 }
                 Some((Annotation::Field, field)) => {
-                    match last_declared.as_mut() {
-                        Some(LastDeclared::Class(class)) => {
+                    let is_ended = last_declared.is_none();
+                    match current_class(&mut last_declared, &mut self.classes, ended_class) {
+                        Some(class) => {
                             let description =
                                 (!doc_comments.is_empty()).then(|| doc_comments.join("\n"));
                             let field = parse_field(&field, description);
                             match field {
-                                Ok(field) => {
+                                Ok(mut field) => {
                                     doc_comments.clear();
 
                                     if nodoc {
@@ -150,23 +827,77 @@ impl Processor {
                                         continue;
                                     }
 
+                                    field.default = pending_default.take();
+                                    field.location = Some(Location {
+                                        file: file.to_path_buf(),
+                                        line,
+                                    });
                                     class.lsp_fields.push(field);
-                                    fn_annotations.clear();
+                                    last_annotated = Some(LastAnnotated::Field);
+
+                                    // Function annotations before the field still belong
+                                    // to the function after it
+                                    if !is_ended {
+                                        fn_annotations.clear();
+                                    }
                                 }
-                                Err(_) => {
-                                    // TODO: miette
+                                Err(err) => {
+                                    errors.push(ProcessError::new(
+                                        file,
+                                        line,
+                                        tag_arg_offset,
+                                        "@field",
+                                        err,
+                                    ));
                                 }
                             }
                         }
-                        _ => continue, // TODO: warn
+                        None => log::warn!(
+                            "{}: `@field {field}` isn't under a `@class`, skipping it",
+                            file.display()
+                        ),
+                    }
+                }
+                Some((Annotation::Operator, operator)) => {
+                    match current_class(&mut last_declared, &mut self.classes, ended_class) {
+                        Some(class) => {
+                            let description =
+                                (!doc_comments.is_empty()).then(|| doc_comments.join("\n"));
+                            match parse_operator(&operator, description) {
+                                Ok(operator) => {
+                                    doc_comments.clear();
+
+                                    if nodoc {
+                                        nodoc = false;
+                                        continue;
+                                    }
+
+                                    class.operators.push(operator);
+                                }
+                                Err(err) => {
+                                    errors.push(ProcessError::new(
+                                        file,
+                                        line,
+                                        tag_arg_offset,
+                                        "@operator",
+                                        err,
+                                    ));
+                                }
+                            }
+                        }
+                        None => log::warn!(
+                            "{}: `@operator {operator}` isn't under a `@class`, skipping it",
+                            file.display()
+                        ),
                     }
                 }
                 Some((Annotation::Alias, alias)) => {
                     let description = (!doc_comments.is_empty()).then(|| doc_comments.join("\n"));
                     let alias = parse_alias(&alias, description);
                     match alias {
-                        Ok(alias) => {
+                        Ok(mut alias) => {
                             doc_comments.clear();
+                            alias.since = fn_annotations.since.take();
 
                             if nodoc {
                                 nodoc = false;
@@ -177,7 +908,7 @@ impl Processor {
 
                             match last_declared {
                                 Some(LastDeclared::Class(class)) => {
-                                    self.classes.push(class);
+                                    self.classes.push(*class);
                                 }
                                 Some(LastDeclared::Alias(alias)) => {
                                     self.aliases.push(alias);
@@ -189,36 +920,39 @@ impl Processor {
                             }
                             fn_annotations.clear();
                         }
-                        Err(_) => {
-                            // TODO:
-                        }
+                        Err(err) => errors.push(ProcessError::new(
+                            file,
+                            line,
+                            tag_arg_offset,
+                            "@alias",
+                            err,
+                        )),
                     }
                 }
                 Some((Annotation::Param, param)) => {
                     let param = parse_param(&param);
                     match param {
-                        Ok(param) => {
+                        Ok(mut param) => {
                             if nodoc {
                                 nodoc = false;
                                 continue;
                             }
 
+                            // A `@default` wins over an inline `[default=...]`
+                            param.default = pending_default.take().or(param.default);
                             fn_annotations.params.push(param);
+                            last_annotated = Some(LastAnnotated::Param);
+                            continued = Some(Continued::Param);
 
-                            match last_declared.take() {
-                                Some(LastDeclared::Class(class)) => {
-                                    self.classes.push(class);
-                                }
-                                Some(LastDeclared::Alias(alias)) => {
-                                    self.aliases.push(alias);
-                                }
-                                Some(LastDeclared::Enum(r#enum)) => {
-                                    self.enums.push(r#enum);
-                                }
-                                _ => (),
-                            }
+                            self.end_declaration(&mut last_declared, &mut ended_class);
                         }
-                        Err(err) => eprintln!("{err}"),
+                        Err(err) => errors.push(ProcessError::new(
+                            file,
+                            line,
+                            tag_arg_offset,
+                            "@param",
+                            err,
+                        )),
                     }
                 }
                 Some((Annotation::Return, ret)) => {
@@ -231,29 +965,48 @@ impl Processor {
                             }
 
                             fn_annotations.returns.push(ret);
+                            continued = Some(Continued::Return);
 
-                            match last_declared.take() {
-                                Some(LastDeclared::Class(class)) => {
-                                    self.classes.push(class);
-                                }
-                                Some(LastDeclared::Alias(alias)) => {
-                                    self.aliases.push(alias);
-                                }
-                                Some(LastDeclared::Enum(r#enum)) => {
-                                    self.enums.push(r#enum);
-                                }
-                                _ => (),
+                            self.end_declaration(&mut last_declared, &mut ended_class);
+                        }
+                        Err(err) => errors.push(ProcessError::new(
+                            file,
+                            line,
+                            tag_arg_offset,
+                            "@return",
+                            err,
+                        )),
+                    }
+                }
+                Some((Annotation::Error, error)) => {
+                    let error = parse_error(&error);
+                    match error {
+                        Ok(error) => {
+                            if nodoc {
+                                nodoc = false;
+                                continue;
                             }
+
+                            fn_annotations.errors.push(error);
+
+                            self.end_declaration(&mut last_declared, &mut ended_class);
                         }
-                        Err(_) => todo!(),
+                        Err(err) => errors.push(ProcessError::new(
+                            file,
+                            line,
+                            tag_arg_offset,
+                            "@error",
+                            err,
+                        )),
                     }
                 }
                 Some((Annotation::Enum, r#enum)) => {
                     let description = (!doc_comments.is_empty()).then(|| doc_comments.join("\n"));
                     let r#enum = parse_enum(&r#enum, description);
                     match r#enum {
-                        Ok(r#enum) => {
+                        Ok(mut r#enum) => {
                             doc_comments.clear();
+                            r#enum.since = fn_annotations.since.take();
 
                             if nodoc {
                                 nodoc = false;
@@ -264,7 +1017,9 @@ impl Processor {
 
                             match last_declared {
                                 Some(LastDeclared::Class(class)) => {
-                                    self.classes.push(class);
+                                    if let Some(class) = companion_class.replace(*class) {
+                                        self.classes.push(class);
+                                    }
                                 }
                                 Some(LastDeclared::Alias(alias)) => {
                                     self.aliases.push(alias);
@@ -276,7 +1031,9 @@ impl Processor {
                             }
                             fn_annotations.clear();
                         }
-                        Err(err) => eprintln!("{err}"),
+                        Err(err) => {
+                            errors.push(ProcessError::new(file, line, tag_arg_offset, "@enum", err))
+                        }
                     }
                 }
                 Some((Annotation::Lcat, lcat)) => {
@@ -286,8 +1043,116 @@ impl Processor {
                         nodoc = true;
                     }
                 }
+                Some((Annotation::Default, default)) => {
+                    let Some(default) = parse_text(&default) else {
+                        continue;
+                    };
+
+                    // `@default` attaches to the `@param`/`@field` right before it,
+                    // or to the next one if it comes first.
+                    match prev_annotated {
+                        Some(LastAnnotated::Param) => {
+                            if let Some(param) = fn_annotations.params.last_mut() {
+                                param.default = Some(default);
+                            }
+                        }
+                        Some(LastAnnotated::Field) => {
+                            if let Some(class) =
+                                current_class(&mut last_declared, &mut self.classes, ended_class)
+                            {
+                                if let Some(field) = class.lsp_fields.last_mut() {
+                                    field.default = Some(default);
+                                }
+                            }
+                        }
+                        None => pending_default = Some(default),
+                    }
+                }
+                Some((Annotation::Since, since)) => {
+                    let since = parse_text(&since);
+
+                    if let (Some(LastAnnotated::Field), Some(class)) = (
+                        prev_annotated,
+                        current_class(&mut last_declared, &mut self.classes, ended_class),
+                    ) {
+                        if let Some(field) = class.lsp_fields.last_mut() {
+                            field.since = since;
+                        }
+                        continue;
+                    }
+
+                    match (prev_annotated, last_declared.as_mut()) {
+                        (_, Some(LastDeclared::Class(class))) => class.since = since,
+                        (_, Some(LastDeclared::Alias(alias))) => alias.since = since,
+                        (_, Some(LastDeclared::Enum(r#enum))) => r#enum.since = since,
+                        _ => fn_annotations.since = since,
+                    }
+                }
+                Some((Annotation::Deprecated, message)) => {
+                    let deprecated = Some(parse_text(&message).unwrap_or_default());
+
+                    if let (Some(LastAnnotated::Field), Some(class)) = (
+                        prev_annotated,
+                        current_class(&mut last_declared, &mut self.classes, ended_class),
+                    ) {
+                        if let Some(field) = class.lsp_fields.last_mut() {
+                            field.deprecated = deprecated;
+                        }
+                        continue;
+                    }
+
+                    match last_declared.as_mut() {
+                        Some(LastDeclared::Class(class)) => class.deprecated = deprecated,
+                        _ => fn_annotations.deprecated = deprecated,
+                    }
+                }
+                Some((Annotation::Badge, badge)) => match parse_badge(&badge) {
+                    Ok(badge) => match last_declared.as_mut() {
+                        Some(LastDeclared::Class(class)) => class.badges.push(badge),
+                        _ => fn_annotations.badges.push(badge),
+                    },
+                    Err(err) => {
+                        errors.push(ProcessError::new(file, line, tag_arg_offset, "@badge", err))
+                    }
+                },
+                Some((Annotation::Async, _)) => fn_annotations.is_async = true,
+                Some((Annotation::Scope(scope), _)) => fn_annotations.scope = Some(scope),
+                Some((Annotation::Module, module)) => {
+                    if let Some(module) = parse_module(&module) {
+                        self.module = Some(module);
+                        declares_module = true;
+                    }
+                }
+                Some((Annotation::Generic, generic)) => match parse_generic(&generic) {
+                    Ok(generics) => fn_annotations.generics.extend(generics),
+                    Err(err) => errors.push(ProcessError::new(
+                        file,
+                        line,
+                        tag_arg_offset,
+                        "@generic",
+                        err,
+                    )),
+                },
+                Some((Annotation::Overload, overload)) => match parse_overload(&overload) {
+                    Ok(overload) => {
+                        if nodoc {
+                            nodoc = false;
+                            continue;
+                        }
+
+                        fn_annotations.overloads.push(overload);
+                    }
+                    Err(err) => errors.push(ProcessError::new(
+                        file,
+                        line,
+                        tag_arg_offset,
+                        "@overload",
+                        err,
+                    )),
+                },
                 Some((Annotation::Type, ty)) => {
-                    let ty = parse_type_annotation(&ty);
+                    // Only the first type of a multiple assignment is documented
+                    let ty = parse_type_annotation(&ty).map(|mut types| types.swap_remove(0));
 
                     match ty {
                         Ok(ty) => {
@@ -300,7 +1165,7 @@ impl Processor {
 
                             match last_declared {
                                 Some(LastDeclared::Class(class)) => {
-                                    self.classes.push(class);
+                                    self.classes.push(*class);
                                 }
                                 Some(LastDeclared::Alias(alias)) => {
                                     self.aliases.push(alias);
@@ -312,7 +1177,9 @@ impl Processor {
                             }
                             fn_annotations.clear();
                         }
-                        Err(_) => todo!(),
+                        Err(err) => {
+                            errors.push(ProcessError::new(file, line, tag_arg_offset, "@type", err))
+                        }
                     }
                 }
                 Some((Annotation::See, see)) => {
@@ -327,71 +1194,103 @@ impl Processor {
 
                             fn_annotations.sees.push(see);
 
-                            match last_declared.take() {
-                                Some(LastDeclared::Class(class)) => {
-                                    self.classes.push(class);
-                                }
-                                Some(LastDeclared::Alias(alias)) => {
-                                    self.aliases.push(alias);
-                                }
-                                Some(LastDeclared::Enum(r#enum)) => {
-                                    self.enums.push(r#enum);
-                                }
-                                _ => (),
-                            }
+                            self.end_declaration(&mut last_declared, &mut ended_class);
+                        }
+                        Err(err) => {
+                            errors.push(ProcessError::new(file, line, tag_arg_offset, "@see", err))
                         }
-                        Err(_) => todo!(),
                     }
                 }
-                Some((Annotation::Unknown(_unknown), _)) => {
-                    // TODO: warn
+                // Only for the language server, so it's left out of the docs. Annotations
+                // like `@default` after it still modify the `@param` or `@field` before it.
+                Some((Annotation::Diagnostic, _)) => last_annotated = prev_annotated,
+                Some((Annotation::Unknown(tag), _)) => {
+                    if IGNORED_TAGS.contains(&tag.as_str()) {
+                        last_annotated = prev_annotated;
+                    } else {
+                        let tag_offset = offset + comment.find('@').unwrap() + 1;
+                        errors.push(ProcessError::unknown(file, line, tag_offset, &tag));
+                    }
                 }
             }
         }
 
-        if let Some(parent_class) = parent_class.as_mut() {
-            if let Block::Field(field_block) = &mut block {
-                if nodoc {
-                    return false;
-                }
-
-                let ty = if let Some(LastDeclared::Type(ty)) = last_declared.as_ref() {
-                    Some(ty.clone())
-                } else {
-                    None
-                };
-
-                let field = TsField {
-                    name: field_block.name.clone(),
-                    ty,
-                    description: (!doc_comments.is_empty()).then(|| doc_comments.join("\n")),
-                    value: field_block.value.clone(),
-                };
-
-                parent_class.ts_fields.push(field);
+        // Functions in an enum are its values rather than functions of their own
+        let is_enum_function =
+            matches!(block, Block::Function(_)) && parent_enum.is_some() && parent_class.is_none();
+
+        // Fields with table values are parsed as table blocks; unless they declare
+        // a class or enum themselves, they're documented like any other field.
+        let ts_field = match &block {
+            Block::Field(field_block) => Some((
+                field_block.name.clone(),
+                field_block.value.clone(),
+                field_block.trailing_comment.clone(),
+                field_block.line,
+                Vec::new(),
+            )),
+            Block::Table(table_block)
+                if !matches!(
+                    last_declared,
+                    Some(LastDeclared::Class(_) | LastDeclared::Enum(_))
+                ) =>
+            {
+                Some((
+                    Some(FieldName::Ident(table_block.name.clone())),
+                    table_block.value.clone(),
+                    None,
+                    table_block.line,
+                    table_block.fields.clone(),
+                ))
             }
+            Block::Function(function_block) if is_enum_function => Some((
+                Some(FieldName::Ident(function_block.name.clone())),
+                function_block
+                    .value
+                    .clone()
+                    .unwrap_or_else(|| function_block.source.clone()),
+                None,
+                function_block.line,
+                Vec::new(),
+            )),
+            _ => None,
         }
+        .filter(|_| parent_class.is_some() || parent_enum.is_some())
+        .map(|(name, value, trailing_comment, line, nested_blocks)| {
+            let ty = if let Some(LastDeclared::Type(ty)) = last_declared.as_ref() {
+                Some(ty.clone())
+            } else {
+                None
+            };
+
+            let ts_field = TsField {
+                name,
+                ty,
+                description: (!doc_comments.is_empty())
+                    .then(|| doc_comments.join("\n"))
+                    .or(trailing_comment),
+                value,
+                fields: Vec::new(),
+                location: Location {
+                    file: file.to_path_buf(),
+                    line,
+                },
+            };
+
+            (ts_field, nested_blocks)
+        });
+
+        if let Some((mut ts_field, nested_blocks)) = ts_field {
+            if nodoc {
+                return false;
+            }
 
-        if let Some(parent_enum) = parent_enum {
-            if let Block::Field(field_block) = &mut block {
-                if nodoc {
-                    return false;
-                }
-
-                let ty = if let Some(LastDeclared::Type(ty)) = last_declared.as_ref() {
-                    Some(ty.clone())
-                } else {
-                    None
-                };
-
-                let field = TsField {
-                    name: field_block.name.clone(),
-                    ty,
-                    description: (!doc_comments.is_empty()).then(|| doc_comments.join("\n")),
-                    value: field_block.value.clone(),
-                };
-
-                parent_enum.fields.push(field);
+            // Values of a table that's both a class and an enum belong to the enum
+            if let Some(parent_enum) = parent_enum {
+                parent_enum.fields.push(ts_field);
+            } else if let Some(parent_class) = parent_class.as_mut() {
+                ts_field.fields = self.process_nested_fields(nested_blocks, file, errors);
+                parent_class.ts_fields.push(ts_field);
             }
         }
 
@@ -400,17 +1299,25 @@ impl Processor {
                 if nodoc {
                     return false;
                 }
+                class.is_module = declares_module;
                 if let Block::Table(table_block) = &mut block {
                     table_class_map.insert(table_block.name.clone(), class.name.clone());
 
                     for block in table_block.fields.clone() {
-                        if self.process_block(block, Some(&mut class), None, table_class_map) {
+                        if self.process_block(
+                            block,
+                            Some(&mut class),
+                            companion_enum.as_mut(),
+                            table_class_map,
+                            file,
+                            errors,
+                        ) {
                             break;
                         }
                     }
                 }
 
-                self.classes.push(class);
+                self.classes.push(*class);
             }
             Some(LastDeclared::Alias(alias)) => {
                 if nodoc {
@@ -424,8 +1331,19 @@ impl Processor {
                 }
 
                 if let Block::Table(table_block) = &mut block {
+                    if let Some(class) = companion_class.as_ref() {
+                        table_class_map.insert(table_block.name.clone(), class.name.clone());
+                    }
+
                     for block in table_block.fields.clone() {
-                        if self.process_block(block, None, Some(&mut r#enum), table_class_map) {
+                        if self.process_block(
+                            block,
+                            companion_class.as_mut(),
+                            Some(&mut r#enum),
+                            table_class_map,
+                            file,
+                            errors,
+                        ) {
                             break;
                         }
                     }
@@ -436,8 +1354,16 @@ impl Processor {
             _ => (),
         }
 
+        if let Some(class) = companion_class {
+            self.classes.push(class);
+        }
+
+        if let Some(r#enum) = companion_enum {
+            self.enums.push(r#enum);
+        }
+
         if let Block::Function(function_block) = &mut block {
-            if nodoc {
+            if nodoc || is_enum_function {
                 return false;
             }
 
@@ -457,14 +1383,51 @@ impl Processor {
                 }
             }
 
+            // References to the function's type parameters don't link anywhere
+            let type_params = fn_annotations
+                .generics
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>();
+
+            if !type_params.is_empty() {
+                let types = fn_annotations
+                    .params
+                    .iter_mut()
+                    .map(|param| &mut param.ty)
+                    .chain(fn_annotations.returns.iter_mut().map(|ret| &mut ret.ty))
+                    .chain(fn_annotations.errors.iter_mut().map(|(ty, _)| ty))
+                    .chain(fn_annotations.overloads.iter_mut());
+
+                for ty in types {
+                    ty.resolve_type_params(&type_params);
+                }
+            }
+
             self.functions.push(Function {
                 name: function_block.name.clone(),
                 params: fn_annotations.params,
                 returns: fn_annotations.returns,
+                errors: fn_annotations.errors,
+                generics: fn_annotations.generics,
+                overloads: fn_annotations.overloads,
                 sees: fn_annotations.sees,
                 table,
                 is_method: function_block.is_method,
+                is_async: fn_annotations.is_async,
+                is_local: function_block.is_local,
+                scope: fn_annotations.scope,
                 description: (!doc_comments.is_empty()).then(|| doc_comments.join("\n")),
+                source: function_block.source.clone(),
+                since: fn_annotations.since,
+                deprecated: fn_annotations.deprecated,
+                badges: fn_annotations.badges,
+                declared_params: function_block.params.clone(),
+                location: Location {
+                    file: file.to_path_buf(),
+                    line: function_block.line,
+                },
+                order: 0,
             });
         }
 
@@ -472,6 +1435,7 @@ impl Processor {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 enum Annotation {
     Class,
     Field,
@@ -482,9 +1446,120 @@ enum Annotation {
     Lcat,
     Type,
     See,
+    Default,
+    Since,
+    Operator,
+    Error,
+    Deprecated,
+    Badge,
+    Async,
+    Scope(Scope),
+    Overload,
+    Generic,
+    Module,
+    Diagnostic,
     Unknown(String),
 }
 
+/// The annotation tags lcat parses and what each is parsed as. `@diagnostic` is parsed
+/// only to be discarded.
+const KNOWN_TAGS: &[(&str, Annotation)] = &[
+    ("alias", Annotation::Alias),
+    ("class", Annotation::Class),
+    ("field", Annotation::Field),
+    ("param", Annotation::Param),
+    ("return", Annotation::Return),
+    ("enum", Annotation::Enum),
+    ("lcat", Annotation::Lcat),
+    ("type", Annotation::Type),
+    ("see", Annotation::See),
+    ("default", Annotation::Default),
+    ("since", Annotation::Since),
+    ("operator", Annotation::Operator),
+    ("error", Annotation::Error),
+    ("throws", Annotation::Error),
+    ("deprecated", Annotation::Deprecated),
+    ("badge", Annotation::Badge),
+    ("async", Annotation::Async),
+    ("public", Annotation::Scope(Scope::Public)),
+    ("private", Annotation::Scope(Scope::Private)),
+    ("protected", Annotation::Scope(Scope::Protected)),
+    ("package", Annotation::Scope(Scope::Package)),
+    ("overload", Annotation::Overload),
+    ("generic", Annotation::Generic),
+    ("module", Annotation::Module),
+    ("diagnostic", Annotation::Diagnostic),
+];
+
+/// LuaLS annotation tags that lcat ignores without warning.
+const IGNORED_TAGS: &[&str] = &[
+    "as",
+    "cast",
+    "meta",
+    "nodiscard",
+    "source",
+    "vararg",
+    "version",
+];
+
+/// Describe a block for debug output, like ``line 3: function `Window.close` ``.
+fn describe_block(block: &Block) -> String {
+    let (annotations, what) = match block {
+        Block::Table(table) => (&table.annotations, format!("table `{}`", table.name)),
+        Block::Field(field) => (
+            &field.annotations,
+            match field.name.as_ref() {
+                Some(name) => format!("field `{name}`"),
+                None => "field".to_string(),
+            },
+        ),
+        Block::Function(func) => {
+            let name = match func.table.as_ref() {
+                Some(table) => format!("{table}.{}", func.name),
+                None => func.name.clone(),
+            };
+            (&func.annotations, format!("function `{name}`"))
+        }
+        Block::Free(free) => (&free.annotations, "free comments".to_string()),
+    };
+
+    match annotations.first() {
+        Some(comment) => format!("line {}: {what}", comment.line),
+        None => what,
+    }
+}
+
+/// The known or ignored tag closest to a misspelled one, if any is close enough.
+fn suggest_tag(tag: &str) -> Option<&'static str> {
+    KNOWN_TAGS
+        .iter()
+        .map(|(known, _)| known)
+        .chain(IGNORED_TAGS)
+        .map(|known| (*known, levenshtein(tag, known)))
+        .filter(|(_, distance)| *distance <= 2 && *distance < tag.len())
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(known, _)| known)
+}
+
+/// The number of single-character insertions, deletions, and substitutions between two strings.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev_row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = prev_row[j] + usize::from(a_char != *b_char);
+            row.push(substitution.min(prev_row[j + 1] + 1).min(row[j] + 1));
+        }
+
+        prev_row = row;
+    }
+
+    prev_row[b.len()]
+}
+
 fn try_parse_annotation(line: &str) -> Option<(Annotation, String)> {
     let mut annotation = PestParser::parse(Rule::annotation, line).ok()?;
 
@@ -501,21 +1576,14 @@ fn try_parse_annotation(line: &str) -> Option<(Annotation, String)> {
         }
     }
 
-    Some((
-        match ident.unwrap().as_str() {
-            "alias" => Annotation::Alias,
-            "class" => Annotation::Class,
-            "field" => Annotation::Field,
-            "param" => Annotation::Param,
-            "return" => Annotation::Return,
-            "enum" => Annotation::Enum,
-            "lcat" => Annotation::Lcat,
-            "type" => Annotation::Type,
-            "see" => Annotation::See,
-            unknown => Annotation::Unknown(unknown.to_string()),
-        },
-        rest_of_line.unwrap_or_default(),
-    ))
+    let tag = ident.unwrap();
+    let annotation = KNOWN_TAGS
+        .iter()
+        .find(|(known, _)| *known == tag)
+        .map(|(_, annotation)| annotation.clone())
+        .unwrap_or_else(|| Annotation::Unknown(tag));
+
+    Some((annotation, rest_of_line.unwrap_or_default()))
 }
 
 fn try_parse_alias_line(line: &str) -> Option<Option<String>> {
@@ -525,3 +1593,1298 @@ fn try_parse_alias_line(line: &str) -> Option<Option<String>> {
 
     Some(rest_of_line.map(|line| line.as_str().to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::state::parse_str;
+
+    mod trailing_comments {
+        use super::*;
+
+        #[test]
+        fn trailing_comments_describe_enum_fields() {
+            let processor = parse_str(
+                r#"
+---@enum Mode
+local Mode = {
+    A = 1, -- the first option
+    ---The second option
+    B = 2, -- not this
+    C = 3,
+    -- a comment about D
+    D = 4 --- the last option
+}
+"#,
+            );
+
+            let descriptions = processor.enums[0]
+                .fields
+                .iter()
+                .map(|field| field.description.as_deref())
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                descriptions,
+                [
+                    Some("the first option"),
+                    Some("The second option"),
+                    None,
+                    Some("the last option")
+                ]
+            );
+        }
+    }
+
+    mod strict_types {
+        use super::*;
+
+        #[test]
+        fn fully_typed_symbols_pass() {
+            let processor = parse_str(
+                r#"
+---@class Window
+local Window = {
+    ---The title
+    ---@type string
+    title = "",
+}
+
+---Resize the window
+---@param width integer
+---@param height integer
+function Window:resize(width, height) end
+
+---@param x integer
+function _private(x) end
+"#,
+            );
+
+            assert_eq!(processor.check_strict_types(), Vec::<String>::new());
+        }
+
+        #[test]
+        fn untyped_symbols_fail() {
+            let processor = parse_str(
+                r#"
+---@class Window
+local Window = {
+    ---The title
+    title = "",
+}
+
+---Resize the window
+---@param width integer
+function Window:resize(width, height) end
+
+---@param x integer
+function undocumented(x) end
+"#,
+            );
+
+            assert_eq!(
+                processor.check_strict_types(),
+                [
+                    "test.lua:10: parameter `height` of function `Window:resize` has no type",
+                    "test.lua:5: field `title` of class `Window` has no type",
+                ]
+            );
+        }
+
+        #[test]
+        fn annotated_self_params_are_typed() {
+            let processor = parse_str(
+                r#"
+---@class Window
+local Window = {}
+
+---@param self Window
+function Window:close() end
+
+---@param self Window
+---@param width integer
+function Window.resize(self, width) end
+
+---Move the window
+function Window.move(self) end
+"#,
+            );
+
+            assert_eq!(
+                processor.check_strict_types(),
+                ["test.lua:13: parameter `self` of function `Window.move` has no type"]
+            );
+        }
+    }
+
+    mod strict {
+        use super::*;
+
+        #[test]
+        fn documented_symbols_pass() {
+            let processor = parse_str(
+                r#"
+---A window
+---@class Window
+---@field private handle integer
+---@field protected parent Window
+---@field package id integer
+local Window = {
+    ---The title
+    title = "",
+}
+
+---Close the window
+function Window:close() end
+
+function _internal() end
+
+---@private
+---@param x integer
+function internal(x) end
+
+---@lcat nodoc
+function hidden() end
+"#,
+            );
+
+            assert_eq!(processor.check_documented(), Vec::<String>::new());
+        }
+
+        #[test]
+        fn undocumented_symbols_fail() {
+            let processor = parse_str(
+                r#"
+---@class Window
+---@field id integer
+local Window = {
+    title = "",
+}
+
+---@param width integer
+function Window:resize(width) end
+"#,
+            );
+
+            assert_eq!(
+                processor.check_documented(),
+                [
+                    "test.lua:2: class `Window` has no description",
+                    "test.lua:3: field `id` of class `Window` has no description",
+                    "test.lua:5: field `title` of class `Window` has no description",
+                    "test.lua:9: function `Window:resize` has no description",
+                ]
+            );
+        }
+    }
+
+    mod prune_unused {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@alias Used string
+---@alias Unused string
+---@alias Recursive Recursive[]
+
+---@enum UsedEnum
+local UsedEnum = {
+    A = 1,
+}
+
+---@enum UnusedEnum
+local UnusedEnum = {
+    B = 2,
+}
+
+---@class Window
+---@field mode Used
+local Window = {}
+
+---@class Unreferenced
+local Unreferenced = {}
+
+---@param enum UsedEnum
+---@return integer
+function Window.get(enum) end
+"#;
+
+        fn names<T>(items: &[T], name: impl Fn(&T) -> &str) -> Vec<&str> {
+            items.iter().map(name).collect()
+        }
+
+        #[test]
+        fn unused_aliases_and_enums_are_kept_by_default() {
+            let processor = parse_str(SOURCE);
+
+            let aliases = names(&processor.aliases, |alias| &alias.name);
+            assert_eq!(aliases, ["Used", "Unused", "Recursive"]);
+            let enums = names(&processor.enums, |r#enum| &r#enum.name);
+            assert_eq!(enums, ["UsedEnum", "UnusedEnum"]);
+        }
+
+        #[test]
+        fn unused_aliases_and_enums_are_pruned() {
+            let mut processor = parse_str(SOURCE);
+            processor.prune_unused();
+
+            let aliases = names(&processor.aliases, |alias| &alias.name);
+            assert_eq!(aliases, ["Used"]);
+            let enums = names(&processor.enums, |r#enum| &r#enum.name);
+            assert_eq!(enums, ["UsedEnum"]);
+            let classes = names(&processor.classes, |class| &class.name);
+            assert_eq!(classes, ["Window", "Unreferenced"]);
+        }
+
+        #[test]
+        fn types_only_used_by_pruned_types_are_pruned() {
+            let mut processor = parse_str(
+                r#"
+---@alias Leaf "a" | "b"
+---@alias Branch Leaf[]
+---@alias Reached Nested | Kind
+
+---@alias Nested string
+
+---@enum Kind
+local Kind = {
+    ---@type Branch
+    A = 1,
+}
+
+---@param reached Reached
+function use(reached) end
+"#,
+            );
+            processor.prune_unused();
+
+            let aliases = names(&processor.aliases, |alias| &alias.name);
+            assert_eq!(aliases, ["Leaf", "Branch", "Reached", "Nested"]);
+            let enums = names(&processor.enums, |r#enum| &r#enum.name);
+            assert_eq!(enums, ["Kind"]);
+
+            let mut processor = parse_str(
+                r#"
+---@alias Leaf "a" | "b"
+---@alias Branch Leaf[]
+---@alias Trunk Branch
+"#,
+            );
+            processor.prune_unused();
+
+            assert!(processor.aliases.is_empty());
+        }
+    }
+
+    mod default {
+        use super::*;
+
+        #[test]
+        fn default_after_param_attaches_to_it() {
+            let processor = parse_str(
+                r#"
+---Open a window.
+---@param width integer
+---@default 800
+---@param height integer
+function open(width, height) end
+"#,
+            );
+
+            let params = &processor.functions[0].params;
+            assert_eq!(params[0].default.as_deref(), Some("800"));
+            assert_eq!(params[1].default, None);
+        }
+
+        #[test]
+        fn default_before_param_attaches_to_next() {
+            let processor = parse_str(
+                r#"
+---@default "auto"
+---@param mode string
+function set_mode(mode) end
+"#,
+            );
+
+            let params = &processor.functions[0].params;
+            assert_eq!(params[0].default.as_deref(), Some(r#""auto""#));
+        }
+
+        #[test]
+        fn default_attaches_to_field() {
+            let processor = parse_str(
+                r#"
+---@class Config
+---@field gaps integer
+---@default 8
+---@field border integer
+local Config = {}
+"#,
+            );
+
+            let fields = &processor.classes[0].lsp_fields;
+            assert_eq!(fields[0].default.as_deref(), Some("8"));
+            assert_eq!(fields[1].default, None);
+        }
+    }
+
+    mod interleaved_fields {
+        use super::*;
+
+        use crate::types::Type;
+
+        #[test]
+        fn fields_after_function_annotations_attach_to_the_class() {
+            let processor = parse_str(
+                r#"
+---@class Window
+---@field id integer
+---@param title string
+---@field title string
+---@default "untitled"
+---@return Window
+function Window.new(title) end
+"#,
+            );
+
+            let fields = &processor.classes[0].lsp_fields;
+            assert_eq!(fields.len(), 2);
+            assert_eq!(fields[0].ident_type, Type::string_literal("id"));
+            assert_eq!(fields[1].ident_type, Type::string_literal("title"));
+            assert_eq!(fields[1].default.as_deref(), Some(r#""untitled""#));
+
+            let function = &processor.functions[0];
+            assert_eq!(function.params.len(), 1);
+            assert_eq!(function.params[0].name, "title");
+            assert_eq!(function.params[0].default, None);
+            assert_eq!(function.returns.len(), 1);
+        }
+
+        #[test]
+        fn fields_without_a_class_are_skipped() {
+            let processor = parse_str(
+                r#"
+---@alias Mode "a" | "b"
+---@field stray integer
+"#,
+            );
+
+            assert!(processor.classes.is_empty());
+            assert_eq!(processor.aliases.len(), 1);
+        }
+    }
+
+    mod operators {
+        use super::*;
+
+        use crate::types::Type;
+
+        #[test]
+        fn operators_with_the_same_name_are_all_kept() {
+            let processor = parse_str(
+                r#"
+---@class Vector
+---@operator sub: Vector
+---Subtract two vectors.
+---@operator sub(Vector): Vector
+---@operator add(Vector): Vector
+local Vector = {}
+"#,
+            );
+
+            let operators = &processor.classes[0].operators;
+
+            assert_eq!(operators.len(), 3);
+            assert_eq!(operators[0].op, "sub");
+            assert_eq!(operators[0].input, None);
+            assert_eq!(operators[0].result, Type::user_defined("Vector"));
+            assert_eq!(operators[1].op, "sub");
+            assert_eq!(operators[1].input, Some(Type::user_defined("Vector")));
+            assert_eq!(
+                operators[1].description.as_deref(),
+                Some("Subtract two vectors.")
+            );
+            assert_eq!(operators[2].op, "add");
+        }
+    }
+
+    mod unicode {
+        use super::*;
+
+        use crate::{processor::ProcessErrorKind, state::parse_files, types::Type};
+
+        #[test]
+        fn unicode_letters_are_identifiers() {
+            let processor = parse_str(
+                r#"
+---@class Ünïcode
+---@field naïve Ünïcode
+local Ünïcode = {}
+
+---@param größe Ünïcode
+---@param naïve٣ Ünïcode
+function Ünïcode.new(größe, naïve٣) end
+"#,
+            );
+
+            let class = &processor.classes[0];
+            assert_eq!(class.name, "Ünïcode");
+            assert_eq!(
+                class.lsp_fields[0].ident_type,
+                Type::string_literal("naïve")
+            );
+            assert_eq!(class.lsp_fields[0].ty, Type::user_defined("Ünïcode"));
+
+            let param = &processor.functions[0].params[0];
+            assert_eq!(param.name, "größe");
+            assert_eq!(param.ty, Type::user_defined("Ünïcode"));
+
+            // A combining diaeresis and an Arabic-Indic digit continue an identifier
+            assert_eq!(processor.functions[0].params[1].name, "nai\u{308}ve٣");
+        }
+
+        #[test]
+        fn non_letter_type_names_are_reported() {
+            let source =
+                "---@param x 💥\n---@return 💥\n---@return integer\nfunction explode(x) end\n";
+
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("explode.lua");
+            std::fs::write(&file, source).unwrap();
+
+            let (processor, files) = parse_files(vec![file], None).unwrap();
+
+            let function = &processor.functions[0];
+            assert!(function.params.is_empty());
+            assert_eq!(function.returns.len(), 1);
+            assert_eq!(function.returns[0].ty, Type::INTEGER);
+
+            assert_eq!(
+                files[0]
+                    .errors
+                    .iter()
+                    .map(|error| (error.kind, error.line, error.annotation.as_str()))
+                    .collect::<Vec<_>>(),
+                [
+                    (ProcessErrorKind::InvalidAnnotation, 1, "@param"),
+                    (ProcessErrorKind::InvalidAnnotation, 2, "@return"),
+                ]
+            );
+        }
+    }
+
+    mod generic_aliases {
+        use super::*;
+
+        use crate::types::{Type, TypeInner};
+
+        #[test]
+        fn type_params_are_resolved_in_every_aliased_type() {
+            let processor = parse_str(
+                r#"
+---@alias Pair<K, V>
+---| { key: K, value: V }
+---| [K, V]
+"#,
+            );
+
+            let alias = &processor.aliases[0];
+            assert_eq!(alias.name, "Pair");
+            assert_eq!(alias.type_params, ["K", "V"]);
+            assert_eq!(alias.name_with_type_params(), "Pair<K, V>");
+
+            let mut params = Vec::new();
+            for (ty, _) in alias.types.iter() {
+                ty.walk(&mut |ty| match &ty.inner {
+                    TypeInner::Generic(name) => params.push(name.clone()),
+                    TypeInner::UserDefined(name) => panic!("`{name}` wasn't resolved"),
+                    _ => (),
+                });
+            }
+            assert_eq!(params, ["K", "V", "K", "V"]);
+        }
+
+        #[test]
+        fn aliases_without_type_params_are_unchanged() {
+            let processor = parse_str("---@alias Handle Window\n");
+
+            let alias = &processor.aliases[0];
+            assert!(alias.type_params.is_empty());
+            assert_eq!(alias.types[0].0, Type::user_defined("Window"));
+        }
+    }
+
+    mod annotation_only_fields {
+        use super::*;
+
+        use crate::types::Type;
+
+        #[test]
+        fn fields_without_a_table_are_documented() {
+            let processor = parse_str(
+                r#"
+---A class whose fields are all set through `rawset`.
+---@class Dynamic
+---@field name string The name
+---@field count integer
+
+rawset(_G, "count", 1)
+"#,
+            );
+
+            let fields = processor.classes[0].fields();
+
+            assert_eq!(fields.len(), 2);
+            assert_eq!(fields[0].ident_type, Type::string_literal("name"));
+            assert_eq!(fields[0].ty, Some(Type::STRING));
+            assert_eq!(fields[0].description.as_deref(), Some("The name"));
+            assert_eq!(fields[0].value, None);
+            assert_eq!(fields[1].ident_type, Type::string_literal("count"));
+            assert_eq!(fields[1].ty, Some(Type::INTEGER));
+        }
+
+        #[test]
+        fn fields_missing_from_the_table_are_documented() {
+            let processor = parse_str(
+                r#"
+---@class Window
+---@field id integer
+---@field title string
+local Window = {
+    id = 0,
+}
+"#,
+            );
+
+            let fields = processor.classes[0].fields();
+
+            assert_eq!(fields.len(), 2);
+            assert_eq!(fields[0].value.as_deref(), Some("0"));
+            assert_eq!(fields[1].ident_type, Type::string_literal("title"));
+            assert_eq!(fields[1].value, None);
+        }
+    }
+
+    mod errors {
+        use super::*;
+
+        use crate::types::Type;
+
+        #[test]
+        fn errors_and_throws_are_collected() {
+            let processor = parse_str(
+                r#"
+---@param path string
+---@return string
+---@error FileNotFound If `path` doesn't exist.
+---@throws string
+function read(path) end
+"#,
+            );
+
+            let errors = &processor.functions[0].errors;
+
+            assert_eq!(errors.len(), 2);
+            assert_eq!(errors[0].0, Type::user_defined("FileNotFound"));
+            assert_eq!(errors[0].1.as_deref(), Some("If `path` doesn't exist."));
+            assert_eq!(errors[1].0.to_string(), "string");
+            assert_eq!(errors[1].1, None);
+        }
+    }
+
+    mod class_enums {
+        use super::*;
+
+        #[test]
+        fn tables_can_be_both_a_class_and_an_enum() {
+            let processor = parse_str(
+                r#"
+---The state of a window.
+---@class State
+---@enum State
+local State = {
+    focused = "focused",
+    unfocused = "unfocused",
+}
+
+---Toggle between states.
+---@param state State
+---@return State
+function State.toggle(state) end
+"#,
+            );
+
+            assert_eq!(processor.classes.len(), 1);
+            assert_eq!(processor.classes[0].name, "State");
+            assert!(processor.classes[0].fields().is_empty());
+
+            assert_eq!(processor.enums.len(), 1);
+            assert_eq!(processor.enums[0].name, "State");
+            assert_eq!(processor.enums[0].fields.len(), 2);
+
+            assert_eq!(processor.functions[0].table.as_deref(), Some("State"));
+        }
+    }
+
+    mod deprecated {
+        use super::*;
+
+        #[test]
+        fn deprecated_classes_fields_and_functions_are_marked() {
+            let processor = parse_str(
+                r#"
+---@class Window
+---@deprecated Use `Surface` instead.
+---@field title string
+---@field id integer
+---@deprecated
+local Window = {}
+
+---@deprecated
+---@param id integer
+function Window.get(id) end
+"#,
+            );
+
+            let class = &processor.classes[0];
+            assert_eq!(class.deprecated.as_deref(), Some("Use `Surface` instead."));
+            assert_eq!(class.lsp_fields[0].deprecated, None);
+            assert_eq!(class.lsp_fields[1].deprecated.as_deref(), Some(""));
+            assert_eq!(processor.functions[0].deprecated.as_deref(), Some(""));
+        }
+
+        #[test]
+        fn annotations_above_a_declaration_belong_to_it() {
+            let processor = parse_str(
+                r#"
+---@deprecated Use `Surface` instead.
+---@since 0.2
+---@badge warning experimental
+---@class Window
+local Window = {}
+
+---@since 0.3
+---@alias Mode "windowed" | "fullscreen"
+
+---@since 0.4
+---@enum State
+local State = { focused = 1 }
+
+---@deprecated
+---@since 0.5
+---@param id integer
+function Window.get(id) end
+"#,
+            );
+
+            let class = &processor.classes[0];
+            assert_eq!(class.deprecated.as_deref(), Some("Use `Surface` instead."));
+            assert_eq!(class.since.as_deref(), Some("0.2"));
+            assert_eq!(
+                class.badges,
+                [("warning".to_string(), "experimental".to_string())]
+            );
+            assert_eq!(processor.aliases[0].since.as_deref(), Some("0.3"));
+            assert_eq!(processor.enums[0].since.as_deref(), Some("0.4"));
+
+            let function = &processor.functions[0];
+            assert_eq!(function.deprecated.as_deref(), Some(""));
+            assert_eq!(function.since.as_deref(), Some("0.5"));
+            assert!(function.badges.is_empty());
+        }
+    }
+
+    mod errors_are_collected {
+        use crate::state::parse_files;
+
+        #[test]
+        fn bad_annotations_are_skipped_and_reported() {
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("window.lua");
+            std::fs::write(
+                &file,
+                r#"
+---@alias Mode
+---| %%
+---| "windowed"
+
+---@type %%
+
+---@class Window
+local Window = {}
+
+---Close the window.
+---@return %%
+---@see
+---@param force boolean
+function Window.close(force) end
+"#,
+            )
+            .unwrap();
+
+            let (processor, files) = parse_files(vec![file.clone()], None).unwrap();
+
+            let errors = &files[0].errors;
+            assert_eq!(
+                errors
+                    .iter()
+                    .map(|error| (error.line, error.annotation.as_str()))
+                    .collect::<Vec<_>>(),
+                [(3, "|"), (6, "@type"), (12, "@return"), (13, "@see")]
+            );
+            assert_eq!(
+                errors[0].to_string(),
+                format!("{}:3: skipping invalid `---|` annotation", file.display())
+            );
+
+            // Everything else is still documented
+            assert_eq!(processor.aliases[0].types.len(), 1);
+            assert_eq!(processor.classes[0].name, "Window");
+            let function = &processor.functions[0];
+            assert_eq!(function.description.as_deref(), Some("Close the window."));
+            assert_eq!(function.params[0].name, "force");
+            assert!(function.returns.is_empty());
+        }
+    }
+
+    mod diagnostics {
+        use miette::Diagnostic;
+
+        use crate::state::parse_files;
+
+        #[test]
+        fn spans_point_at_the_offending_text() {
+            let source = "---@class Window\n---@field title string\n---@return   %% the result\nfunction close() end\n";
+
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("window.lua");
+            std::fs::write(&file, source).unwrap();
+
+            let (_, files) = parse_files(vec![file], None).unwrap();
+            let error = &files[0].errors[0];
+
+            let span = error.span.unwrap();
+            assert!(source[span.offset()..].starts_with("%% the result"));
+            assert_eq!(error.message, "expected single_type");
+
+            assert_eq!(error.help().unwrap().to_string(), "expected single_type");
+            assert_eq!(
+                error.labels().unwrap().next().unwrap().offset(),
+                span.offset()
+            );
+            assert!(error.source_code().is_some());
+        }
+    }
+
+    mod unknown_annotations {
+        use super::super::*;
+
+        use crate::state::{parse_files, parse_str};
+
+        #[test]
+        fn diagnostics_are_discarded() {
+            let source = r#"
+---@class Window
+local Window = {}
+
+---Close the window.
+---@diagnostic disable-next-line: undefined-global
+---@param force boolean
+function Window.close(force) end
+"#;
+
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("window.lua");
+            std::fs::write(&file, source).unwrap();
+
+            let (_, files) = parse_files(vec![file], None).unwrap();
+            assert!(files[0].errors.is_empty());
+
+            let processor = parse_str(source);
+            let close = &processor.functions[0];
+            assert_eq!(close.description.as_deref(), Some("Close the window."));
+            assert_eq!(close.params.len(), 1);
+        }
+
+        #[test]
+        fn diagnostics_dont_detach_defaults() {
+            let processor = parse_str(
+                r#"
+---@param width integer
+---@diagnostic disable-next-line: undefined-global
+---@default 800
+function resize(width) end
+"#,
+            );
+
+            assert_eq!(
+                processor.functions[0].params[0].default.as_deref(),
+                Some("800")
+            );
+        }
+
+        #[test]
+        fn known_tags_are_parsed() {
+            for (tag, expected) in KNOWN_TAGS {
+                let (annotation, _) = try_parse_annotation(&format!("@{tag} x")).unwrap();
+                assert_eq!(annotation, *expected, "`@{tag}`");
+            }
+        }
+
+        #[test]
+        fn typos_are_reported_with_suggestions() {
+            let source = "---@diagnostic disable-next-line\n---@parm width integer\n---@retrun integer\n---@zzzzzz\nfunction resize(width) end\n";
+
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("window.lua");
+            std::fs::write(&file, source).unwrap();
+
+            let (_, files) = parse_files(vec![file], None).unwrap();
+            let errors = &files[0].errors;
+
+            assert_eq!(
+                errors
+                    .iter()
+                    .map(|error| (
+                        error.line,
+                        error.annotation.as_str(),
+                        error.message.as_str()
+                    ))
+                    .collect::<Vec<_>>(),
+                [
+                    (2, "@parm", "did you mean `@param`?"),
+                    (3, "@retrun", "did you mean `@return`?"),
+                    (
+                        4,
+                        "@zzzzzz",
+                        "lcat doesn't know this annotation and ignores it"
+                    ),
+                ]
+            );
+            assert!(errors
+                .iter()
+                .all(|error| error.kind == ProcessErrorKind::UnknownAnnotation));
+
+            let span = errors[0].span.unwrap();
+            assert_eq!(&source[span.offset()..span.offset() + span.len()], "parm");
+        }
+
+        #[test]
+        fn levenshtein_distance() {
+            assert_eq!(levenshtein("parm", "param"), 1);
+            assert_eq!(levenshtein("retrun", "return"), 2);
+            assert_eq!(levenshtein("", "abc"), 3);
+            assert_eq!(levenshtein("class", "class"), 0);
+        }
+    }
+
+    mod merge_classes {
+        use crate::state::parse_files;
+
+        #[test]
+        fn classes_split_across_files_are_merged() {
+            let dir = tempfile::tempdir().unwrap();
+            let window = dir.path().join("window.lua");
+            let extra = dir.path().join("extra.lua");
+            std::fs::write(
+                &window,
+                "---@class Window : Widget\n---@field title string\nlocal Window = {}\n",
+            )
+            .unwrap();
+            std::fs::write(
+                &extra,
+                "---A window on screen.\n---@class Window\n---@field width integer\nlocal Window = {}\n\n---Close it.\nfunction Window.close() end\n",
+            )
+            .unwrap();
+
+            let (processor, _) = parse_files(vec![window, extra], None).unwrap();
+
+            assert_eq!(processor.classes.len(), 1);
+
+            let class = &processor.classes[0];
+            assert_eq!(class.description.as_deref(), Some("A window on screen."));
+            assert_eq!(class.parent.as_ref().unwrap().to_string(), "Widget");
+            assert_eq!(
+                class
+                    .fields()
+                    .iter()
+                    .map(|field| field.ident_type.format_as_table_field_name())
+                    .collect::<Vec<_>>(),
+                ["title", "width"]
+            );
+            assert_eq!(processor.functions[0].table.as_deref(), Some("Window"));
+        }
+    }
+
+    mod declaration_order {
+        use crate::state::parse_str;
+
+        const SOURCE: &str = r#"
+---@class Outer
+local Outer = {
+    ---@class Inner
+    inner = {
+        ---Nested.
+        nested = function() end,
+    },
+}
+
+---First.
+function Outer.first() end
+
+---@class Last
+local Last = {}
+
+---Second.
+function Outer.second() end
+"#;
+
+        #[test]
+        fn symbols_are_in_source_order() {
+            let processor = parse_str(SOURCE);
+
+            assert_eq!(
+                processor
+                    .classes
+                    .iter()
+                    .map(|class| class.name.as_str())
+                    .collect::<Vec<_>>(),
+                ["Outer", "Inner", "Last"]
+            );
+            assert_eq!(
+                processor
+                    .functions
+                    .iter()
+                    .map(|function| function.name.as_str())
+                    .collect::<Vec<_>>(),
+                ["nested", "first", "second"]
+            );
+        }
+
+        #[test]
+        fn order_is_stable() {
+            let names = |processor: crate::processor::Processor| {
+                processor
+                    .classes
+                    .into_iter()
+                    .map(|class| (class.name, class.order))
+                    .collect::<Vec<_>>()
+            };
+
+            assert_eq!(names(parse_str(SOURCE)), names(parse_str(SOURCE)));
+        }
+    }
+
+    mod type_annotations {
+        use crate::{state::parse_str, types::Type};
+
+        #[test]
+        fn only_the_first_of_multiple_types_is_documented() {
+            let processor = parse_str(
+                r#"
+---@class Window
+local Window = {
+    ---@type string, integer
+    title = "untitled",
+}
+"#,
+            );
+
+            let fields = processor.classes[0].fields();
+            assert_eq!(fields[0].ty, Some(Type::STRING));
+        }
+    }
+
+    mod multiple_assignments {
+        use crate::state::parse_files;
+
+        #[test]
+        fn every_target_gets_a_block() {
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("multiple.lua");
+            std::fs::write(
+                &file,
+                r#"
+---@class Point
+local Point, distance = {
+    ---The horizontal position.
+    x = 0,
+}, function(a, b) end
+
+---Move a point.
+function Point.move() end
+
+local M = {}
+
+---@return nil
+M.reset, M.clear = function() end, function(force) end
+"#,
+            )
+            .unwrap();
+
+            let (processor, _) = parse_files(vec![file], None).unwrap();
+
+            assert_eq!(processor.classes.len(), 1);
+            assert_eq!(processor.classes[0].name, "Point");
+            assert_eq!(processor.classes[0].fields().len(), 1);
+
+            assert_eq!(
+                processor
+                    .functions
+                    .iter()
+                    .map(|function| (function.table.as_deref(), function.name.as_str()))
+                    .collect::<Vec<_>>(),
+                [
+                    (None, "distance"),
+                    (Some("Point"), "move"),
+                    (Some("M"), "reset"),
+                    (Some("M"), "clear"),
+                ]
+            );
+            assert_eq!(processor.functions[2].returns.len(), 1);
+            assert!(processor.functions[3].returns.is_empty());
+        }
+    }
+
+    mod piped_aliases {
+        use super::*;
+
+        #[test]
+        fn piped_lines_accept_any_type() {
+            let processor = parse_str(
+                r#"
+---@alias Callback
+---| "none" # No callback
+---| 42 The answer
+---| fun(): string Returns a string
+---| fun(x: integer): boolean, string
+---| { x: number, y: number } # A point
+"#,
+            );
+
+            let types = processor.aliases[0]
+                .types
+                .iter()
+                .map(|(ty, desc)| (ty.to_string(), desc.as_deref()))
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                types,
+                [
+                    (r#""none""#.to_string(), Some("No callback")),
+                    ("42".to_string(), Some("The answer")),
+                    ("fun(): string".to_string(), Some("Returns a string")),
+                    ("fun(x: integer): boolean, string".to_string(), None),
+                    ("{ x: number, y: number }".to_string(), Some("A point")),
+                ]
+            );
+        }
+
+        #[test]
+        fn aliased_types_keep_source_order() {
+            let processor = parse_str(
+                r#"
+---@alias Mode "c" | "a" | "b"
+---| "z"
+---| "d" # D
+"#,
+            );
+
+            let types = processor.aliases[0]
+                .types
+                .iter()
+                .map(|(ty, _)| ty.to_string())
+                .collect::<Vec<_>>();
+
+            assert_eq!(types, [r#""c" | "a" | "b""#, r#""z""#, r#""d""#]);
+        }
+    }
+
+    mod multiline_descriptions {
+        use super::*;
+
+        #[test]
+        fn lines_after_params_and_returns_continue_their_descriptions() {
+            let processor = parse_str(
+                r#"
+---Open a window.
+---@param title string The title,
+---  shown in the title bar
+---@param width integer
+---@return Window win The window,
+---or nil if it couldn't be opened
+function open(title, width) end
+"#,
+            );
+
+            let func = &processor.functions[0];
+            assert_eq!(func.description.as_deref(), Some("Open a window."));
+            assert_eq!(
+                func.params[0].description.as_deref(),
+                Some("The title,\nshown in the title bar")
+            );
+            assert_eq!(func.params[1].description, None);
+            assert_eq!(
+                func.returns[0].description.as_deref(),
+                Some("The window,\nor nil if it couldn't be opened")
+            );
+        }
+
+        #[test]
+        fn descriptions_can_start_on_the_next_line() {
+            let processor = parse_str(
+                r#"
+---@param callback fun()
+---Called once the window closes
+function on_close(callback) end
+"#,
+            );
+
+            assert_eq!(
+                processor.functions[0].params[0].description.as_deref(),
+                Some("Called once the window closes")
+            );
+        }
+
+        #[test]
+        fn empty_lines_end_descriptions() {
+            let processor = parse_str(
+                r#"
+---@param x integer The value
+---
+---Not about x
+---@param y integer
+---@default 2
+function f(x, y) end
+"#,
+            );
+
+            let func = &processor.functions[0];
+            assert_eq!(func.params[0].description.as_deref(), Some("The value"));
+            assert_eq!(func.params[1].default.as_deref(), Some("2"));
+        }
+    }
+
+    mod badges {
+        use super::*;
+
+        use crate::state::parse_files;
+
+        #[test]
+        fn badges_attach_to_classes_and_functions() {
+            let processor = parse_str(
+                r#"
+---@class Window
+---@badge warning experimental
+---@badge info server-only
+local Window = {}
+
+---@badge danger unstable API
+---@param force boolean
+function Window.close(force) end
+"#,
+            );
+
+            assert_eq!(
+                processor.classes[0].badges,
+                [
+                    ("warning".to_string(), "experimental".to_string()),
+                    ("info".to_string(), "server-only".to_string()),
+                ]
+            );
+            assert_eq!(
+                processor.functions[0].badges,
+                [("danger".to_string(), "unstable API".to_string())]
+            );
+        }
+
+        #[test]
+        fn unknown_badge_types_are_skipped_and_reported() {
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("window.lua");
+            std::fs::write(
+                &file,
+                r#"
+---@badge red experimental
+---@badge tip
+---@return nil
+function close() end
+"#,
+            )
+            .unwrap();
+
+            let (processor, files) = parse_files(vec![file], None).unwrap();
+
+            assert!(processor.functions[0].badges.is_empty());
+            assert_eq!(
+                files[0]
+                    .errors
+                    .iter()
+                    .map(|error| (error.line, error.message.as_str()))
+                    .collect::<Vec<_>>(),
+                [
+                    (
+                        2,
+                        "badge type `red` isn't one of `info`, `tip`, `warning`, `danger`"
+                    ),
+                    (3, "expected the text of the badge after its type"),
+                ]
+            );
+        }
+    }
+
+    mod nested_fields {
+        use super::*;
+
+        const CONFIG: &str = r#"
+---@class Config
+local config = {
+    ---Keys bound in the picker.
+    keybinds = {
+        ---Close the picker
+        quit = "q",
+        save = "<C-s>", -- Save the selection
+        movement = {
+            up = "k",
+        },
+        on_open = function() end,
+    },
+    gaps = { 1, 2, 3 },
+}
+"#;
+
+        #[test]
+        fn table_fields_keep_their_own_fields() {
+            let processor = parse_str(CONFIG);
+            let keybinds = &processor.classes[0].ts_fields[0];
+
+            let fields = keybinds
+                .fields
+                .iter()
+                .map(|field| {
+                    (
+                        field.name.as_ref().unwrap().to_string(),
+                        field.description.as_deref(),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                fields,
+                [
+                    ("quit".to_string(), Some("Close the picker")),
+                    ("save".to_string(), Some("Save the selection")),
+                    ("movement".to_string(), None),
+                ]
+            );
+            assert_eq!(keybinds.fields[2].fields[0].value, r#""k""#);
+        }
+
+        #[test]
+        fn lists_and_functions_are_not_nested_fields() {
+            let processor = parse_str(CONFIG);
+            let class = &processor.classes[0];
+
+            assert!(class.ts_fields[1].fields.is_empty());
+            assert!(processor.functions.is_empty());
+        }
+    }
+}
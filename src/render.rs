@@ -1,9 +1,735 @@
+pub mod html;
+pub mod json;
+pub mod markdown;
+pub mod search;
+pub mod vimdoc;
 pub mod vitepress;
 
-use crate::processor::Processor;
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
 
+use similar::TextDiff;
+
+use crate::{
+    annotation::{Alias, Class, Enum, Function},
+    processor::Processor,
+    types::Type,
+};
+
+/// The format documentation is generated in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// A VitePress site with a page per class, alias, and enum
+    #[default]
+    Vitepress,
+    /// A single Neovim `:help` file
+    Vimdoc,
+    /// A single `lcat.json` file with everything lcat found, for other tools to consume
+    Json,
+    /// Plain GitHub-flavored Markdown pages without VitePress components or HTML
+    Markdown,
+    /// A static HTML site with embedded CSS that can be opened straight from disk
+    Html,
+}
+
+/// The encoding generated files are written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputEncoding {
+    /// UTF-8
+    #[default]
+    Utf8,
+    /// UTF-8 with a leading byte order mark, for tools that need one to detect UTF-8
+    Utf8Bom,
+}
+
+impl OutputEncoding {
+    const BOM: &'static str = "\u{feff}";
+
+    /// Encode the contents of a generated file.
+    pub fn encode(self, contents: &str) -> Vec<u8> {
+        match self {
+            OutputEncoding::Utf8 => contents.as_bytes().to_vec(),
+            OutputEncoding::Utf8Bom => format!("{}{contents}", Self::BOM).into_bytes(),
+        }
+    }
+}
+
+/// Give generated file contents LF line endings and exactly one trailing newline,
+/// regardless of the line endings of the Lua sources they came from.
+pub fn normalize_line_endings(contents: &str) -> String {
+    let mut contents = contents.replace("\r\n", "\n").replace('\r', "\n");
+    contents.truncate(contents.trim_end_matches('\n').len());
+    contents.push('\n');
+    contents
+}
+
+/// How many files a render wrote, and how many it left alone because their contents
+/// were already up to date.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteStats {
+    pub changed: usize,
+    pub unchanged: usize,
+}
+
+impl WriteStats {
+    fn record(&mut self, written: bool) {
+        if written {
+            self.changed += 1;
+        } else {
+            self.unchanged += 1;
+        }
+    }
+}
+
+/// Write `contents` to `path` unless the file already holds exactly those bytes,
+/// so unchanged files keep their modification times.
+///
+/// Returns whether the file was written.
+pub fn write_if_changed(path: &Path, contents: &[u8]) -> bool {
+    if std::fs::read(path).is_ok_and(|existing| existing == contents) {
+        return false;
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(path, contents).unwrap();
+    true
+}
+
+/// Write rendered pages into `out_dir`, skipping the ones that haven't changed.
+pub fn write_pages(
+    out_dir: &Path,
+    pages: &[(PathBuf, String)],
+    encoding: OutputEncoding,
+) -> WriteStats {
+    let mut stats = WriteStats::default();
+
+    for (path, contents) in pages {
+        stats.record(write_if_changed(
+            &out_dir.join(path),
+            &encoding.encode(contents),
+        ));
+    }
+
+    stats
+}
+
+/// Renders processed documentation into pages in an output directory.
+///
+/// Pages are rendered in memory first, so the same pages can be written, listed with
+/// `--dry-run`, or diffed with `--diff`.
 pub trait Renderer {
-    type Output;
+    /// The directory pages are written to.
+    fn out_dir(&self) -> &Path;
+
+    /// The encoding pages are written in.
+    fn encoding(&self) -> OutputEncoding;
+
+    /// Render every page, returning each page's path relative to the output directory
+    /// along with its contents.
+    fn render_pages(&self, processor: Processor) -> Vec<(PathBuf, String)>;
+
+    /// Write every page, deleting the pages the previous run generated that weren't
+    /// rendered this time.
+    fn render(&self, processor: Processor) -> WriteStats {
+        write_generated_pages(
+            self.out_dir(),
+            &self.render_pages(processor),
+            self.encoding(),
+        )
+    }
+
+    /// Render every page in memory, returning the paths in the output directory that
+    /// rendering would write.
+    fn dry_run(&self, processor: Processor) -> Vec<PathBuf> {
+        self.render_pages(processor)
+            .into_iter()
+            .map(|(path, _)| self.out_dir().join(path))
+            .collect()
+    }
+
+    /// Render every page in memory and diff it against the existing output directory.
+    ///
+    /// Returns a unified diff, which is empty if the documentation is up to date.
+    fn diff(&self, processor: Processor) -> String {
+        diff_generated_pages(self.out_dir(), &self.render_pages(processor))
+    }
+}
+
+/// Remove the indentation a multi-line expression's lines after the first share, which
+/// it had in the source but its first line, starting mid-line, doesn't.
+pub(crate) fn dedent_continuation_lines(value: &str) -> String {
+    let mut lines = value.lines();
+    let first = lines.next().unwrap_or_default();
+    let rest = lines.collect::<Vec<_>>();
+
+    let indent = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or_default();
+
+    std::iter::once(first)
+        .chain(
+            rest.iter()
+                .map(|line| line.get(indent..).unwrap_or_default()),
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Escape text for use in HTML content and quoted attributes.
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            ch => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
+
+/// The backticks to fence `code` with: one more than its longest run of backticks, and
+/// at least three, so the code can't end the block early.
+pub(crate) fn code_fence(code: &str) -> String {
+    let longest_run = code
+        .split(|ch| ch != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+
+    "`".repeat((longest_run + 1).max(3))
+}
+
+/// A one-line stand-in for a multi-line value, like `function(button) ... end` for a
+/// function or `{ ... }` for a table, for places a code block can't go.
+pub(crate) fn value_placeholder(value: &str) -> String {
+    let first = value.lines().next().unwrap_or_default().trim_end();
+
+    if first.starts_with("function") {
+        format!("{first} ... end")
+    } else if first.starts_with('{') {
+        "{ ... }".to_string()
+    } else {
+        format!("{first} ...")
+    }
+}
+
+/// Marks the start of a linkable type name in a formatted type.
+const NAME_START: char = '\u{1}';
+/// Marks the end of a linkable type name in a formatted type.
+const NAME_END: char = '\u{2}';
+
+/// Links from a page to the pages of documented types.
+pub(crate) struct Links<'a> {
+    type_pages: &'a HashMap<String, String>,
+    /// The path from the linking page to the output directory, like `../`.
+    prefix: &'a str,
+}
+
+impl<'a> Links<'a> {
+    pub(crate) fn new(type_pages: &'a HashMap<String, String>, prefix: &'a str) -> Self {
+        Self { type_pages, prefix }
+    }
+
+    /// Format a type, passing each documented type name and the link to its page to
+    /// `link` and the text around them to `text`.
+    pub(crate) fn format_type(
+        &self,
+        ty: &Type,
+        text: impl Fn(&str) -> String,
+        link: impl Fn(&str, &str) -> String,
+    ) -> String {
+        let marked = ty.format_nullable(ty.format_with_names(&|name| {
+            if self.type_pages.contains_key(name) {
+                format!("{NAME_START}{name}{NAME_END}")
+            } else {
+                name.to_string()
+            }
+        }));
+
+        marked
+            .split([NAME_START, NAME_END])
+            .enumerate()
+            .map(|(i, part)| {
+                if i % 2 == 1 {
+                    link(part, &format!("{}{}", self.prefix, self.type_pages[part]))
+                } else {
+                    text(part)
+                }
+            })
+            .collect()
+    }
+
+    /// The link to the page of the type a `@see` reference starts with, if documented.
+    pub(crate) fn see_link(&self, ident: &str) -> Option<String> {
+        let belonging_type = ident.split(['.', ':']).next().unwrap_or(ident);
+
+        self.type_pages
+            .get(belonging_type)
+            .map(|page| format!("{}{page}", self.prefix))
+    }
+}
+
+/// A format with a page per class, alias, and enum and an index page listing them,
+/// which lists functions not belonging to a class itself.
+pub(crate) trait PageFormat {
+    /// The extension of pages, like `md`.
+    const EXTENSION: &'static str;
+    /// The path of the index relative to the output directory.
+    const INDEX: &'static str;
+
+    fn generate_class(class: &Class, functions: &[Function], links: &Links) -> String;
+    fn generate_alias(alias: &Alias, links: &Links) -> String;
+    fn generate_enum(en: &Enum) -> String;
+    fn generate_function(func: &Function, links: &Links) -> String;
+
+    /// A heading on the index, whose title is the only heading of level 1.
+    fn index_heading(level: usize, text: &str) -> String;
+
+    /// A list linking to pages from the index, given each page's name and path.
+    fn index_list(entries: &[(&str, &str)]) -> String;
+
+    /// Wrap the body of a page in a complete document, where `prefix` is the path from
+    /// the page to the output directory.
+    fn page(title: &str, prefix: &str, body: String) -> String;
+}
+
+/// Render a page per class, alias, and enum and an index, returning each page's path
+/// relative to the output directory along with its contents.
+pub(crate) fn render_type_pages<F: PageFormat>(processor: Processor) -> Vec<(PathBuf, String)> {
+    let Processor {
+        classes,
+        aliases,
+        mut functions,
+        enums,
+        ..
+    } = processor;
+
+    let page_path = |dir: &str, name: &str| format!("{dir}/{name}.{}", F::EXTENSION);
+
+    // Type names mapped to their pages relative to the output directory
+    let type_pages = classes
+        .iter()
+        .map(|class| (class.name.clone(), page_path("classes", &class.name)))
+        .chain(
+            aliases
+                .iter()
+                .map(|alias| (alias.name.clone(), page_path("aliases", &alias.name))),
+        )
+        .chain(
+            enums
+                .iter()
+                .map(|en| (en.name.clone(), page_path("enums", &en.name))),
+        )
+        .collect::<HashMap<_, _>>();
+
+    // Pages are one directory below the index
+    let page_links = Links::new(&type_pages, "../");
+    let index_links = Links::new(&type_pages, "");
+
+    let mut pages = Vec::new();
+    let mut index = F::index_heading(1, "API Reference");
+
+    let sections = [
+        (
+            "Classes",
+            classes.iter().map(|class| &class.name).collect::<Vec<_>>(),
+        ),
+        ("Aliases", aliases.iter().map(|alias| &alias.name).collect()),
+        ("Enums", enums.iter().map(|en| &en.name).collect()),
+    ];
+
+    for (heading, names) in sections.iter().filter(|(_, names)| !names.is_empty()) {
+        let entries = names
+            .iter()
+            .map(|name| (name.as_str(), type_pages[*name].as_str()))
+            .collect::<Vec<_>>();
+
+        index.push_str(&F::index_heading(2, heading));
+        index.push_str(&F::index_list(&entries));
+    }
+
+    for class in classes.iter() {
+        let mut class_functions = Vec::new();
+        functions.retain(|func| {
+            if func.table.as_ref() == Some(&class.name) {
+                class_functions.push(func.clone());
+                false
+            } else {
+                true
+            }
+        });
+
+        pages.push((
+            PathBuf::from(&type_pages[&class.name]),
+            F::page(
+                &class.name,
+                "../",
+                F::generate_class(class, &class_functions, &page_links),
+            ),
+        ));
+    }
+
+    for alias in aliases.iter() {
+        pages.push((
+            PathBuf::from(&type_pages[&alias.name]),
+            F::page(&alias.name, "../", F::generate_alias(alias, &page_links)),
+        ));
+    }
+
+    for en in enums.iter() {
+        pages.push((
+            PathBuf::from(&type_pages[&en.name]),
+            F::page(&en.name, "../", F::generate_enum(en)),
+        ));
+    }
+
+    // Functions not belonging to a class are listed on the index itself
+    if !functions.is_empty() {
+        index.push_str(&F::index_heading(2, "Functions"));
+    }
+
+    for func in functions.iter() {
+        index.push_str(&F::generate_function(func, &index_links));
+    }
+
+    pages.push((PathBuf::from(F::INDEX), F::page("API Reference", "", index)));
+
+    pages
+        .into_iter()
+        .map(|(path, contents)| (path, normalize_line_endings(&contents)))
+        .collect()
+}
+
+/// The file in the output directory listing the files lcat generated there, so later
+/// runs only delete their own output.
+pub const MANIFEST_FILE: &str = ".lcat-manifest";
+
+/// Read the paths of the files the previous run generated, relative to `out_dir`.
+///
+/// Returns nothing if there's no manifest, like for output from before manifests existed.
+/// Entries that would point outside `out_dir`, like `../notes.txt`, are skipped.
+pub fn read_manifest(out_dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_to_string(out_dir.join(MANIFEST_FILE))
+        .map(|manifest| {
+            manifest
+                .lines()
+                .map(PathBuf::from)
+                .filter(|path| is_inside(path))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether `path` is a relative path that stays inside the directory it's joined to.
+fn is_inside(path: &Path) -> bool {
+    path.components().next().is_some()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// Record the paths of generated files, relative to `out_dir`, one per line.
+pub fn write_manifest(out_dir: &Path, paths: &[PathBuf]) {
+    let mut lines = paths
+        .iter()
+        .map(|path| path.display().to_string().replace('\\', "/"))
+        .collect::<Vec<_>>();
+    lines.sort();
+
+    write_if_changed(
+        &out_dir.join(MANIFEST_FILE),
+        (lines.join("\n") + "\n").as_bytes(),
+    );
+}
+
+/// Delete the files in `previous` that are no longer generated, along with the directories
+/// that leaves empty. Files lcat didn't generate are left alone.
+pub fn remove_stale_files(out_dir: &Path, previous: &[PathBuf], generated: &[PathBuf]) {
+    for path in previous
+        .iter()
+        .filter(|path| is_inside(path) && !generated.contains(path))
+    {
+        if std::fs::remove_file(out_dir.join(path)).is_err() {
+            continue;
+        }
+
+        for dir in path.ancestors().skip(1) {
+            if dir.as_os_str().is_empty() || std::fs::remove_dir(out_dir.join(dir)).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Write rendered pages into `out_dir` and record them in the manifest, deleting the
+/// pages the previous run generated that weren't rendered this time.
+///
+/// Only pages from the previous run are deleted, so hand-written pages survive.
+pub fn write_generated_pages(
+    out_dir: &Path,
+    pages: &[(PathBuf, String)],
+    encoding: OutputEncoding,
+) -> WriteStats {
+    let generated = pages
+        .iter()
+        .map(|(path, _)| path.clone())
+        .collect::<Vec<_>>();
+
+    remove_stale_files(out_dir, &read_manifest(out_dir), &generated);
+
+    let stats = write_pages(out_dir, pages, encoding);
+    write_manifest(out_dir, &generated);
+    stats
+}
+
+/// Diff rendered pages against the ones already in `out_dir`, where the pages the
+/// previous run generated that weren't rendered count as removed.
+pub fn diff_generated_pages(out_dir: &Path, pages: &[(PathBuf, String)]) -> String {
+    diff_pages(out_dir, pages, &read_manifest(out_dir))
+}
+
+/// Diff rendered pages against the ones already in `out_dir`, returning a unified diff
+/// of every added, removed, or modified page, or an empty string if nothing changed.
+///
+/// The `previous` pages that weren't rendered count as removed, as rendering would
+/// delete them.
+pub fn diff_pages(out_dir: &Path, pages: &[(PathBuf, String)], previous: &[PathBuf]) -> String {
+    let mut paths = pages
+        .iter()
+        .map(|(path, _)| path.clone())
+        .collect::<Vec<_>>();
+
+    for path in previous {
+        if !paths.contains(path) {
+            paths.push(path.clone());
+        }
+    }
+
+    paths.sort();
+
+    let mut diff = String::new();
+
+    for path in paths {
+        let old = std::fs::read_to_string(out_dir.join(&path))
+            .ok()
+            .map(|old| old.trim_start_matches(OutputEncoding::BOM).to_string());
+        let new = pages
+            .iter()
+            .find(|(page, _)| page == &path)
+            .map(|(_, contents)| contents.as_str());
+
+        if old.as_deref() == new {
+            continue;
+        }
+
+        let display = path.display().to_string().replace('\\', "/");
+        let old_header = match old {
+            Some(_) => format!("a/{display}"),
+            None => "/dev/null".to_string(),
+        };
+        let new_header = match new {
+            Some(_) => format!("b/{display}"),
+            None => "/dev/null".to_string(),
+        };
+
+        diff.push_str(
+            &TextDiff::from_lines(old.as_deref().unwrap_or_default(), new.unwrap_or_default())
+                .unified_diff()
+                .header(&old_header, &new_header)
+                .to_string(),
+        );
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod output {
+        use super::*;
+
+        #[test]
+        fn line_endings_are_normalized() {
+            assert_eq!(normalize_line_endings("a\r\nb\rc"), "a\nb\nc\n");
+            assert_eq!(normalize_line_endings("a\n\n\n"), "a\n");
+            assert_eq!(normalize_line_endings("a\r\n\r\n"), "a\n");
+        }
+
+        #[test]
+        fn bom_is_prepended() {
+            assert_eq!(OutputEncoding::Utf8.encode("a"), b"a");
+            assert_eq!(OutputEncoding::Utf8Bom.encode("a"), b"\xef\xbb\xbfa");
+        }
+    }
+
+    mod write {
+        use super::*;
+
+        use std::time::{Duration, SystemTime};
+
+        #[test]
+        fn only_changed_pages_are_written() {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::create_dir_all(dir.path().join("classes")).unwrap();
+            std::fs::write(dir.path().join("classes/Same.md"), "same\n").unwrap();
+            std::fs::write(dir.path().join("classes/Changed.md"), "old\n").unwrap();
+
+            let long_ago = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+            std::fs::File::options()
+                .write(true)
+                .open(dir.path().join("classes/Same.md"))
+                .unwrap()
+                .set_modified(long_ago)
+                .unwrap();
+
+            let pages = [
+                (PathBuf::from("classes/Same.md"), "same\n".to_string()),
+                (PathBuf::from("classes/Changed.md"), "new\n".to_string()),
+                (PathBuf::from("classes/Added.md"), "added\n".to_string()),
+            ];
+
+            assert_eq!(
+                write_pages(dir.path(), &pages, OutputEncoding::Utf8),
+                WriteStats {
+                    changed: 2,
+                    unchanged: 1
+                }
+            );
+            assert_eq!(
+                std::fs::metadata(dir.path().join("classes/Same.md"))
+                    .unwrap()
+                    .modified()
+                    .unwrap(),
+                long_ago
+            );
+            assert_eq!(
+                std::fs::read_to_string(dir.path().join("classes/Changed.md")).unwrap(),
+                "new\n"
+            );
+            assert_eq!(
+                std::fs::read_to_string(dir.path().join("classes/Added.md")).unwrap(),
+                "added\n"
+            );
+        }
+
+        #[test]
+        fn changing_the_encoding_rewrites_pages() {
+            let dir = tempfile::tempdir().unwrap();
+            let pages = [(PathBuf::from("lcat.txt"), "help\n".to_string())];
+
+            write_pages(dir.path(), &pages, OutputEncoding::Utf8);
+
+            assert_eq!(
+                write_pages(dir.path(), &pages, OutputEncoding::Utf8Bom),
+                WriteStats {
+                    changed: 1,
+                    unchanged: 0
+                }
+            );
+        }
+    }
+
+    mod manifest {
+        use super::*;
+
+        #[test]
+        fn entries_outside_the_output_directory_are_skipped() {
+            let dir = tempfile::tempdir().unwrap();
+            let out_dir = dir.path().join("out");
+            std::fs::create_dir_all(out_dir.join("classes")).unwrap();
+            std::fs::write(dir.path().join("victim.txt"), "keep\n").unwrap();
+            std::fs::write(out_dir.join("classes/Old.md"), "old\n").unwrap();
+            std::fs::write(
+                out_dir.join(MANIFEST_FILE),
+                format!(
+                    "../victim.txt\nclasses/../../victim.txt\n{}\nclasses/Old.md\n",
+                    dir.path().join("victim.txt").display()
+                ),
+            )
+            .unwrap();
+
+            assert_eq!(read_manifest(&out_dir), [PathBuf::from("classes/Old.md")]);
+
+            write_generated_pages(&out_dir, &[], OutputEncoding::Utf8);
+
+            assert!(dir.path().join("victim.txt").exists());
+            assert!(!out_dir.join("classes").exists());
+        }
+
+        #[test]
+        fn stale_files_outside_the_output_directory_are_left_alone() {
+            let dir = tempfile::tempdir().unwrap();
+            let out_dir = dir.path().join("out");
+            std::fs::create_dir_all(&out_dir).unwrap();
+            std::fs::write(dir.path().join("victim.txt"), "keep\n").unwrap();
+
+            remove_stale_files(&out_dir, &[PathBuf::from("../victim.txt")], &[]);
+
+            assert!(dir.path().join("victim.txt").exists());
+        }
+    }
+
+    mod diff {
+        use super::*;
+
+        #[test]
+        fn changed_added_and_removed_pages_are_diffed() {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::create_dir_all(dir.path().join("classes")).unwrap();
+            std::fs::write(dir.path().join("classes/Same.md"), "same\n").unwrap();
+            std::fs::write(dir.path().join("classes/Changed.md"), "old\n").unwrap();
+            std::fs::write(dir.path().join("classes/Removed.md"), "gone\n").unwrap();
+
+            let pages = [
+                (PathBuf::from("classes/Same.md"), "same\n".to_string()),
+                (PathBuf::from("classes/Changed.md"), "new\n".to_string()),
+                (PathBuf::from("classes/Added.md"), "added\n".to_string()),
+            ];
+
+            assert_eq!(
+                diff_pages(dir.path(), &pages, &[PathBuf::from("classes/Removed.md")]),
+                "--- /dev/null\n+++ b/classes/Added.md\n@@ -0,0 +1 @@\n+added\n\
+                --- a/classes/Changed.md\n+++ b/classes/Changed.md\n@@ -1 +1 @@\n-old\n+new\n\
+                --- a/classes/Removed.md\n+++ /dev/null\n@@ -1 +0,0 @@\n-gone\n"
+            );
+        }
+
+        #[test]
+        fn only_previously_generated_pages_count_as_removed() {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::create_dir_all(dir.path().join("classes")).unwrap();
+            std::fs::write(dir.path().join("classes/Old.md"), "old\n").unwrap();
+            std::fs::write(dir.path().join("classes/custom.md"), "mine\n").unwrap();
+
+            assert_eq!(
+                diff_pages(dir.path(), &[], &[PathBuf::from("classes/Old.md")]),
+                "--- a/classes/Old.md\n+++ /dev/null\n@@ -1 +0,0 @@\n-old\n"
+            );
+        }
+
+        #[test]
+        fn unchanged_pages_have_no_diff() {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::write(dir.path().join("lcat.txt"), "help\n").unwrap();
+
+            let pages = [(PathBuf::from("lcat.txt"), "help\n".to_string())];
 
-    fn render(&mut self, processor: Processor) -> Self::Output;
+            assert_eq!(diff_pages(dir.path(), &pages, &[]), "");
+        }
+    }
 }
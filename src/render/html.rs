@@ -0,0 +1,754 @@
+use std::path::{Path, PathBuf};
+
+use markdown::Options;
+
+use crate::{
+    annotation::{Alias, Class, Enum, Function},
+    processor::Processor,
+    treesitter::FieldName,
+    types::Type,
+};
+
+use super::{
+    dedent_continuation_lines, escape_html, render_type_pages, Links, OutputEncoding, PageFormat,
+    Renderer,
+};
+
+/// The stylesheet embedded in every page, so pages need nothing but each other.
+const STYLE: &str = "\
+body { max-width: 52rem; margin: 0 auto; padding: 1rem 1.5rem 4rem; font-family: system-ui, sans-serif; line-height: 1.6; color: #1f2328; }
+a { color: #0969da; text-decoration: none; }
+a:hover { text-decoration: underline; }
+nav { margin-bottom: 2rem; font-size: 0.9rem; }
+h1, h2, h3, h4 { line-height: 1.25; }
+h2 { margin-top: 2.5rem; padding-bottom: 0.3rem; border-bottom: 1px solid #d1d9e0; }
+h3 { margin-top: 2rem; }
+code { font-family: ui-monospace, monospace; font-size: 0.9em; background: #f6f8fa; padding: 0.1em 0.3em; border-radius: 4px; }
+pre { background: #f6f8fa; padding: 0.8rem 1rem; border-radius: 6px; overflow-x: auto; }
+pre code { padding: 0; background: none; }
+.deprecated { border-left: 4px solid #cf222e; padding: 0.2rem 1rem; background: #ffebe9; }
+";
+
+/// Renders a static HTML site with embedded CSS and relative links, which can be opened
+/// straight from disk without a build step.
+///
+/// Pages link to each other with relative `.html` paths, and an index is written to
+/// `index.html`.
+pub struct HtmlRenderer {
+    out_dir: PathBuf,
+    output_encoding: OutputEncoding,
+}
+
+impl HtmlRenderer {
+    pub fn new(out_dir: PathBuf) -> Self {
+        Self {
+            out_dir,
+            output_encoding: OutputEncoding::default(),
+        }
+    }
+
+    /// Set the encoding pages are written in.
+    pub fn output_encoding(mut self, output_encoding: OutputEncoding) -> Self {
+        self.output_encoding = output_encoding;
+        self
+    }
+}
+
+impl Renderer for HtmlRenderer {
+    fn out_dir(&self) -> &Path {
+        &self.out_dir
+    }
+
+    fn encoding(&self) -> OutputEncoding {
+        self.output_encoding
+    }
+
+    fn render_pages(&self, processor: Processor) -> Vec<(PathBuf, String)> {
+        render_type_pages::<Html>(processor)
+    }
+}
+
+/// Standalone HTML pages, with an index in `index.html`.
+struct Html;
+
+impl PageFormat for Html {
+    const EXTENSION: &'static str = "html";
+    const INDEX: &'static str = "index.html";
+
+    fn generate_class(class: &Class, functions: &[Function], links: &Links) -> String {
+        generate_class(class, functions, links)
+    }
+
+    fn generate_alias(alias: &Alias, links: &Links) -> String {
+        generate_alias(alias, links)
+    }
+
+    fn generate_enum(en: &Enum) -> String {
+        generate_enum(en)
+    }
+
+    fn generate_function(func: &Function, links: &Links) -> String {
+        generate_function(func, links)
+    }
+
+    fn index_heading(level: usize, text: &str) -> String {
+        format!("<h{level}>{}</h{level}>\n", escape_html(text))
+    }
+
+    fn index_list(entries: &[(&str, &str)]) -> String {
+        let mut list = String::from("<ul>\n");
+
+        for (name, path) in entries {
+            list.push_str(&index_entry(name, path));
+        }
+
+        list.push_str("</ul>\n");
+        list
+    }
+
+    fn page(title: &str, prefix: &str, body: String) -> String {
+        page(title, prefix, &body)
+    }
+}
+
+/// Wrap a page's body in a standalone document with the stylesheet and a link back to
+/// the index, which is at `prefix`.
+fn page(title: &str, prefix: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n\
+        <html lang=\"en\">\n\
+        <head>\n\
+        <meta charset=\"utf-8\">\n\
+        <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+        <title>{}</title>\n\
+        <style>\n{STYLE}</style>\n\
+        </head>\n\
+        <body>\n\
+        <nav><a href=\"{prefix}index.html\">API Reference</a></nav>\n\
+        <main>\n{body}</main>\n\
+        </body>\n\
+        </html>\n",
+        escape_html(title)
+    )
+}
+
+fn index_entry(name: &str, path: &str) -> String {
+    format!(
+        "<li><a href=\"{}\"><code>{}</code></a></li>\n",
+        escape_html(path),
+        escape_html(name)
+    )
+}
+
+/// Format a type as inline code, with documented type names linked to their pages.
+fn format_type(ty: &Type, links: &Links) -> String {
+    let formatted = links.format_type(ty, escape_html, |name, link| {
+        format!(
+            "<a href=\"{}\">{}</a>",
+            escape_html(link),
+            escape_html(name)
+        )
+    });
+
+    format!("<code>{formatted}</code>")
+}
+
+/// Link a `@see` reference to the page of the type it starts with, if documented.
+fn format_see(ident: &str, links: &Links) -> String {
+    match links.see_link(ident) {
+        Some(link) => format!(
+            "<a href=\"{}\"><code>{}</code></a>",
+            escape_html(&link),
+            escape_html(ident)
+        ),
+        None => code(ident),
+    }
+}
+
+fn generate_class(class: &Class, functions: &[Function], links: &Links) -> String {
+    let mut contents = format!("<h1>Class {}</h1>\n", code(&class.name));
+
+    if let Some(deprecated) = class.deprecated.as_deref() {
+        contents.push_str(&deprecation_notice(deprecated));
+    }
+
+    if let Some(parent) = class.parent.as_ref() {
+        contents.push_str(&format!(
+            "<p><strong>Parent:</strong> {}</p>\n",
+            format_type(parent, links)
+        ));
+    }
+
+    if let Some(desc) = class.description.as_deref() {
+        contents.push_str(&description(desc));
+    }
+
+    let fields = class.fields();
+    if !fields.is_empty() {
+        contents.push_str("<h2>Fields</h2>\n");
+    }
+
+    for field in fields {
+        let name = field.ident_type.format_as_table_field_name();
+        contents.push_str(&format!(
+            "<h3 id=\"{}\">{}</h3>\n",
+            escape_html(&name),
+            code(&name)
+        ));
+
+        if let Some(deprecated) = field.deprecated.as_deref() {
+            contents.push_str(&deprecation_notice(deprecated));
+        }
+
+        if let Some(ty) = field.ty.as_ref() {
+            contents.push_str(&format!(
+                "<p><strong>Type:</strong> {}</p>\n",
+                format_type(ty, links)
+            ));
+        }
+
+        if let Some(value) = field.value.as_deref() {
+            if value.contains('\n') {
+                contents.push_str(&format!(
+                    "<p><strong>Value:</strong></p>\n<pre><code>{}</code></pre>\n",
+                    escape_html(value)
+                ));
+            } else {
+                contents.push_str(&format!("<p><strong>Value:</strong> {}</p>\n", code(value)));
+            }
+        }
+
+        if let Some(default) = field.default.as_deref() {
+            contents.push_str(&format!(
+                "<p><strong>Default:</strong> {}</p>\n",
+                code(default)
+            ));
+        }
+
+        if let Some(desc) = field.description.as_deref() {
+            contents.push_str(&description(desc));
+        }
+    }
+
+    if !class.operators.is_empty() {
+        contents.push_str("<h2>Operators</h2>\n<ul>\n");
+    }
+
+    for operator in class.operators.iter() {
+        let input = operator
+            .input
+            .as_ref()
+            .map(|input| format!(" with {}", format_type(input, links)))
+            .unwrap_or_default();
+        let description = operator
+            .description
+            .as_deref()
+            .map(|desc| format!(": {}", inline_description(desc)))
+            .unwrap_or_default();
+
+        contents.push_str(&format!(
+            "<li>{}{input} → {}{description}</li>\n",
+            code(&operator.op),
+            format_type(&operator.result, links)
+        ));
+    }
+
+    if !class.operators.is_empty() {
+        contents.push_str("</ul>\n");
+    }
+
+    if !functions.is_empty() {
+        contents.push_str("<h2>Functions</h2>\n");
+    }
+
+    for func in functions {
+        contents.push_str(&generate_function(func, links));
+    }
+
+    contents
+}
+
+fn generate_alias(alias: &Alias, links: &Links) -> String {
+    let mut contents = format!("<h1>Alias {}</h1>\n", code(&alias.name_with_type_params()));
+
+    if let Some(desc) = alias.description.as_deref() {
+        contents.push_str(&description(desc));
+    }
+
+    if !alias.types.is_empty() {
+        contents.push_str("<h2>Aliased types</h2>\n<ul>\n");
+    }
+
+    for (ty, desc) in alias.types.iter() {
+        let description = desc
+            .as_deref()
+            .map(|desc| format!(": {}", inline_description(desc)))
+            .unwrap_or_default();
+
+        contents.push_str(&format!(
+            "<li>{}{description}</li>\n",
+            format_type(ty, links)
+        ));
+    }
+
+    if !alias.types.is_empty() {
+        contents.push_str("</ul>\n");
+    }
+
+    contents
+}
+
+fn generate_enum(en: &Enum) -> String {
+    let mut contents = format!("<h1>Enum {}</h1>\n", code(&en.name));
+
+    if let Some(desc) = en.description.as_deref() {
+        contents.push_str(&description(desc));
+    }
+
+    let fields = en
+        .fields
+        .iter()
+        .filter_map(|field| match field.name.as_ref() {
+            Some(FieldName::Ident(ident)) => Some((ident, field)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if !fields.is_empty() {
+        let title = if en.is_key { "Values" } else { "Fields" };
+        contents.push_str(&format!("<h2>{title}</h2>\n<ul>\n"));
+    }
+
+    for (ident, field) in fields.iter() {
+        let name = if en.is_key {
+            format!("\"{ident}\"")
+        } else {
+            format!("{}.{ident}", en.name)
+        };
+        let description = field
+            .description
+            .as_deref()
+            .map(|desc| format!(": {}", inline_description(desc)))
+            .unwrap_or_default();
+
+        // Multi-line values, like functions, go in a code block inside the list item
+        let value = if field.value.contains('\n') {
+            format!(
+                "{description}\n<pre><code class=\"language-lua\">{}</code></pre>\n",
+                escape_html(&dedent_continuation_lines(&field.value))
+            )
+        } else {
+            format!(" = {}{description}", code(&field.value))
+        };
+
+        contents.push_str(&format!(
+            "<li id=\"{}\">{}{value}</li>\n",
+            escape_html(ident),
+            code(&name)
+        ));
+    }
+
+    if !fields.is_empty() {
+        contents.push_str("</ul>\n");
+    }
+
+    contents
+}
+
+fn generate_function(func: &Function, links: &Links) -> String {
+    let qualified_name = func.qualified_name();
+
+    let params_short = func
+        .params
+        .iter()
+        .map(|param| {
+            let nullable = param.ty.nullable.then_some("?").unwrap_or_default();
+            format!("{}{nullable}: {}", param.name, param.ty)
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let returns_short = func
+        .returns
+        .iter()
+        .map(|ret| ret.ty.format_nullable(ret.ty.to_string()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let returns_short = if returns_short.is_empty() {
+        String::new()
+    } else {
+        format!(": {returns_short}")
+    };
+    let async_keyword = if func.is_async { "async " } else { "" };
+
+    let mut contents = format!(
+        "<h3 id=\"{}\">{}</h3>\n<pre><code>{}</code></pre>\n",
+        escape_html(&qualified_name),
+        code(&qualified_name),
+        escape_html(&format!(
+            "{async_keyword}function {qualified_name}({params_short}){returns_short}"
+        ))
+    );
+
+    if let Some(deprecated) = func.deprecated.as_deref() {
+        contents.push_str(&deprecation_notice(deprecated));
+    }
+
+    if let Some(desc) = func.description.as_deref() {
+        contents.push_str(&description(desc));
+    }
+
+    if !func.params.is_empty() {
+        contents.push_str("<h4>Parameters</h4>\n<ul>\n");
+    }
+
+    for param in func.params.iter() {
+        let nullable = param.ty.nullable.then_some("?").unwrap_or_default();
+        let description = param
+            .description
+            .as_deref()
+            .map(|desc| format!(": {}", inline_description(desc)))
+            .unwrap_or_default();
+        let default = param
+            .default
+            .as_ref()
+            .map(|default| format!(" (Default: {})", code(default)))
+            .unwrap_or_default();
+
+        // The name already marks the param optional, like VitePress pages do
+        let ty = Type {
+            nullable: false,
+            ..param.ty.clone()
+        };
+
+        contents.push_str(&format!(
+            "<li>{}: {}{description}{default}</li>\n",
+            code(&format!("{}{nullable}", param.name)),
+            format_type(&ty, links)
+        ));
+    }
+
+    if !func.params.is_empty() {
+        contents.push_str("</ul>\n");
+    }
+
+    if !func.returns.is_empty() {
+        contents.push_str("<h4>Returns</h4>\n<ol>\n");
+    }
+
+    for ret in func.returns.iter() {
+        let name = ret
+            .name
+            .as_ref()
+            .map(|name| format!("{}: ", code(name)))
+            .unwrap_or_default();
+        let description = ret
+            .description
+            .as_deref()
+            .map(|desc| format!(": {}", inline_description(desc)))
+            .unwrap_or_default();
+
+        contents.push_str(&format!(
+            "<li>{name}{}{description}</li>\n",
+            format_type(&ret.ty, links)
+        ));
+    }
+
+    if !func.returns.is_empty() {
+        contents.push_str("</ol>\n");
+    }
+
+    if !func.errors.is_empty() {
+        contents.push_str("<h4>Errors</h4>\n<ul>\n");
+    }
+
+    for (ty, desc) in func.errors.iter() {
+        let description = desc
+            .as_deref()
+            .map(|desc| format!(": {}", inline_description(desc)))
+            .unwrap_or_default();
+
+        contents.push_str(&format!(
+            "<li>{}{description}</li>\n",
+            format_type(ty, links)
+        ));
+    }
+
+    if !func.errors.is_empty() {
+        contents.push_str("</ul>\n");
+    }
+
+    if !func.sees.is_empty() {
+        contents.push_str("<h4>See also</h4>\n<ul>\n");
+    }
+
+    for see in func.sees.iter() {
+        let description = see
+            .description
+            .as_deref()
+            .map(|desc| format!(": {}", inline_description(desc)))
+            .unwrap_or_default();
+
+        let reference = if see.is_url() {
+            let url = escape_html(&see.ident);
+            format!("<a href=\"{url}\">{url}</a>")
+        } else {
+            format_see(&see.ident, links)
+        };
+
+        contents.push_str(&format!("<li>{reference}{description}</li>\n"));
+    }
+
+    if !func.sees.is_empty() {
+        contents.push_str("</ul>\n");
+    }
+
+    contents
+}
+
+/// Convert a markdown description to HTML blocks.
+///
+/// Raw HTML in descriptions is escaped rather than passed through.
+fn description(markdown: &str) -> String {
+    match markdown::to_html_with_options(markdown, &Options::gfm()) {
+        Ok(html) => format!("{}\n", html.trim_end()),
+        Err(_) => format!("<p>{}</p>\n", escape_html(markdown)),
+    }
+}
+
+/// Convert a one-line markdown description to HTML for use inside a list item,
+/// without a wrapping paragraph.
+fn inline_description(markdown: &str) -> String {
+    let html = description(markdown);
+    let html = html.trim_end();
+
+    match html
+        .strip_prefix("<p>")
+        .and_then(|html| html.strip_suffix("</p>"))
+    {
+        Some(inline) if !inline.contains("<p>") => inline.to_string(),
+        _ => html.to_string(),
+    }
+}
+
+/// A notice in place of VitePress's deprecation badge and block.
+fn deprecation_notice(message: &str) -> String {
+    if message.is_empty() {
+        "<p class=\"deprecated\"><strong>Deprecated</strong></p>\n".to_string()
+    } else {
+        format!(
+            "<div class=\"deprecated\"><p><strong>Deprecated:</strong> {}</p></div>\n",
+            inline_description(message)
+        )
+    }
+}
+
+fn code(text: &str) -> String {
+    format!("<code>{}</code>", escape_html(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use crate::state::parse_str;
+
+    fn render(source: &str) -> HashMap<PathBuf, String> {
+        render_type_pages::<Html>(parse_str(source))
+            .into_iter()
+            .collect()
+    }
+
+    const SOURCE: &str = r#"
+---A **window**.
+---@class Window
+---@field title string? The title
+---@field children table<string, Window>
+local Window = {}
+
+---@alias Direction "left" | "right"
+
+---@enum State
+local State = {
+    focused = 1,
+}
+
+---Move the window.
+---@param direction Direction | Window[]
+---@return boolean moved
+---@see Direction
+---@see https://example.com
+function Window:move(direction) end
+
+---@deprecated Use `Window:move` instead.
+function Window.shift() end
+
+---Find a window.
+---@return Window?
+function find() end
+"#;
+
+    mod pages {
+        use super::*;
+
+        #[test]
+        fn pages_are_standalone_documents() {
+            let pages = render(SOURCE);
+
+            for (path, page) in pages.iter() {
+                assert!(page.starts_with("<!DOCTYPE html>\n"), "{path:?}");
+                assert!(page.contains("<style>\n"), "{path:?}");
+                assert!(page.ends_with("</html>\n"), "{path:?}");
+                assert!(!page.contains("href=\"/"), "{path:?} has an absolute link");
+            }
+
+            let mut paths = pages.keys().cloned().collect::<Vec<_>>();
+            paths.sort();
+            assert_eq!(
+                paths,
+                [
+                    "aliases/Direction.html",
+                    "classes/Window.html",
+                    "enums/State.html",
+                    "index.html",
+                ]
+                .map(PathBuf::from)
+            );
+        }
+
+        #[test]
+        fn types_link_to_their_pages() {
+            let pages = render(SOURCE);
+
+            let page = &pages[&PathBuf::from("classes/Window.html")];
+            assert!(page.contains("<nav><a href=\"../index.html\">API Reference</a></nav>"));
+            assert!(page.contains(
+                "<li><code>direction</code>: <code><a href=\"../aliases/Direction.html\">Direction</a> | \
+                <a href=\"../classes/Window.html\">Window</a>[]</code></li>"
+            ));
+            assert!(page.contains(
+                "<code>table&lt;string, <a href=\"../classes/Window.html\">Window</a>&gt;</code>"
+            ));
+            assert!(page.contains(
+                "<li><a href=\"../aliases/Direction.html\"><code>Direction</code></a></li>\n\
+                <li><a href=\"https://example.com\">https://example.com</a></li>"
+            ));
+
+            let index = &pages[&PathBuf::from("index.html")];
+            assert!(
+                index.contains("<li><a href=\"classes/Window.html\"><code>Window</code></a></li>")
+            );
+            assert!(
+                index.contains("<li><code><a href=\"classes/Window.html\">Window</a>?</code></li>")
+            );
+        }
+
+        #[test]
+        fn descriptions_are_converted_from_markdown() {
+            let pages = render(SOURCE);
+
+            let page = &pages[&PathBuf::from("classes/Window.html")];
+            assert!(page.contains("<p>A <strong>window</strong>.</p>\n"));
+            assert!(page.contains(
+                "<div class=\"deprecated\"><p><strong>Deprecated:</strong> Use <code>Window:move</code> instead.</p></div>"
+            ));
+            assert!(page.contains(
+                "<pre><code>function Window:move(direction: Direction | Window[]): boolean</code></pre>"
+            ));
+        }
+
+        #[test]
+        fn raw_html_is_escaped() {
+            let pages = render(
+                r#"
+---Closes <script>alert(1)</script> windows.
+---@class Window
+local Window = {}
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("classes/Window.html")];
+            assert!(!page.contains("<script>"));
+            assert!(page.contains("&lt;script&gt;"));
+        }
+
+        #[test]
+        fn nullable_params_are_marked_once() {
+            let pages = render(
+                r#"
+---@class Timer
+local Timer = {}
+
+---@param delay? integer
+---@param cb? fun(): nil
+function Timer.start(delay, cb) end
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("classes/Timer.html")];
+            assert!(page.contains("<li><code>delay?</code>: <code>integer</code></li>\n"));
+            assert!(page.contains("<li><code>cb?</code>: <code>fun(): nil</code></li>\n"));
+        }
+
+        #[test]
+        fn multi_line_enum_values_are_code_blocks() {
+            let pages = render(
+                r#"
+---@enum Handlers
+local Handlers = {
+    ---Called on click
+    on_click = function(button)
+        print(button)
+    end,
+}
+"#,
+            );
+
+            assert!(pages[&PathBuf::from("enums/Handlers.html")].contains(
+                "<li id=\"on_click\"><code>Handlers.on_click</code>: Called on click\n\
+                <pre><code class=\"language-lua\">function(button)\n    print(button)\nend\
+                </code></pre>\n</li>\n"
+            ));
+        }
+    }
+
+    mod manifest {
+        use super::*;
+
+        #[test]
+        fn only_generated_pages_are_removed() {
+            let dir = tempfile::tempdir().unwrap();
+            let out_dir = dir.path();
+
+            HtmlRenderer::new(out_dir.to_path_buf()).render(parse_str(
+                r#"
+---@class Window
+local Window = {}
+
+---@class Old
+local Old = {}
+"#,
+            ));
+            std::fs::write(out_dir.join("classes/custom.html"), "mine\n").unwrap();
+
+            let source = r#"
+---@class Window
+local Window = {}
+"#;
+            let renderer = HtmlRenderer::new(out_dir.to_path_buf());
+            let diff = renderer.diff(parse_str(source));
+            assert!(diff.contains("--- a/classes/Old.html\n+++ /dev/null\n"));
+            assert!(!diff.contains("custom.html"));
+
+            HtmlRenderer::new(out_dir.to_path_buf()).render(parse_str(source));
+
+            assert!(out_dir.join("classes/Window.html").exists());
+            assert!(!out_dir.join("classes/Old.html").exists());
+            assert_eq!(
+                std::fs::read_to_string(out_dir.join("classes/custom.html")).unwrap(),
+                "mine\n"
+            );
+        }
+    }
+}
@@ -0,0 +1,463 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::{
+    annotation::{Alias, Class, Enum, Function, Location, Scope, TsField},
+    processor::Processor,
+    treesitter::FunctionParam,
+    types::Type,
+};
+
+use super::{diff_pages, write_pages, OutputEncoding, Renderer, WriteStats};
+
+/// The file the documentation is written to.
+const FILE_NAME: &str = "lcat.json";
+
+/// Bumped whenever the shape of the JSON changes in a way that could break consumers.
+const SCHEMA_VERSION: u32 = 2;
+
+/// Renders everything lcat processed into a single `lcat.json`, for other tools to consume.
+///
+/// The shape is set by the `*Json` types below rather than lcat's own types, so it only
+/// changes along with [`SCHEMA_VERSION`]. Types are tagged with their kind, like
+/// `{ "kind": "user_defined", "value": "Window" }`.
+pub struct JsonRenderer {
+    out_dir: PathBuf,
+    output_encoding: OutputEncoding,
+}
+
+/// The top level of `lcat.json`.
+#[derive(Serialize)]
+struct Document<'a> {
+    /// [`SCHEMA_VERSION`].
+    version: u32,
+    classes: Vec<ClassJson<'a>>,
+    aliases: Vec<AliasJson<'a>>,
+    enums: Vec<EnumJson<'a>>,
+    functions: Vec<FunctionJson<'a>>,
+}
+
+#[derive(Serialize)]
+struct ClassJson<'a> {
+    name: &'a str,
+    description: Option<&'a str>,
+    parent: Option<&'a Type>,
+    /// Whether the class is declared `(exact)`, so it has no fields but the documented ones.
+    exact: bool,
+    /// Fields from `@field` merged with the ones assigned in the class's table.
+    fields: Vec<FieldJson>,
+    operators: Vec<OperatorJson<'a>>,
+    /// The module declared with `@module` in the class's file, if any.
+    module: Option<&'a str>,
+    since: Option<&'a str>,
+    /// The deprecation message, which is empty if none was given.
+    deprecated: Option<&'a str>,
+    location: Option<&'a Location>,
+}
+
+#[derive(Serialize)]
+struct FieldJson {
+    /// The name as it would be written in a table, like `title` or `[integer]`.
+    name: String,
+    ty: Option<Type>,
+    description: Option<String>,
+    scope: Option<Scope>,
+    /// The Lua source of the assigned value, if any.
+    value: Option<String>,
+    default: Option<String>,
+    since: Option<String>,
+    deprecated: Option<String>,
+    location: Option<Location>,
+}
+
+#[derive(Serialize)]
+struct OperatorJson<'a> {
+    /// The metamethod name without the leading `__`, like `add`.
+    op: &'a str,
+    /// The type of the other operand, which is `null` for unary operators.
+    input: Option<&'a Type>,
+    result: &'a Type,
+    description: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct AliasJson<'a> {
+    name: &'a str,
+    type_params: &'a [String],
+    description: Option<&'a str>,
+    types: Vec<DescribedTypeJson<'a>>,
+    since: Option<&'a str>,
+}
+
+/// A type with a description, like an aliased type or a raised error.
+#[derive(Serialize)]
+struct DescribedTypeJson<'a> {
+    ty: &'a Type,
+    description: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct EnumJson<'a> {
+    name: &'a str,
+    description: Option<&'a str>,
+    /// Whether the enum is declared `(key)`, so its keys are the values.
+    is_key: bool,
+    values: Vec<EnumValueJson<'a>>,
+    since: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct EnumValueJson<'a> {
+    /// The key, like `focused` or `[1]`.
+    name: String,
+    /// The Lua source of the value.
+    value: &'a str,
+    description: Option<&'a str>,
+    location: &'a Location,
+}
+
+#[derive(Serialize)]
+struct FunctionJson<'a> {
+    name: &'a str,
+    /// The name qualified with the table, like `Window:close`.
+    qualified_name: String,
+    table: Option<&'a str>,
+    is_method: bool,
+    is_async: bool,
+    is_local: bool,
+    scope: Option<Scope>,
+    description: Option<&'a str>,
+    params: Vec<ParamJson<'a>>,
+    /// The parameter names in the Lua definition, with `...` for varargs.
+    declared_params: Vec<&'a str>,
+    returns: Vec<ReturnJson<'a>>,
+    errors: Vec<DescribedTypeJson<'a>>,
+    generics: Vec<GenericJson<'a>>,
+    /// Alternative signatures from `@overload`, as function types.
+    overloads: &'a [Type],
+    see: Vec<SeeJson<'a>>,
+    since: Option<&'a str>,
+    /// The deprecation message, which is empty if none was given.
+    deprecated: Option<&'a str>,
+    location: &'a Location,
+}
+
+#[derive(Serialize)]
+struct ParamJson<'a> {
+    name: &'a str,
+    ty: &'a Type,
+    description: Option<&'a str>,
+    default: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct ReturnJson<'a> {
+    name: Option<&'a str>,
+    ty: &'a Type,
+    description: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct GenericJson<'a> {
+    name: &'a str,
+    /// The type the type parameter is constrained to, if any.
+    constraint: Option<&'a Type>,
+}
+
+#[derive(Serialize)]
+struct SeeJson<'a> {
+    /// The referenced symbol, or a URL.
+    reference: &'a str,
+    is_url: bool,
+    description: Option<&'a str>,
+}
+
+impl<'a> Document<'a> {
+    fn new(processor: &'a Processor) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            classes: processor.classes.iter().map(ClassJson::new).collect(),
+            aliases: processor.aliases.iter().map(AliasJson::new).collect(),
+            enums: processor.enums.iter().map(EnumJson::new).collect(),
+            functions: processor.functions.iter().map(FunctionJson::new).collect(),
+        }
+    }
+}
+
+impl<'a> ClassJson<'a> {
+    fn new(class: &'a Class) -> Self {
+        Self {
+            name: &class.name,
+            description: class.description.as_deref(),
+            parent: class.parent.as_ref(),
+            exact: class.exact,
+            fields: class
+                .fields()
+                .into_iter()
+                .map(|field| FieldJson {
+                    name: field.ident_type.format_as_table_field_name(),
+                    ty: field.ty,
+                    description: field.description,
+                    scope: field.scope,
+                    value: field.value,
+                    default: field.default,
+                    since: field.since,
+                    deprecated: field.deprecated,
+                    location: field.location,
+                })
+                .collect(),
+            operators: class
+                .operators
+                .iter()
+                .map(|operator| OperatorJson {
+                    op: &operator.op,
+                    input: operator.input.as_ref(),
+                    result: &operator.result,
+                    description: operator.description.as_deref(),
+                })
+                .collect(),
+            module: class.module.as_deref(),
+            since: class.since.as_deref(),
+            deprecated: class.deprecated.as_deref(),
+            location: class.location.as_ref(),
+        }
+    }
+}
+
+impl<'a> AliasJson<'a> {
+    fn new(alias: &'a Alias) -> Self {
+        Self {
+            name: &alias.name,
+            type_params: &alias.type_params,
+            description: alias.description.as_deref(),
+            types: alias
+                .types
+                .iter()
+                .map(|(ty, description)| DescribedTypeJson {
+                    ty,
+                    description: description.as_deref(),
+                })
+                .collect(),
+            since: alias.since.as_deref(),
+        }
+    }
+}
+
+impl<'a> EnumJson<'a> {
+    fn new(en: &'a Enum) -> Self {
+        Self {
+            name: &en.name,
+            description: en.description.as_deref(),
+            is_key: en.is_key,
+            values: en.fields.iter().filter_map(EnumValueJson::new).collect(),
+            since: en.since.as_deref(),
+        }
+    }
+}
+
+impl<'a> EnumValueJson<'a> {
+    fn new(field: &'a TsField) -> Option<Self> {
+        Some(Self {
+            name: field.name.as_ref()?.to_string(),
+            value: &field.value,
+            description: field.description.as_deref(),
+            location: &field.location,
+        })
+    }
+}
+
+impl<'a> FunctionJson<'a> {
+    fn new(function: &'a Function) -> Self {
+        Self {
+            name: &function.name,
+            qualified_name: function.qualified_name(),
+            table: function.table.as_deref(),
+            is_method: function.is_method,
+            is_async: function.is_async,
+            is_local: function.is_local,
+            scope: function.scope,
+            description: function.description.as_deref(),
+            params: function
+                .params
+                .iter()
+                .map(|param| ParamJson {
+                    name: &param.name,
+                    ty: &param.ty,
+                    description: param.description.as_deref(),
+                    default: param.default.as_deref(),
+                })
+                .collect(),
+            declared_params: function
+                .declared_params
+                .iter()
+                .map(|param| match param {
+                    FunctionParam::Ident(name) => name.as_str(),
+                    FunctionParam::Varargs => "...",
+                })
+                .collect(),
+            returns: function
+                .returns
+                .iter()
+                .map(|ret| ReturnJson {
+                    name: ret.name.as_deref(),
+                    ty: &ret.ty,
+                    description: ret.description.as_deref(),
+                })
+                .collect(),
+            errors: function
+                .errors
+                .iter()
+                .map(|(ty, description)| DescribedTypeJson {
+                    ty,
+                    description: description.as_deref(),
+                })
+                .collect(),
+            generics: function
+                .generics
+                .iter()
+                .map(|(name, constraint)| GenericJson {
+                    name,
+                    constraint: constraint.as_ref(),
+                })
+                .collect(),
+            overloads: &function.overloads,
+            see: function
+                .sees
+                .iter()
+                .map(|see| SeeJson {
+                    reference: &see.ident,
+                    is_url: see.is_url(),
+                    description: see.description.as_deref(),
+                })
+                .collect(),
+            since: function.since.as_deref(),
+            deprecated: function.deprecated.as_deref(),
+            location: &function.location,
+        }
+    }
+}
+
+impl JsonRenderer {
+    pub fn new(out_dir: PathBuf) -> Self {
+        Self {
+            out_dir,
+            output_encoding: OutputEncoding::default(),
+        }
+    }
+
+    /// Set the encoding the JSON file is written in.
+    pub fn output_encoding(mut self, output_encoding: OutputEncoding) -> Self {
+        self.output_encoding = output_encoding;
+        self
+    }
+}
+
+// Like vimdoc, the one file needs no manifest
+impl Renderer for JsonRenderer {
+    fn out_dir(&self) -> &Path {
+        &self.out_dir
+    }
+
+    fn encoding(&self) -> OutputEncoding {
+        self.output_encoding
+    }
+
+    fn render_pages(&self, processor: Processor) -> Vec<(PathBuf, String)> {
+        vec![(PathBuf::from(FILE_NAME), render_json(&processor))]
+    }
+
+    fn render(&self, processor: Processor) -> WriteStats {
+        write_pages(
+            &self.out_dir,
+            &self.render_pages(processor),
+            self.output_encoding,
+        )
+    }
+
+    fn diff(&self, processor: Processor) -> String {
+        diff_pages(&self.out_dir, &self.render_pages(processor), &[])
+    }
+}
+
+fn render_json(processor: &Processor) -> String {
+    serde_json::to_string_pretty(&Document::new(processor)).unwrap() + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::{json, Value};
+
+    use crate::state::parse_str;
+
+    fn render(source: &str) -> Value {
+        serde_json::from_str(&render_json(&parse_str(source))).unwrap()
+    }
+
+    mod json {
+        use super::*;
+
+        #[test]
+        fn symbols_are_serialized() {
+            let json = render(
+                r#"
+---A window.
+---@class Window
+---@field title string?
+local Window = {}
+
+---@alias Direction "left" | "right"
+
+---@enum State
+local State = {
+    focused = 1,
+}
+
+---Resize the window.
+---@param width integer
+---@return Window[]
+function Window:resize(width) end
+"#,
+            );
+
+            assert_eq!(json["version"], 2);
+
+            assert_eq!(json["classes"][0]["name"], "Window");
+            assert_eq!(json["classes"][0]["description"], "A window.");
+            assert_eq!(
+                json["classes"][0]["fields"][0]["ty"],
+                json!({ "kind": "string", "generics": [], "nullable": true })
+            );
+
+            assert_eq!(json["aliases"][0]["name"], "Direction");
+            assert_eq!(json["aliases"][0]["types"][0]["ty"]["kind"], "union");
+            assert_eq!(
+                json["aliases"][0]["types"][0]["ty"]["value"][0]["value"],
+                json!({ "kind": "string", "value": "\"left\"" })
+            );
+
+            assert_eq!(json["enums"][0]["name"], "State");
+            assert_eq!(json["enums"][0]["values"][0]["name"], "focused");
+            assert_eq!(json["enums"][0]["values"][0]["value"], "1");
+
+            let function = &json["functions"][0];
+            assert_eq!(function["name"], "resize");
+            assert_eq!(function["qualified_name"], "Window:resize");
+            assert_eq!(function["declared_params"], json!(["width"]));
+            assert_eq!(function["table"], "Window");
+            assert_eq!(function["is_method"], true);
+            assert_eq!(function["params"][0]["ty"]["kind"], "integer");
+            assert_eq!(
+                function["returns"][0]["ty"],
+                json!({
+                    "kind": "array",
+                    "value": { "kind": "user_defined", "value": "Window", "generics": [], "nullable": false },
+                    "generics": [],
+                    "nullable": false,
+                })
+            );
+        }
+    }
+}
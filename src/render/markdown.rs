@@ -0,0 +1,743 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    annotation::{Alias, Class, Enum, Function},
+    processor::Processor,
+    treesitter::FieldName,
+    types::Type,
+};
+
+use super::{
+    code_fence, dedent_continuation_lines, normalize_line_endings, render_type_pages,
+    search::{self, SearchEntry, SymbolItem},
+    Links, OutputEncoding, PageFormat, Renderer,
+};
+
+/// Renders portable GitHub-flavored Markdown that doesn't rely on VitePress components
+/// or raw HTML, for reading on GitHub or in an editor.
+///
+/// Pages link to each other with relative `.md` paths, and an index is written to
+/// `README.md`.
+pub struct MarkdownRenderer {
+    out_dir: PathBuf,
+    search_index: bool,
+    output_encoding: OutputEncoding,
+}
+
+impl MarkdownRenderer {
+    pub fn new(out_dir: PathBuf) -> Self {
+        Self {
+            out_dir,
+            search_index: false,
+            output_encoding: OutputEncoding::default(),
+        }
+    }
+
+    /// Write a `search-index.json` of every symbol's name, kind, description snippet, and
+    /// page, since Markdown has no search of its own.
+    pub fn search_index(mut self, search_index: bool) -> Self {
+        self.search_index = search_index;
+        self
+    }
+
+    /// Set the encoding pages are written in.
+    pub fn output_encoding(mut self, output_encoding: OutputEncoding) -> Self {
+        self.output_encoding = output_encoding;
+        self
+    }
+}
+
+impl Renderer for MarkdownRenderer {
+    fn out_dir(&self) -> &Path {
+        &self.out_dir
+    }
+
+    fn encoding(&self) -> OutputEncoding {
+        self.output_encoding
+    }
+
+    fn render_pages(&self, processor: Processor) -> Vec<(PathBuf, String)> {
+        render_pages(processor, self.search_index)
+    }
+}
+
+/// Render every page, returning each page's path relative to the output directory
+/// along with its contents.
+fn render_pages(processor: Processor, search_index: bool) -> Vec<(PathBuf, String)> {
+    let search_index = search_index.then(|| {
+        (
+            PathBuf::from(search::FILE_NAME),
+            normalize_line_endings(&generate_search_index(&processor)),
+        )
+    });
+
+    search_index
+        .into_iter()
+        .chain(render_type_pages::<Markdown>(processor))
+        .collect()
+}
+
+/// Portable Markdown pages, with an index in `README.md`.
+struct Markdown;
+
+impl PageFormat for Markdown {
+    const EXTENSION: &'static str = "md";
+    const INDEX: &'static str = "README.md";
+
+    fn generate_class(class: &Class, functions: &[Function], links: &Links) -> String {
+        generate_class(class, functions, links)
+    }
+
+    fn generate_alias(alias: &Alias, links: &Links) -> String {
+        generate_alias(alias, links)
+    }
+
+    fn generate_enum(en: &Enum) -> String {
+        generate_enum(en)
+    }
+
+    fn generate_function(func: &Function, links: &Links) -> String {
+        generate_function(func, links)
+    }
+
+    fn index_heading(level: usize, text: &str) -> String {
+        match level {
+            1 => format!("# {text}\n"),
+            level => format!("\n{} {text}\n", "#".repeat(level)),
+        }
+    }
+
+    fn index_list(entries: &[(&str, &str)]) -> String {
+        let mut list = String::from("\n");
+
+        for (name, path) in entries {
+            list.push_str(&format!("- [`{name}`]({path})\n"));
+        }
+
+        list
+    }
+
+    fn page(_title: &str, _prefix: &str, body: String) -> String {
+        body
+    }
+}
+
+/// Generate the search index, linking each symbol to its page and to its heading if it
+/// has one.
+fn generate_search_index(processor: &Processor) -> String {
+    let mut entries = Vec::new();
+
+    for symbol in search::symbols(
+        &processor.classes,
+        &processor.functions,
+        &processor.aliases,
+        &processor.enums,
+    ) {
+        let url = match &symbol.item {
+            SymbolItem::Class(class) => format!("classes/{}.md", class.name),
+            SymbolItem::Field {
+                class, field_name, ..
+            } => format!("classes/{}.md#{}", class.name, heading_slug(field_name)),
+            SymbolItem::Function(function) => {
+                // Functions not belonging to a class are on the index
+                let page = match function.table.as_ref() {
+                    Some(table) if processor.classes.iter().any(|class| &class.name == table) => {
+                        format!("classes/{table}.md")
+                    }
+                    _ => "README.md".to_string(),
+                };
+                format!("{page}#{}", heading_slug(&symbol.name))
+            }
+            SymbolItem::Alias(alias) => format!("aliases/{}.md", alias.name),
+            // Enum values are list items without headings of their own
+            SymbolItem::Enum(en) | SymbolItem::EnumValue { en, .. } => {
+                format!("enums/{}.md", en.name)
+            }
+        };
+
+        entries.push(SearchEntry::new(
+            symbol.name.clone(),
+            symbol.kind(),
+            symbol.description(),
+            url,
+        ));
+    }
+
+    search::render_index(&entries)
+}
+
+/// The id GitHub gives a heading: lowercased, with spaces turned into `-` and
+/// punctuation other than `-` and `_` removed.
+fn heading_slug(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter_map(|ch| match ch {
+            ' ' => Some('-'),
+            '-' | '_' => Some(ch),
+            ch if ch.is_alphanumeric() => Some(ch),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Format a type as inline code, with documented type names linked to their pages.
+///
+/// Links can't go inside code spans, so the type is split into code spans
+/// around each link.
+fn format_type(ty: &Type, links: &Links) -> String {
+    links.format_type(
+        ty,
+        |part| {
+            let trimmed = part.trim();
+            if trimmed.is_empty() {
+                return part.to_string();
+            }
+
+            // Spaces at the edges of a code span may be stripped, so keep them outside
+            format!(
+                "{}{}{}",
+                &part[..part.len() - part.trim_start().len()],
+                code(trimmed),
+                &part[part.trim_end().len()..]
+            )
+        },
+        |name, link| format!("[`{name}`]({link})"),
+    )
+}
+
+/// Link a `@see` reference to the page of the type it starts with, if documented.
+fn format_see(ident: &str, links: &Links) -> String {
+    match links.see_link(ident) {
+        Some(link) => format!("[`{ident}`]({link})"),
+        None => code(ident),
+    }
+}
+
+fn generate_class(class: &Class, functions: &[Function], links: &Links) -> String {
+    let mut contents = format!("# Class `{}`\n\n", class.name);
+
+    if let Some(deprecated) = class.deprecated.as_deref() {
+        contents.push_str(&deprecation_notice(deprecated));
+    }
+
+    if let Some(parent) = class.parent.as_ref() {
+        contents.push_str(&format!("**Parent:** {}\n\n", format_type(parent, links)));
+    }
+
+    if let Some(description) = class.description.as_deref() {
+        contents.push_str(&format!("{description}\n\n"));
+    }
+
+    let fields = class.fields();
+    if !fields.is_empty() {
+        contents.push_str("## Fields\n\n");
+    }
+
+    for field in fields {
+        contents.push_str(&format!(
+            "### `{}`\n\n",
+            field.ident_type.format_as_table_field_name()
+        ));
+
+        if let Some(deprecated) = field.deprecated.as_deref() {
+            contents.push_str(&deprecation_notice(deprecated));
+        }
+
+        if let Some(ty) = field.ty.as_ref() {
+            contents.push_str(&format!("**Type:** {}\n\n", format_type(ty, links)));
+        }
+
+        // Multiline values like inline tables would break the list, so leave them out
+        if let Some(value) = field.value.filter(|value| !value.contains('\n')) {
+            contents.push_str(&format!("**Value:** {}\n\n", code(&value)));
+        }
+
+        if let Some(default) = field.default.as_deref() {
+            contents.push_str(&format!("**Default:** {}\n\n", code(default)));
+        }
+
+        if let Some(description) = field.description.as_deref() {
+            contents.push_str(&format!("{description}\n\n"));
+        }
+    }
+
+    if !class.operators.is_empty() {
+        contents.push_str("## Operators\n\n");
+    }
+
+    for operator in class.operators.iter() {
+        let input = operator
+            .input
+            .as_ref()
+            .map(|input| format!(" with {}", format_type(input, links)))
+            .unwrap_or_default();
+        let description = operator
+            .description
+            .as_ref()
+            .map(|desc| format!(": {desc}"))
+            .unwrap_or_default();
+
+        contents.push_str(&format!(
+            "- `{}`{input} → {}{description}\n",
+            operator.op,
+            format_type(&operator.result, links)
+        ));
+    }
+
+    if !class.operators.is_empty() {
+        contents.push('\n');
+    }
+
+    if !functions.is_empty() {
+        contents.push_str("## Functions\n");
+    }
+
+    for func in functions {
+        contents.push_str(&generate_function(func, links));
+    }
+
+    contents
+}
+
+fn generate_alias(alias: &Alias, links: &Links) -> String {
+    let mut contents = format!("# Alias `{}`\n\n", alias.name_with_type_params());
+
+    if let Some(description) = alias.description.as_deref() {
+        contents.push_str(&format!("{description}\n\n"));
+    }
+
+    if !alias.types.is_empty() {
+        contents.push_str("## Aliased types\n\n");
+    }
+
+    for (ty, description) in alias.types.iter() {
+        let description = description
+            .as_ref()
+            .map(|desc| format!(": {desc}"))
+            .unwrap_or_default();
+
+        contents.push_str(&format!("- {}{description}\n", format_type(ty, links)));
+    }
+
+    contents
+}
+
+fn generate_enum(en: &Enum) -> String {
+    let mut contents = format!("# Enum `{}`\n\n", en.name);
+
+    if let Some(description) = en.description.as_deref() {
+        contents.push_str(&format!("{description}\n\n"));
+    }
+
+    let fields = en
+        .fields
+        .iter()
+        .filter_map(|field| match field.name.as_ref() {
+            Some(FieldName::Ident(ident)) => Some((ident, field)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if !fields.is_empty() {
+        let title = if en.is_key { "Values" } else { "Fields" };
+        contents.push_str(&format!("## {title}\n\n"));
+    }
+
+    for (ident, field) in fields {
+        let name = if en.is_key {
+            format!("\"{ident}\"")
+        } else {
+            format!("{}.{ident}", en.name)
+        };
+        let description = field
+            .description
+            .as_ref()
+            .map(|desc| format!(": {desc}"))
+            .unwrap_or_default();
+
+        // Multi-line values, like functions, go in a code block under the list item
+        if field.value.contains('\n') {
+            let fence = code_fence(&field.value);
+            let value = dedent_continuation_lines(&field.value).replace('\n', "\n  ");
+            contents.push_str(&format!(
+                "- {}{description}\n\n  {fence}lua\n  {value}\n  {fence}\n\n",
+                code(&name)
+            ));
+        } else {
+            contents.push_str(&format!(
+                "- {} = {}{description}\n",
+                code(&name),
+                code(&field.value)
+            ));
+        }
+    }
+
+    contents
+}
+
+fn generate_function(func: &Function, links: &Links) -> String {
+    let qualified_name = func.qualified_name();
+
+    let params_short = func
+        .params
+        .iter()
+        .map(|param| {
+            let nullable = param.ty.nullable.then_some("?").unwrap_or_default();
+            format!("{}{nullable}: {}", param.name, param.ty)
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let returns_short = func
+        .returns
+        .iter()
+        .map(|ret| ret.ty.format_nullable(ret.ty.to_string()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let returns_short = if returns_short.is_empty() {
+        String::new()
+    } else {
+        format!(": {returns_short}")
+    };
+    let async_keyword = if func.is_async { "async " } else { "" };
+
+    let mut contents = format!(
+        "\n### `{qualified_name}`\n\n\
+        ```lua\n{async_keyword}function {qualified_name}({params_short}){returns_short}\n```\n\n"
+    );
+
+    if let Some(deprecated) = func.deprecated.as_deref() {
+        contents.push_str(&deprecation_notice(deprecated));
+    }
+
+    if let Some(description) = func.description.as_deref() {
+        contents.push_str(&format!("{description}\n\n"));
+    }
+
+    if !func.params.is_empty() {
+        contents.push_str("#### Parameters\n\n");
+    }
+
+    for param in func.params.iter() {
+        let nullable = param.ty.nullable.then_some("?").unwrap_or_default();
+        let description = param
+            .description
+            .as_ref()
+            .map(|desc| format!(": {desc}"))
+            .unwrap_or_default();
+        let default = param
+            .default
+            .as_ref()
+            .map(|default| format!(" (Default: {})", code(default)))
+            .unwrap_or_default();
+
+        // The name already marks the param optional, like VitePress pages do
+        let ty = Type {
+            nullable: false,
+            ..param.ty.clone()
+        };
+
+        contents.push_str(&format!(
+            "- `{}{nullable}`: {}{description}{default}\n",
+            param.name,
+            format_type(&ty, links)
+        ));
+    }
+
+    if !func.params.is_empty() {
+        contents.push('\n');
+    }
+
+    if !func.returns.is_empty() {
+        contents.push_str("#### Returns\n\n");
+    }
+
+    for (i, ret) in func.returns.iter().enumerate() {
+        let name = ret
+            .name
+            .as_ref()
+            .map(|name| format!("`{name}`: "))
+            .unwrap_or_default();
+        let description = ret
+            .description
+            .as_ref()
+            .map(|desc| format!(": {desc}"))
+            .unwrap_or_default();
+
+        contents.push_str(&format!(
+            "{}. {name}{}{description}\n",
+            i + 1,
+            format_type(&ret.ty, links)
+        ));
+    }
+
+    if !func.returns.is_empty() {
+        contents.push('\n');
+    }
+
+    if !func.errors.is_empty() {
+        contents.push_str("#### Errors\n\n");
+    }
+
+    for (ty, desc) in func.errors.iter() {
+        let description = desc
+            .as_ref()
+            .map(|desc| format!(": {desc}"))
+            .unwrap_or_default();
+
+        contents.push_str(&format!("- {}{description}\n", format_type(ty, links)));
+    }
+
+    if !func.errors.is_empty() {
+        contents.push('\n');
+    }
+
+    if !func.sees.is_empty() {
+        contents.push_str("#### See also\n\n");
+    }
+
+    for see in func.sees.iter() {
+        let description = see
+            .description
+            .as_ref()
+            .map(|desc| format!(": {desc}"))
+            .unwrap_or_default();
+
+        let reference = if see.is_url() {
+            format!("<{}>", see.ident)
+        } else {
+            format_see(&see.ident, links)
+        };
+
+        contents.push_str(&format!("- {reference}{description}\n"));
+    }
+
+    contents
+}
+
+/// A bold notice in place of VitePress's deprecation badge and block.
+fn deprecation_notice(message: &str) -> String {
+    if message.is_empty() {
+        "**Deprecated**\n\n".to_string()
+    } else {
+        format!("**Deprecated:** {message}\n\n")
+    }
+}
+
+/// Wrap text in a code span, using a longer fence if the text contains backticks.
+fn code(text: &str) -> String {
+    if text.contains('`') {
+        format!("`` {text} ``")
+    } else {
+        format!("`{text}`")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use crate::state::parse_str;
+
+    fn render(source: &str) -> HashMap<PathBuf, String> {
+        render_pages(parse_str(source), false).into_iter().collect()
+    }
+
+    const SOURCE: &str = r#"
+---A window.
+---@class Window
+---@field title string? The title
+local Window = {}
+
+---@alias Direction "left" | "right"
+
+---@enum State
+local State = {
+    focused = 1,
+}
+
+---Move the window.
+---@param direction Direction | Window[]
+---@return boolean moved
+---@see Direction
+---@see https://example.com
+function Window:move(direction) end
+
+---@deprecated Use `Window:move` instead.
+function Window.shift() end
+
+---Find a window.
+---@return Window?
+function find() end
+"#;
+
+    mod pages {
+        use super::*;
+
+        #[test]
+        fn pages_use_relative_md_links() {
+            let pages = render(SOURCE);
+
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+            assert!(page.starts_with("# Class `Window`\n\nA window.\n"));
+            assert!(page.contains("### `title`\n\n**Type:** `string?`\n\nThe title\n"));
+            assert!(page.contains(
+                "```lua\nfunction Window:move(direction: Direction | Window[]): boolean\n```\n"
+            ));
+            assert!(page.contains(
+                "- `direction`: [`Direction`](../aliases/Direction.md) `|` \
+                [`Window`](../classes/Window.md)`[]`\n"
+            ));
+            assert!(page.contains("1. `moved`: `boolean`\n"));
+            assert!(page
+                .contains("- [`Direction`](../aliases/Direction.md)\n- <https://example.com>\n"));
+
+            let index = &pages[&PathBuf::from("README.md")];
+            assert!(index.contains("- [`Window`](classes/Window.md)\n"));
+            assert!(index.contains("- [`State`](enums/State.md)\n"));
+            assert!(index.contains("1. [`Window`](classes/Window.md)`?`\n"));
+
+            let page = &pages[&PathBuf::from("enums/State.md")];
+            assert!(page.contains("- `State.focused` = `1`\n"));
+        }
+
+        #[test]
+        fn no_vitepress_components_or_html() {
+            for (path, page) in render(SOURCE) {
+                for html in ["<Badge", "<code>", "<a ", "<div", ":::"] {
+                    assert!(!page.contains(html), "{path:?} contains `{html}`");
+                }
+            }
+        }
+
+        #[test]
+        fn deprecation_is_bold_text() {
+            let pages = render(SOURCE);
+
+            assert!(pages[&PathBuf::from("classes/Window.md")]
+                .contains("**Deprecated:** Use `Window:move` instead.\n"));
+        }
+
+        #[test]
+        fn nullable_params_are_marked_once() {
+            let pages = render(
+                r#"
+---@class Timer
+local Timer = {}
+
+---@param delay? integer
+---@param cb? fun(): nil
+---@param on_error fun(): nil?
+function Timer.start(delay, cb, on_error) end
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("classes/Timer.md")];
+            assert!(page.contains("- `delay?`: `integer`\n"));
+            assert!(page.contains("- `cb?`: `fun(): nil`\n"));
+            assert!(page.contains("- `on_error`: `fun(): nil?`\n"));
+        }
+
+        #[test]
+        fn multi_line_enum_values_are_code_blocks() {
+            let pages = render(
+                r#"
+---@enum Handlers
+local Handlers = {
+    ---Called on click
+    on_click = function(button)
+        print(button)
+    end,
+}
+"#,
+            );
+
+            assert!(pages[&PathBuf::from("enums/Handlers.md")].contains(
+                "- `Handlers.on_click`: Called on click\n\n  ```lua\n  function(button)\n      \
+                print(button)\n  end\n  ```\n"
+            ));
+        }
+    }
+
+    mod search_index {
+        use super::*;
+
+        #[test]
+        fn symbols_link_to_their_headings() {
+            let pages = render_pages(parse_str(SOURCE), true)
+                .into_iter()
+                .collect::<HashMap<_, _>>();
+            let index: serde_json::Value =
+                serde_json::from_str(&pages[&PathBuf::from("search-index.json")]).unwrap();
+
+            assert_eq!(
+                index[0],
+                serde_json::json!({
+                    "name": "Window",
+                    "kind": "class",
+                    "snippet": "A window.",
+                    "url": "classes/Window.md",
+                })
+            );
+            assert_eq!(index[1]["name"], "Window.title");
+            assert_eq!(index[1]["url"], "classes/Window.md#title");
+
+            let urls = index
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|entry| {
+                    (
+                        entry["name"].as_str().unwrap(),
+                        entry["url"].as_str().unwrap(),
+                    )
+                })
+                .collect::<HashMap<_, _>>();
+            assert_eq!(urls["Window:move"], "classes/Window.md#windowmove");
+            assert_eq!(urls["find"], "README.md#find");
+            assert_eq!(urls["Direction"], "aliases/Direction.md");
+            assert_eq!(urls["State.focused"], "enums/State.md");
+        }
+    }
+
+    mod manifest {
+        use super::*;
+
+        #[test]
+        fn only_generated_pages_are_removed() {
+            let dir = tempfile::tempdir().unwrap();
+            let out_dir = dir.path();
+
+            MarkdownRenderer::new(out_dir.to_path_buf()).render(parse_str(
+                r#"
+---@class Window
+local Window = {}
+
+---@class Old
+local Old = {}
+"#,
+            ));
+            std::fs::write(out_dir.join("classes/custom.md"), "mine\n").unwrap();
+
+            let source = r#"
+---@class Window
+local Window = {}
+"#;
+            let renderer = MarkdownRenderer::new(out_dir.to_path_buf());
+            let diff = renderer.diff(parse_str(source));
+            assert!(diff.contains("--- a/classes/Old.md\n+++ /dev/null\n"));
+            assert!(!diff.contains("custom.md"));
+
+            MarkdownRenderer::new(out_dir.to_path_buf()).render(parse_str(source));
+
+            assert!(out_dir.join("classes/Window.md").exists());
+            assert!(!out_dir.join("classes/Old.md").exists());
+            assert_eq!(
+                std::fs::read_to_string(out_dir.join("classes/custom.md")).unwrap(),
+                "mine\n"
+            );
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use markdown::{mdast::Node, ParseOptions};
+use serde::Serialize;
+
+use crate::{
+    annotation::{Alias, Class, ClassField, Enum, Function, TsField},
+    treesitter::FieldName,
+};
+
+/// The file the search index is written to.
+pub const FILE_NAME: &str = "search-index.json";
+
+/// The maximum length of a description snippet, in characters.
+const SNIPPET_LEN: usize = 150;
+
+/// A symbol in `search-index.json`, pointing at where it's documented.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchEntry {
+    pub name: String,
+    pub kind: &'static str,
+    /// The start of the description as plain text, empty if there's no description.
+    pub snippet: String,
+    pub url: String,
+}
+
+impl SearchEntry {
+    pub fn new(name: String, kind: &'static str, description: Option<&str>, url: String) -> Self {
+        Self {
+            name,
+            kind,
+            snippet: description.map(snippet).unwrap_or_default(),
+            url,
+        }
+    }
+}
+
+/// A documented symbol, as listed in search indexes and the symbols manifest.
+pub struct Symbol<'a> {
+    /// The qualified name, like `Window.title` or `Window:close`.
+    pub name: String,
+    pub item: SymbolItem<'a>,
+}
+
+pub enum SymbolItem<'a> {
+    Class(&'a Class),
+    Field {
+        class: &'a Class,
+        field: Box<ClassField>,
+        /// The name as it would be written in a table, like `title` or `[1]`.
+        field_name: String,
+    },
+    Function(&'a Function),
+    Alias(&'a Alias),
+    Enum(&'a Enum),
+    EnumValue {
+        en: &'a Enum,
+        field: &'a TsField,
+        ident: &'a str,
+    },
+}
+
+impl Symbol<'_> {
+    /// The kind of the symbol, like `class`, `method`, or `enum_value`.
+    pub fn kind(&self) -> &'static str {
+        match &self.item {
+            SymbolItem::Class(_) => "class",
+            SymbolItem::Field { .. } => "field",
+            SymbolItem::Function(function) if function.is_method => "method",
+            SymbolItem::Function(_) => "function",
+            SymbolItem::Alias(_) => "alias",
+            SymbolItem::Enum(_) => "enum",
+            SymbolItem::EnumValue { .. } => "enum_value",
+        }
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        match &self.item {
+            SymbolItem::Class(class) => class.description.as_deref(),
+            SymbolItem::Field { field, .. } => field.description.as_deref(),
+            SymbolItem::Function(function) => function.description.as_deref(),
+            SymbolItem::Alias(alias) => alias.description.as_deref(),
+            SymbolItem::Enum(en) => en.description.as_deref(),
+            SymbolItem::EnumValue { field, .. } => field.description.as_deref(),
+        }
+    }
+}
+
+/// Every class followed by its fields, then every function, alias, and enum followed by
+/// its values, which is the order search indexes and the symbols manifest list them in.
+///
+/// Enum entries without an identifier name, like `[1] = ...`, aren't symbols.
+pub fn symbols<'a>(
+    classes: &'a [Class],
+    functions: impl IntoIterator<Item = &'a Function> + 'a,
+    aliases: &'a [Alias],
+    enums: &'a [Enum],
+) -> impl Iterator<Item = Symbol<'a>> + 'a {
+    let classes = classes.iter().flat_map(|class| {
+        let fields = class.fields().into_iter().map(move |field| {
+            let field_name = field.ident_type.format_as_table_field_name();
+            let connector = if field_name.starts_with('[') { "" } else { "." };
+            Symbol {
+                name: format!("{}{connector}{field_name}", class.name),
+                item: SymbolItem::Field {
+                    class,
+                    field: Box::new(field),
+                    field_name,
+                },
+            }
+        });
+
+        std::iter::once(Symbol {
+            name: class.name.clone(),
+            item: SymbolItem::Class(class),
+        })
+        .chain(fields)
+    });
+
+    let functions = functions.into_iter().map(|function| Symbol {
+        name: function.qualified_name(),
+        item: SymbolItem::Function(function),
+    });
+
+    let aliases = aliases.iter().map(|alias| Symbol {
+        name: alias.name.clone(),
+        item: SymbolItem::Alias(alias),
+    });
+
+    let enums = enums.iter().flat_map(|en| {
+        let values = en.fields.iter().filter_map(move |field| {
+            let Some(FieldName::Ident(ident)) = field.name.as_ref() else {
+                return None;
+            };
+            Some(Symbol {
+                name: format!("{}.{ident}", en.name),
+                item: SymbolItem::EnumValue { en, field, ident },
+            })
+        });
+
+        std::iter::once(Symbol {
+            name: en.name.clone(),
+            item: SymbolItem::Enum(en),
+        })
+        .chain(values)
+    });
+
+    classes.chain(functions).chain(aliases).chain(enums)
+}
+
+/// Render the search index, a JSON array of entries.
+pub fn render_index(entries: &[SearchEntry]) -> String {
+    serde_json::to_string_pretty(entries).unwrap() + "\n"
+}
+
+/// Strip the markdown from a description, joining its paragraphs and lines with single spaces.
+pub fn plain_text(description: &str) -> String {
+    let text = match markdown::to_mdast(description, &ParseOptions::gfm()) {
+        Ok(Node::Root(root)) => root
+            .children
+            .iter()
+            .map(|block| block.to_string())
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => description.to_string(),
+    };
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Strip the markdown from a description and cut it to about [`SNIPPET_LEN`] characters,
+/// ending at a word boundary with `…` if it's cut.
+fn snippet(description: &str) -> String {
+    let text = plain_text(description);
+
+    if text.chars().count() <= SNIPPET_LEN {
+        return text;
+    }
+
+    let cut = text.chars().take(SNIPPET_LEN).collect::<String>();
+    let cut = match cut.rfind(' ') {
+        Some(space) => &cut[..space],
+        None => &cut,
+    };
+
+    format!(
+        "{}…",
+        cut.trim_end_matches(|ch: char| ch.is_ascii_punctuation())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod snippets {
+        use super::*;
+
+        #[test]
+        fn markdown_is_stripped() {
+            assert_eq!(
+                snippet(
+                    "Open a **window** with `open()`.\n\nSee [the guide](https://example.com)."
+                ),
+                "Open a window with open(). See the guide."
+            );
+        }
+
+        #[test]
+        fn long_descriptions_are_cut_at_a_word() {
+            let description = "word ".repeat(40);
+            let snippet = snippet(&description);
+
+            assert!(snippet.chars().count() <= SNIPPET_LEN + 1);
+            assert!(snippet.ends_with("word…"));
+        }
+    }
+
+    mod symbols {
+        use super::*;
+
+        use crate::state::parse_str;
+
+        #[test]
+        fn symbols_are_listed_by_kind_with_their_members() {
+            let processor = parse_str(
+                r#"
+---@class Window
+---@field title string
+local Window = {}
+
+---@alias Direction "left" | "right"
+
+---@enum State
+local State = {
+    focused = 1,
+    [2] = "unnamed",
+}
+
+---Close the window.
+function Window:close() end
+"#,
+            );
+
+            let symbols = symbols(
+                &processor.classes,
+                &processor.functions,
+                &processor.aliases,
+                &processor.enums,
+            )
+            .map(|symbol| (symbol.kind(), symbol.name))
+            .collect::<Vec<_>>();
+
+            assert_eq!(
+                symbols,
+                [
+                    ("class", "Window".to_string()),
+                    ("field", "Window.title".to_string()),
+                    ("method", "Window:close".to_string()),
+                    ("alias", "Direction".to_string()),
+                    ("enum", "State".to_string()),
+                    ("enum_value", "State.focused".to_string()),
+                ]
+            );
+        }
+    }
+}
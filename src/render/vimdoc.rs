@@ -0,0 +1,638 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    annotation::{Alias, Class, Enum, Function},
+    processor::Processor,
+    treesitter::FieldName,
+    types::Type,
+};
+
+use super::{
+    dedent_continuation_lines, diff_pages, normalize_line_endings, value_placeholder, write_pages,
+    OutputEncoding, Renderer, WriteStats,
+};
+
+/// The width help files are laid out to, matching Neovim's own `textwidth=78`.
+const WIDTH: usize = 78;
+
+/// Renders a single Neovim `:help` file.
+pub struct VimdocRenderer {
+    out_dir: PathBuf,
+    name: String,
+    output_encoding: OutputEncoding,
+}
+
+impl VimdocRenderer {
+    /// Create a renderer that writes `{name}.txt` into `out_dir`.
+    ///
+    /// `name` also prefixes every tag, so a class `Foo` is tagged `*{name}-Foo*`.
+    pub fn new(out_dir: PathBuf, name: String) -> Self {
+        Self {
+            out_dir,
+            name,
+            output_encoding: OutputEncoding::default(),
+        }
+    }
+
+    /// Set the encoding the help file is written in.
+    pub fn output_encoding(mut self, output_encoding: OutputEncoding) -> Self {
+        self.output_encoding = output_encoding;
+        self
+    }
+}
+
+// The help file usually goes in a directory of other plugins' help files, so there's no
+// manifest and nothing is ever deleted
+impl Renderer for VimdocRenderer {
+    fn out_dir(&self) -> &Path {
+        &self.out_dir
+    }
+
+    fn encoding(&self) -> OutputEncoding {
+        self.output_encoding
+    }
+
+    fn render_pages(&self, processor: Processor) -> Vec<(PathBuf, String)> {
+        vec![(
+            PathBuf::from(format!("{}.txt", self.name)),
+            self.render_help(processor),
+        )]
+    }
+
+    fn render(&self, processor: Processor) -> WriteStats {
+        write_pages(
+            &self.out_dir,
+            &self.render_pages(processor),
+            self.output_encoding,
+        )
+    }
+
+    fn diff(&self, processor: Processor) -> String {
+        diff_pages(&self.out_dir, &self.render_pages(processor), &[])
+    }
+}
+
+impl VimdocRenderer {
+    /// Render the contents of the help file.
+    fn render_help(&self, processor: Processor) -> String {
+        let Processor {
+            classes,
+            aliases,
+            mut functions,
+            enums,
+            ..
+        } = processor;
+
+        let known_types = classes
+            .iter()
+            .map(|class| class.name.clone())
+            .chain(aliases.iter().map(|alias| alias.name.clone()))
+            .chain(enums.iter().map(|en| en.name.clone()))
+            .collect::<HashSet<_>>();
+
+        let mut help = tagged_line(&format!("*{}.txt*", self.name), "Generated by lcat", WIDTH);
+        help.push('\n');
+
+        let mut class_sections = Vec::new();
+        for class in classes.iter() {
+            let mut class_functions = Vec::new();
+            functions.retain(|func| {
+                if func.table.as_ref() == Some(&class.name) {
+                    class_functions.push(func.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+
+            class_sections.push(self.generate_class(class, &class_functions, &known_types));
+        }
+
+        let alias_sections = aliases
+            .iter()
+            .map(|alias| self.generate_alias(alias, &known_types))
+            .collect::<Vec<_>>();
+
+        let enum_sections = enums
+            .iter()
+            .map(|en| self.generate_enum(en))
+            .collect::<Vec<_>>();
+
+        let function_sections = functions
+            .iter()
+            .map(|func| self.generate_function(func, &known_types))
+            .collect::<Vec<_>>();
+
+        for (title, tag, sections) in [
+            ("CLASSES", "classes", class_sections),
+            ("ALIASES", "aliases", alias_sections),
+            ("ENUMS", "enums", enum_sections),
+            ("FUNCTIONS", "functions", function_sections),
+        ] {
+            if sections.is_empty() {
+                continue;
+            }
+
+            help.push('\n');
+            help.push_str(&"=".repeat(WIDTH));
+            help.push('\n');
+            help.push_str(&self.heading(title, tag));
+            help.push('\n');
+
+            for section in sections {
+                help.push_str(&section);
+            }
+        }
+
+        help.push_str("\n vim:tw=78:ts=8:noet:ft=help:norl:\n");
+
+        normalize_line_endings(&help)
+    }
+
+    fn generate_class(
+        &self,
+        class: &Class,
+        functions: &[Function],
+        known_types: &HashSet<String>,
+    ) -> String {
+        let parent = class
+            .parent
+            .as_ref()
+            .map(|ty| format!(" : {}", self.format_type(ty, known_types)))
+            .unwrap_or_default();
+
+        let mut contents = format!(
+            "\n{}\n",
+            self.heading(&format!("Class {}{parent}", class.name), &class.name)
+        );
+        contents.push_str(&format_description(class.description.as_deref(), 4));
+
+        let fields = class.fields();
+        if !fields.is_empty() {
+            contents.push_str("\n    Fields ~\n");
+        }
+
+        for field in fields {
+            let name = field.ident_type.format_as_table_field_name();
+            let nullable = field
+                .ty
+                .as_ref()
+                .and_then(|ty| ty.nullable.then_some("?"))
+                .unwrap_or_default();
+            let ty = field
+                .ty
+                .as_ref()
+                .map(|ty| format!(": {}", self.format_type(ty, known_types)))
+                .unwrap_or_default();
+            // Multiline values like inline tables would break the layout, so leave them out
+            let value = field
+                .value
+                .filter(|value| !value.contains('\n'))
+                .map(|value| format!(" = {value}"))
+                .unwrap_or_default();
+
+            contents.push('\n');
+            contents.push_str(&self.heading(
+                &format!("    {name}{nullable}{ty}{value}"),
+                &format!("{}.{name}", class.name),
+            ));
+            contents.push('\n');
+            if let Some(default) = field.default {
+                contents.push_str(&format!("        Default: `{default}`\n"));
+            }
+            contents.push_str(&format_description(field.description.as_deref(), 8));
+        }
+
+        if !functions.is_empty() {
+            contents.push_str("\n    Functions ~\n");
+        }
+
+        for func in functions {
+            contents.push_str(&self.generate_function(func, known_types));
+        }
+
+        contents
+    }
+
+    fn generate_alias(&self, alias: &Alias, known_types: &HashSet<String>) -> String {
+        let mut contents = format!(
+            "\n{}\n",
+            self.heading(
+                &format!("Alias {}", alias.name_with_type_params()),
+                &alias.name
+            )
+        );
+        contents.push_str(&format_description(alias.description.as_deref(), 4));
+
+        if !alias.types.is_empty() {
+            contents.push_str("\n    Aliased types ~\n");
+        }
+
+        for (ty, description) in alias.types.iter() {
+            contents.push_str(&format!("      - {}\n", self.format_type(ty, known_types)));
+            contents.push_str(&format_description(description.as_deref(), 8));
+        }
+
+        contents
+    }
+
+    fn generate_enum(&self, en: &Enum) -> String {
+        let mut contents = format!(
+            "\n{}\n",
+            self.heading(&format!("Enum {}", en.name), &en.name)
+        );
+        contents.push_str(&format_description(en.description.as_deref(), 4));
+
+        let fields = en
+            .fields
+            .iter()
+            .filter_map(|field| match field.name.as_ref() {
+                Some(FieldName::Ident(ident)) => Some((ident, field)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if !fields.is_empty() {
+            let title = if en.is_key { "Values" } else { "Fields" };
+            contents.push_str(&format!("\n    {title} ~\n"));
+        }
+
+        for (ident, field) in fields {
+            // Headings are a single line, so multi-line values, like functions, are shown
+            // in full in a code block under one
+            let multi_line = !en.is_key && field.value.contains('\n');
+            let line = if en.is_key {
+                format!("    \"{ident}\"")
+            } else if multi_line {
+                format!(
+                    "    {}.{ident} = {}",
+                    en.name,
+                    value_placeholder(&field.value)
+                )
+            } else {
+                format!("    {}.{ident} = {}", en.name, field.value)
+            };
+
+            contents.push('\n');
+            contents.push_str(&self.heading(&line, &format!("{}.{ident}", en.name)));
+            contents.push('\n');
+            if multi_line {
+                let block = format!("```lua\n{}\n```", dedent_continuation_lines(&field.value));
+                contents.push_str(&format_description(Some(&block), 8));
+            }
+            contents.push_str(&format_description(field.description.as_deref(), 8));
+        }
+
+        contents
+    }
+
+    fn generate_function(&self, func: &Function, known_types: &HashSet<String>) -> String {
+        let table = func
+            .table
+            .as_ref()
+            .map(|table| {
+                let connector = if func.is_method { ":" } else { "." };
+                format!("{table}{connector}")
+            })
+            .unwrap_or_default();
+        let qualified_name = format!("{table}{}", func.name);
+
+        let params_short = func
+            .params
+            .iter()
+            .map(|param| format!("{{{}}}", param.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut contents = format!(
+            "\n{}\n",
+            self.heading(
+                &format!("{qualified_name}({params_short})"),
+                &format!("{qualified_name}()")
+            )
+        );
+        contents.push_str(&format_description(func.description.as_deref(), 4));
+
+        if !func.params.is_empty() {
+            contents.push_str("\n    Parameters: ~\n");
+        }
+
+        for param in func.params.iter() {
+            let nullable = param.ty.nullable.then_some("?").unwrap_or_default();
+            let description = param
+                .description
+                .as_ref()
+                .map(|desc| format!(" {desc}"))
+                .unwrap_or_default();
+            let default = param
+                .default
+                .as_ref()
+                .map(|default| format!(" (Default: `{default}`)"))
+                .unwrap_or_default();
+
+            contents.push_str(&wrap(
+                &format!(
+                    "- {{{}}}{nullable} ({}){description}{default}",
+                    param.name,
+                    self.format_type(&param.ty, known_types)
+                ),
+                6,
+            ));
+        }
+
+        if !func.returns.is_empty() {
+            contents.push_str("\n    Returns: ~\n");
+        }
+
+        for ret in func.returns.iter() {
+            let name = ret
+                .name
+                .as_ref()
+                .map(|name| format!("{{{name}}} "))
+                .unwrap_or_default();
+            let description = ret
+                .description
+                .as_ref()
+                .map(|desc| format!(" {desc}"))
+                .unwrap_or_default();
+
+            contents.push_str(&wrap(
+                &format!(
+                    "- {name}({}){description}",
+                    self.format_type(&ret.ty, known_types)
+                ),
+                6,
+            ));
+        }
+
+        if !func.errors.is_empty() {
+            contents.push_str("\n    Errors: ~\n");
+        }
+
+        for (ty, desc) in func.errors.iter() {
+            let description = desc
+                .as_ref()
+                .map(|desc| format!(" {desc}"))
+                .unwrap_or_default();
+
+            contents.push_str(&wrap(
+                &format!("- ({}){description}", self.format_type(ty, known_types)),
+                6,
+            ));
+        }
+
+        if !func.sees.is_empty() {
+            contents.push_str("\n    See also: ~\n");
+        }
+
+        for see in func.sees.iter() {
+            let description = see
+                .description
+                .as_ref()
+                .map(|desc| format!(": {desc}"))
+                .unwrap_or_default();
+
+            // Vim highlights URLs by itself, so they don't need to be tags
+            let reference = if see.is_url() {
+                see.ident.clone()
+            } else {
+                format!("|{}|", self.tag(&see.ident))
+            };
+
+            contents.push_str(&wrap(&format!("- {reference}{description}"), 6));
+        }
+
+        contents
+    }
+
+    /// Format a type, turning documented type names into `|tag|` links.
+    fn format_type(&self, ty: &Type, known_types: &HashSet<String>) -> String {
+        ty.format_with_names(&|name| {
+            if known_types.contains(name) {
+                format!("|{}|", self.tag(name))
+            } else {
+                name.to_string()
+            }
+        })
+    }
+
+    fn tag(&self, name: &str) -> String {
+        format!("{}-{name}", self.name)
+    }
+
+    /// A line of text with the `*tag*` for `name` right-aligned after it.
+    fn heading(&self, text: &str, name: &str) -> String {
+        tagged_line(text, &format!("*{}*", self.tag(name)), WIDTH)
+    }
+}
+
+/// Right-align `right` after `left` within `width` columns.
+///
+/// If both don't fit on one line, `right` is put on its own line above `left`,
+/// which is where Vim looks for tags belonging to a heading anyway.
+fn tagged_line(left: &str, right: &str, width: usize) -> String {
+    let left_len = left.chars().count();
+    let right_len = right.chars().count();
+
+    if left_len + 1 + right_len <= width {
+        format!("{left}{}{right}", " ".repeat(width - left_len - right_len))
+    } else {
+        format!(
+            "{}{right}\n{left}",
+            " ".repeat(width.saturating_sub(right_len))
+        )
+    }
+}
+
+/// Indent a description, converting fenced code blocks into help file code blocks.
+fn format_description(description: Option<&str>, indent: usize) -> String {
+    let Some(description) = description else {
+        return String::new();
+    };
+
+    let mut formatted = String::new();
+    let mut in_code_block = false;
+
+    for line in description.lines() {
+        if let Some(lang) = line.trim().strip_prefix("```") {
+            if in_code_block {
+                formatted.push_str("<\n");
+            } else {
+                formatted.push_str(&format!("{}>{lang}\n", " ".repeat(indent)));
+            }
+            in_code_block = !in_code_block;
+        } else if in_code_block {
+            formatted.push_str(&format!("{}{line}\n", " ".repeat(indent + 4)));
+        } else if line.trim().is_empty() {
+            formatted.push('\n');
+        } else {
+            formatted.push_str(&wrap(line.trim(), indent));
+        }
+    }
+
+    if in_code_block {
+        formatted.push_str("<\n");
+    }
+
+    formatted
+}
+
+/// Word-wrap a line of text to [`WIDTH`] characters, indenting every line by `indent`.
+fn wrap(text: &str, indent: usize) -> String {
+    let mut wrapped = String::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if !line.is_empty() && indent + line.chars().count() + 1 + word.chars().count() > WIDTH {
+            wrapped.push_str(&format!("{}{line}\n", " ".repeat(indent)));
+            line.clear();
+        }
+
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+
+    if !line.is_empty() {
+        wrapped.push_str(&format!("{}{line}\n", " ".repeat(indent)));
+    }
+
+    wrapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::state::parse_str;
+
+    fn render(source: &str) -> String {
+        VimdocRenderer::new(PathBuf::new(), "lcat".into()).render_help(parse_str(source))
+    }
+
+    mod tags {
+        use super::*;
+
+        #[test]
+        fn symbols_get_right_aligned_tags() {
+            let help = render(
+                r#"
+---A window.
+---@class Window
+---@field title string The title
+local Window = {}
+
+---Resize the window.
+---@param width integer
+function Window:resize(width) end
+"#,
+            );
+
+            let lines = help.lines().collect::<Vec<_>>();
+
+            for (text, tag) in [
+                ("*lcat.txt*", ""),
+                ("CLASSES", "*lcat-classes*"),
+                ("Class Window", "*lcat-Window*"),
+                ("    title: string", "*lcat-Window.title*"),
+                ("Window:resize({width})", "*lcat-Window:resize()*"),
+            ] {
+                let line = lines
+                    .iter()
+                    .find(|line| line.starts_with(text) && line.ends_with(tag))
+                    .unwrap_or_else(|| panic!("no line for `{text}` tagged `{tag}`"));
+                assert!(line.chars().count() <= WIDTH);
+            }
+
+            let tagged = lines
+                .iter()
+                .filter(|line| line.ends_with('*'))
+                .collect::<Vec<_>>();
+            assert!(tagged.iter().all(|line| line.chars().count() == WIDTH));
+        }
+
+        #[test]
+        fn documented_types_are_linked() {
+            let help = render(
+                r#"
+---@class Window
+local Window = {}
+
+---@param other Window
+---@return string
+function Window:swap(other) end
+"#,
+            );
+
+            assert!(help.contains("      - {other} (|lcat-Window|)\n"));
+            assert!(help.contains("      - (string)\n"));
+        }
+
+        #[test]
+        fn long_headings_put_the_tag_above() {
+            assert_eq!(
+                tagged_line(&"a".repeat(75), "*tag*", 78),
+                format!("{}*tag*\n{}", " ".repeat(73), "a".repeat(75))
+            );
+        }
+    }
+
+    mod enums {
+        use super::*;
+
+        #[test]
+        fn multi_line_values_are_code_blocks_under_a_one_line_heading() {
+            let help = render(
+                r#"
+---@enum Handlers
+local Handlers = {
+    ---Called on click
+    on_click = function(button)
+        print(button)
+    end,
+}
+"#,
+            );
+
+            let heading = help
+                .lines()
+                .find(|line| line.ends_with("*lcat-Handlers.on_click*"))
+                .unwrap();
+            assert!(heading.starts_with("    Handlers.on_click = function(button) ... end "));
+            let block = [
+                "        >lua",
+                "            function(button)",
+                "                print(button)",
+                "            end",
+                "<",
+                "        Called on click",
+            ]
+            .join("\n");
+            assert!(help.contains(&format!("*lcat-Handlers.on_click*\n{block}\n")));
+        }
+    }
+
+    mod descriptions {
+        use super::*;
+
+        #[test]
+        fn code_blocks_are_converted() {
+            assert_eq!(
+                format_description(Some("Usage:\n```lua\nlocal x = 1\n```"), 4),
+                "    Usage:\n    >lua\n        local x = 1\n<\n"
+            );
+        }
+
+        #[test]
+        fn wrapping_counts_characters_not_bytes() {
+            let text = ["ééééééééé"; 7].join(" ");
+            assert_eq!(text.chars().count(), 69);
+
+            assert_eq!(wrap(&text, 4), format!("    {text}\n"));
+        }
+    }
+}
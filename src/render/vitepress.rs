@@ -1,43 +1,254 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
 
 use markdown::ParseOptions;
 
-use crate::{annotation::Function, processor::Processor, treesitter::FieldName, types::Metatype};
+use crate::{
+    annotation::{Alias, Class, Enum, Function, Location, Param, Scope, TsField},
+    processor::Processor,
+    treesitter::FieldName,
+    types::{unquote, LinkOptions, Literal, Metatype, SectionDirs, Type, TypeInner},
+};
 
-use super::Renderer;
+use super::{
+    code_fence, dedent_continuation_lines, escape_html, normalize_line_endings,
+    search::{self, SearchEntry, SymbolItem},
+    OutputEncoding, Renderer,
+};
+
+/// The default maximum length of a field value before it gets elided.
+pub const DEFAULT_MAX_VALUE_LEN: usize = 100;
+
+/// Bumped whenever the shape of `symbols.json` changes in a way that could break consumers.
+const SYMBOLS_SCHEMA_VERSION: u32 = 1;
+
+/// The page documenting functions that don't belong to a table, without `.md`.
+const FUNCTIONS_PAGE: &str = "functions";
+
+/// Field and return types that are unions of more members than this are listed one member
+/// per line instead of inline.
+const MAX_INLINE_UNION_LEN: usize = 4;
+
+/// How the aliased types of an alias are laid out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum AliasLayout {
+    /// A heading per aliased type followed by its description
+    #[default]
+    Sections,
+    /// A table of aliased types and their descriptions
+    Table,
+}
+
+/// How methods are shown in function signatures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum MethodStyle {
+    /// `Class:method(...)`
+    #[default]
+    Colon,
+    /// `Class.method(self, ...)`
+    Dot,
+}
 
 pub struct VitePressRenderer {
     out_dir: PathBuf,
     base_url: String,
+    max_value_len: usize,
+    emit_sources: bool,
+    alias_layout: AliasLayout,
+    version_matrix: bool,
+    symbols_manifest: bool,
+    search_index: bool,
+    function_pages: bool,
+    no_empty_sections: bool,
+    inline_descriptions: bool,
+    group_optional_params: bool,
+    verbose_types: bool,
+    link_literals: bool,
+    method_style: MethodStyle,
+    show_self: bool,
+    section_dirs: SectionDirs,
+    source_url_template: Option<String>,
+    output_encoding: OutputEncoding,
 }
 
 impl VitePressRenderer {
     pub fn new(out_dir: PathBuf, base_url: Option<String>) -> Self {
         Self {
             out_dir,
-            base_url: base_url.unwrap_or("/".into()),
+            base_url: normalize_base_url(base_url.as_deref().unwrap_or_default()),
+            max_value_len: DEFAULT_MAX_VALUE_LEN,
+            emit_sources: false,
+            alias_layout: AliasLayout::default(),
+            version_matrix: false,
+            symbols_manifest: false,
+            search_index: false,
+            function_pages: false,
+            no_empty_sections: false,
+            inline_descriptions: false,
+            group_optional_params: false,
+            verbose_types: false,
+            link_literals: false,
+            method_style: MethodStyle::default(),
+            show_self: false,
+            section_dirs: SectionDirs::default(),
+            source_url_template: None,
+            output_encoding: OutputEncoding::default(),
         }
     }
+
+    /// Set the maximum length of a rendered field value.
+    ///
+    /// Longer values are elided and point the reader at the source instead.
+    pub fn max_value_len(mut self, max_value_len: usize) -> Self {
+        self.max_value_len = max_value_len;
+        self
+    }
+
+    /// Embed the Lua source of each function in a collapsed block under its documentation.
+    pub fn emit_sources(mut self, emit_sources: bool) -> Self {
+        self.emit_sources = emit_sources;
+        self
+    }
+
+    /// Set how the aliased types of aliases are laid out.
+    pub fn alias_layout(mut self, alias_layout: AliasLayout) -> Self {
+        self.alias_layout = alias_layout;
+        self
+    }
+
+    /// Generate a `versions.md` page listing symbols by the version that introduced them.
+    pub fn version_matrix(mut self, version_matrix: bool) -> Self {
+        self.version_matrix = version_matrix;
+        self
+    }
+
+    /// Write a `symbols.json` manifest of every documented symbol and the page it's on,
+    /// for editor extensions and other tools.
+    pub fn symbols_manifest(mut self, symbols_manifest: bool) -> Self {
+        self.symbols_manifest = symbols_manifest;
+        self
+    }
+
+    /// Write a `search-index.json` of every symbol's name, kind, description snippet, and URL.
+    pub fn search_index(mut self, search_index: bool) -> Self {
+        self.search_index = search_index;
+        self
+    }
+
+    /// Give each class function its own page under the classes directory and link to
+    /// it from the class page instead of inlining it.
+    pub fn function_pages(mut self, function_pages: bool) -> Self {
+        self.function_pages = function_pages;
+        self
+    }
+
+    /// Remove section headings that have nothing under them from every page.
+    pub fn no_empty_sections(mut self, no_empty_sections: bool) -> Self {
+        self.no_empty_sections = no_empty_sections;
+        self
+    }
+
+    /// Render fields and parameters with a one-line description as a single
+    /// `name: type — description` line instead of a heading and a paragraph.
+    pub fn inline_descriptions(mut self, inline_descriptions: bool) -> Self {
+        self.inline_descriptions = inline_descriptions;
+        self
+    }
+
+    /// List required parameters first and nullable or defaulted ones under
+    /// an "Optional parameters" subsection.
+    pub fn group_optional_params(mut self, group_optional_params: bool) -> Self {
+        self.group_optional_params = group_optional_params;
+        self
+    }
+
+    /// Spell out details of types that are easy to miss, like tuples being fixed-length.
+    pub fn verbose_types(mut self, verbose_types: bool) -> Self {
+        self.verbose_types = verbose_types;
+        self
+    }
+
+    /// Link literal types like `"focused"` to the enum value they match.
+    pub fn link_literals(mut self, link_literals: bool) -> Self {
+        self.link_literals = link_literals;
+        self
+    }
+
+    /// Set how methods are shown in function signatures.
+    pub fn method_style(mut self, method_style: MethodStyle) -> Self {
+        self.method_style = method_style;
+        self
+    }
+
+    /// Keep a `self` parameter annotated with `---@param self` on a method.
+    ///
+    /// It's left out by default, as methods declared with `:` already take `self`.
+    pub fn show_self(mut self, show_self: bool) -> Self {
+        self.show_self = show_self;
+        self
+    }
+
+    /// Set the subdirectories class, alias, and enum pages are written to, which links
+    /// to them follow.
+    pub fn section_dirs(mut self, section_dirs: SectionDirs) -> Self {
+        self.section_dirs = section_dirs;
+        self
+    }
+
+    /// Link the source location of each class, field, and function with a URL template
+    /// like `https://github.com/user/repo/blob/main/{file}#L{line}`.
+    ///
+    /// Without a template, locations are shown as plain code spans.
+    pub fn source_url_template(mut self, source_url_template: Option<String>) -> Self {
+        self.source_url_template = source_url_template;
+        self
+    }
+
+    /// Set the encoding pages are written in.
+    pub fn output_encoding(mut self, output_encoding: OutputEncoding) -> Self {
+        self.output_encoding = output_encoding;
+        self
+    }
 }
 
 impl Renderer for VitePressRenderer {
-    type Output = ();
-
-    fn render(&mut self, processor: Processor) -> Self::Output {
-        let dir = tempfile::tempdir().unwrap();
-        let root_dir = dir.path();
-        let class_dir = root_dir.join("classes");
-        let alias_dir = root_dir.join("aliases");
-        let enum_dir = root_dir.join("enums");
-        std::fs::create_dir_all(&class_dir).unwrap();
-        std::fs::create_dir_all(&alias_dir).unwrap();
-        std::fs::create_dir_all(&enum_dir).unwrap();
+    fn out_dir(&self) -> &Path {
+        &self.out_dir
+    }
+
+    fn encoding(&self) -> OutputEncoding {
+        self.output_encoding
+    }
+
+    fn render_pages(&self, processor: Processor) -> Vec<(PathBuf, String)> {
+        let classes_dir = &self.section_dirs.classes;
+        let aliases_dir = &self.section_dirs.aliases;
+        let enums_dir = &self.section_dirs.enums;
+        let mut pages = Vec::new();
+
+        // Maps class names to the fields of each of their parents, nearest parent first
+        let inherited_fields = processor
+            .classes
+            .iter()
+            .map(|class| {
+                let parents = processor
+                    .class_parents(class)
+                    .into_iter()
+                    .map(|parent| (parent.name.clone(), parent.fields()))
+                    .collect::<Vec<_>>();
+                (class.name.clone(), parents)
+            })
+            .collect::<HashMap<_, _>>();
 
         let Processor {
             classes,
             aliases,
-            mut functions,
+            functions,
             enums,
+            ..
         } = processor;
 
         let ident_lookup = {
@@ -58,6 +269,118 @@ impl Renderer for VitePressRenderer {
             map
         };
 
+        // Only class functions get their own pages, and only with `function_pages`
+        let has_page = |func: &Function| {
+            self.function_pages
+                && func
+                    .table
+                    .as_ref()
+                    .is_some_and(|table| matches!(ident_lookup.get(table), Some(Metatype::Class)))
+        };
+
+        let mut page_names =
+            function_page_names(functions.iter().filter(|func| has_page(func))).into_iter();
+        let mut functions = functions
+            .iter()
+            .map(|func| {
+                let page_name = has_page(func).then(|| page_names.next()).flatten();
+                (func.clone(), page_name)
+            })
+            .collect::<Vec<_>>();
+
+        // Maps `Table.name` to the page of a class function when functions get their own pages
+        let mut function_links = HashMap::new();
+
+        for (func, page_name) in functions.iter() {
+            if let (Some(table), Some(page_name)) = (func.table.as_ref(), page_name) {
+                function_links
+                    .entry(format!("{table}.{}", func.name))
+                    .or_insert_with(|| format!("{classes_dir}/{page_name}"));
+            }
+        }
+
+        let member_links =
+            self.generate_member_links(&classes, &functions, &enums, &function_links);
+
+        // Maps literal types to the enum values they match when `link_literals` is set.
+        // Literals that match values of more than one enum are ambiguous and left alone.
+        let literal_links = if self.link_literals {
+            let mut links = HashMap::new();
+            let mut ambiguous = Vec::new();
+
+            for en in enums.iter() {
+                for field in en.fields.iter() {
+                    let Some(FieldName::Ident(ident)) = field.name.as_ref() else {
+                        continue;
+                    };
+
+                    let key = if en.is_key {
+                        Literal::String(ident.clone()).link_key()
+                    } else if unquote(&field.value) != field.value {
+                        Literal::String(field.value.clone()).link_key()
+                    } else {
+                        field.value.trim().to_string()
+                    };
+
+                    let Some(link) = member_links.get(&format!("{}.{ident}", en.name)) else {
+                        continue;
+                    };
+
+                    if links.insert(key.clone(), link.clone()).is_some() {
+                        ambiguous.push(key);
+                    }
+                }
+            }
+
+            for key in ambiguous {
+                links.remove(&key);
+            }
+
+            links
+        } else {
+            HashMap::new()
+        };
+
+        pages.push((
+            PathBuf::from("sidebar.json"),
+            self.generate_sidebar(&classes, &aliases, &functions, &enums),
+        ));
+
+        if self.version_matrix {
+            pages.push((
+                PathBuf::from("versions.md"),
+                self.generate_version_matrix(&classes, &aliases, &functions, &enums, &member_links),
+            ));
+        }
+
+        for module in module_names(&classes) {
+            pages.push((
+                PathBuf::from("modules").join(format!("{module}.md")),
+                self.generate_module_page(module, &classes, &functions, &member_links),
+            ));
+        }
+
+        if self.symbols_manifest {
+            pages.push((
+                PathBuf::from("symbols.json"),
+                self.generate_symbols_manifest(
+                    &classes,
+                    &aliases,
+                    &functions,
+                    &enums,
+                    &ident_lookup,
+                    &member_links,
+                ),
+            ));
+        }
+
+        if self.search_index {
+            pages.push((
+                PathBuf::from(search::FILE_NAME),
+                self.generate_search_index(&classes, &aliases, &functions, &enums, &member_links),
+            ));
+        }
+
         for class in classes {
             let name = class.name.clone();
             let desc = class.description.clone().unwrap_or_default();
@@ -67,24 +390,77 @@ impl Renderer for VitePressRenderer {
                 .map(|ty| {
                     format!(
                         " : <code>{}</code>",
-                        ty.format_with_links(&ident_lookup, &self.base_url)
+                        self.format_type(ty, &ident_lookup, &literal_links)
                     )
                 })
                 .unwrap_or_default();
 
             let mut class_functions = Vec::new();
-            functions.retain(|func| {
+            functions.retain(|(func, page_name)| {
                 if func.table.as_ref().is_some_and(|table| table == &name) {
-                    class_functions.push(func.clone());
+                    class_functions.push((func.clone(), page_name.clone()));
                     false
                 } else {
                     true
                 }
             });
 
+            let class_fields = class.fields();
+
+            // Fields redeclared by the class or a nearer parent are overridden
+            let mut seen_fields = class_fields
+                .iter()
+                .map(|field| field.ident_type.format_as_table_field_name())
+                .collect::<HashSet<_>>();
+
+            let inherited = inherited_fields
+                .get(&name)
+                .into_iter()
+                .flatten()
+                .filter_map(|(parent_name, parent_fields)| {
+                    let links = parent_fields
+                        .iter()
+                        .filter(|field| !matches!(field.scope, Some(Scope::Private)))
+                        .filter(|field| {
+                            seen_fields.insert(field.ident_type.format_as_table_field_name())
+                        })
+                        .map(|field| {
+                            let field_name = field.ident_type.format_as_table_field_name();
+                            let nullable = field
+                                .ty
+                                .as_ref()
+                                .and_then(|ty| ty.nullable.then_some("?"))
+                                .unwrap_or_default();
+                            let ty = field
+                                .ty
+                                .as_ref()
+                                .map(|ty| {
+                                    format!(
+                                        ": <code>{}</code>",
+                                        self.format_type(ty, &ident_lookup, &literal_links)
+                                    )
+                                })
+                                .unwrap_or_default();
+
+                            format!(
+                                r#"- <code><a href="{}{classes_dir}/{parent_name}">{field_name}{nullable}</a></code>{ty}"#,
+                                self.base_url,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+
+                    (!links.is_empty()).then(|| {
+                        format!(
+                            "### Inherited from `{parent_name}`\n\n{}\n",
+                            links.join("\n")
+                        )
+                    })
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
             let mut fields =
-                class
-                    .fields()
+                class_fields
                     .into_iter()
                     .map(|field| {
                         let description = field.description.unwrap_or_default();
@@ -101,38 +477,217 @@ impl Renderer for VitePressRenderer {
                             .as_ref()
                             .and_then(|ty| ty.nullable.then_some("?"))
                             .unwrap_or_default();
+                        let deprecated_badge = field
+                            .deprecated
+                            .as_ref()
+                            .map(|_| format!(" {DEPRECATED_BADGE}"))
+                            .unwrap_or_default();
+                        let deprecation = field
+                            .deprecated
+                            .as_deref()
+                            .map(deprecation_notice)
+                            .unwrap_or_default();
                         let name = field.ident_type.format_as_table_field_name();
+                        // Tables with fields of their own are expanded below instead
                         let value = field
                             .value
-                            .map(|value| format!(" = `{value}`"))
+                            .filter(|_| field.fields.is_empty())
+                            .map(|value| self.format_value(&value))
                             .unwrap_or_default();
                         let ty = field
                             .ty
+                            .as_ref()
                             .map(|ty| {
                                 format!(
                                     ": <code>{}</code>",
-                                    ty.format_with_links(&ident_lookup, &self.base_url)
+                                    self.format_type(ty, &ident_lookup, &literal_links)
                                 )
                             })
                             .unwrap_or_default();
 
+                        if self.inline_descriptions && !description.contains('\n') {
+                            let description = if description.is_empty() {
+                                String::new()
+                            } else {
+                                format!(" — {description}")
+                            };
+                            let default = field
+                                .default
+                                .map(|default| format!(" (Default: `{default}`)"))
+                                .unwrap_or_default();
+                            let since = field
+                                .since
+                                .as_ref()
+                                .map(|since| format!(" *(Since {since})*"))
+                                .unwrap_or_default();
+
+                            let nested_fields = if field.fields.is_empty() {
+                                String::new()
+                            } else {
+                                format!(
+                                    "\n{}",
+                                    self.format_nested_fields(
+                                        &field.fields,
+                                        "  ",
+                                        &ident_lookup,
+                                        &literal_links,
+                                    )
+                                )
+                            };
+
+                            return format!(
+                                "- `{name}{nullable}`{deprecated_badge}{ty}{value}{description}{default}{since}{nested_fields}\n"
+                            );
+                        }
+
+                        let default = field
+                            .default
+                            .map(|default| format!("Default: `{default}`\n\n"))
+                            .unwrap_or_default();
+
+                        let location = self.format_source(field.location.as_ref());
+                        let since = since_note(field.since.as_deref());
+
+                        let signature = match long_union(field.ty.as_ref()) {
+                            Some(members) => format!(
+                                "`{name}{nullable}`{value}: one of\n\n{}",
+                                self.format_union_list(members, "", &ident_lookup, &literal_links)
+                            ),
+                            None => format!("`{name}{nullable}`{ty}{value}"),
+                        };
+
+                        let nested_fields = if field.fields.is_empty() {
+                            String::new()
+                        } else {
+                            format!(
+                                "\n<details>\n<summary>Fields of <code>{name}</code></summary>\n\n{}\n\n</details>\n",
+                                self.format_nested_fields(
+                                    &field.fields,
+                                    "",
+                                    &ident_lookup,
+                                    &literal_links,
+                                )
+                            )
+                        };
+
                         format!(
-                            "### {name}{badge}\n\n`{name}{nullable}`{ty}{value}\n\n{description}\n",
+                            "### {name}{badge}{deprecated_badge}\n\n{signature}\n\n{since}{location}{deprecation}{default}{description}\n{nested_fields}",
                         )
                     })
                     .collect::<Vec<_>>()
                     .join("\n");
 
+            if !inherited.is_empty() {
+                if fields.is_empty() {
+                    fields = inherited;
+                } else {
+                    fields = format!("{fields}\n{inherited}");
+                }
+            }
+
             if !fields.is_empty() {
                 fields = format!("## Fields\n\n{fields}")
             }
 
-            let mut class_functions = class_functions
-                .into_iter()
-                .map(|func| generate_function_block(&func, &ident_lookup, &self.base_url))
+            // The operand the operator is applied to, like `vector` in `vector + Vector`
+            let operand = name.to_lowercase();
+
+            let mut operators = class
+                .operators
+                .iter()
+                .map(|operator| {
+                    let input = operator
+                        .input
+                        .as_ref()
+                        .map(|ty| self.format_type(ty, &ident_lookup, &literal_links));
+                    let result = self.format_type(&operator.result, &ident_lookup, &literal_links);
+                    let description = operator
+                        .description
+                        .as_ref()
+                        .map(|desc| format!(" - {desc}"))
+                        .unwrap_or_default();
+
+                    let expression = match (operator.op.as_str(), operator.symbol(), input) {
+                        ("call", _, input) => {
+                            format!("{operand}({})", input.unwrap_or_default())
+                        }
+                        ("index", _, input) => {
+                            format!("{operand}[{}]", input.unwrap_or_default())
+                        }
+                        (_, Some(symbol), Some(input)) => {
+                            format!("{operand} {} {input}", symbol.replace('<', "&lt;"))
+                        }
+                        (_, Some(symbol), None) => {
+                            format!("{}{operand}", symbol.replace('<', "&lt;"))
+                        }
+                        (op, None, input) => format!(
+                            "{op}{}",
+                            input.map(|input| format!("({input})")).unwrap_or_default()
+                        ),
+                    };
+
+                    format!("- <code>{expression} → {result}</code>{description}")
+                })
                 .collect::<Vec<_>>()
                 .join("\n");
 
+            if !operators.is_empty() {
+                operators = format!("## Operators\n\n{operators}\n");
+            }
+
+            let mut class_functions = if self.function_pages {
+                let class_functions = class_functions
+                    .into_iter()
+                    .filter_map(|(func, page_name)| Some((func, page_name?)))
+                    .collect::<Vec<_>>();
+
+                let links = class_functions
+                    .iter()
+                    .map(|(func, page_name)| {
+                        format!(
+                            r#"- <code><a href="{}{classes_dir}/{page_name}">{}</a></code>"#,
+                            self.base_url,
+                            func.qualified_name()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                for (func, page_name) in class_functions {
+                    let block = self.generate_function_block(
+                        &func,
+                        &ident_lookup,
+                        &member_links,
+                        &literal_links,
+                        1,
+                    );
+                    let frontmatter = frontmatter(
+                        &format!("Function {}", func.qualified_name()),
+                        func.description.as_deref().unwrap_or_default(),
+                    );
+                    pages.push((
+                        PathBuf::from(classes_dir).join(format!("{page_name}.md")),
+                        frontmatter + &sanitize_angle_brackets(block),
+                    ));
+                }
+
+                links
+            } else {
+                class_functions
+                    .into_iter()
+                    .map(|(func, _)| {
+                        self.generate_function_block(
+                            &func,
+                            &ident_lookup,
+                            &member_links,
+                            &literal_links,
+                            3,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
+
             if !class_functions.is_empty() {
                 class_functions = format!("## Functions\n\n{class_functions}");
             }
@@ -142,29 +697,65 @@ impl Renderer for VitePressRenderer {
                 .then_some(r#"<Badge type="tip" text="exact" />"#)
                 .unwrap_or_default();
 
-            let mut contents = format!(
-                r#"---
-outline: [2, 3]
----
+            let deprecated_badge = class
+                .deprecated
+                .as_ref()
+                .map(|_| format!(" {DEPRECATED_BADGE}"))
+                .unwrap_or_default();
+            let custom_badges = custom_badges(&class.badges);
+            let deprecation = class
+                .deprecated
+                .as_deref()
+                .map(deprecation_notice)
+                .unwrap_or_default();
+            let location = self.format_source(class.location.as_ref());
+            let since = since_note(class.since.as_deref());
 
-# Class `{name}`{parent}
+            let contents = format!(
+                r#"# Class `{name}`{parent}{deprecated_badge}{custom_badges}
 {exact_badge}
 
-{desc}
+{since}{location}{deprecation}{desc}
 
 {fields}
 
+{operators}
+
 {class_functions}"#
             );
 
-            contents = sanitize_angle_brackets(contents);
+            let contents =
+                frontmatter(&format!("Class {name}"), &desc) + &sanitize_angle_brackets(contents);
+
+            pages.push((
+                PathBuf::from(classes_dir).join(format!("{name}.md")),
+                contents,
+            ));
+        }
+
+        // Functions left after the class pages, which don't belong to a table or belong to
+        // one that isn't a class, like a module's `M.get`, are documented on a page of their own
+        let free_functions = functions
+            .iter()
+            .map(|(func, _)| {
+                self.generate_function_block(func, &ident_lookup, &member_links, &literal_links, 2)
+            })
+            .collect::<Vec<_>>();
 
-            let write_to = class_dir.join(format!("{name}.md"));
-            std::fs::write(write_to, contents).unwrap();
+        if !free_functions.is_empty() {
+            pages.push((
+                PathBuf::from(format!("{FUNCTIONS_PAGE}.md")),
+                frontmatter("Functions", "")
+                    + &sanitize_angle_brackets(format!(
+                        "# Functions\n\n{}",
+                        free_functions.join("\n")
+                    )),
+            ));
         }
 
         for alias in aliases {
             let name = alias.name.clone();
+            let title = alias.name_with_type_params();
             let desc = alias.description.clone().unwrap_or_default();
 
             let types_short = alias
@@ -173,45 +764,73 @@ outline: [2, 3]
                 .map(|(ty, _desc)| {
                     format!(
                         "<code>{}</code>",
-                        ty.format_with_links(&ident_lookup, &self.base_url)
+                        self.format_type(ty, &ident_lookup, &literal_links)
                     )
                 })
                 .collect::<Vec<_>>()
                 .join(" | ");
 
-            let mut types = alias
-                .types
-                .into_iter()
-                .map(|(ty, desc)| {
-                    format!(
-                        "### <code>{}</code>\n\n{}\n",
-                        ty.format_with_links(&ident_lookup, &self.base_url),
-                        desc.unwrap_or_default()
-                    )
-                })
-                .collect::<Vec<_>>()
-                .join("\n");
+            let mut types = match self.alias_layout {
+                AliasLayout::Sections => alias
+                    .types
+                    .into_iter()
+                    .map(|(ty, desc)| {
+                        format!(
+                            "### <code>{}</code>\n\n{}\n",
+                            self.format_type(&ty, &ident_lookup, &literal_links),
+                            desc.unwrap_or_default()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                AliasLayout::Table => {
+                    let rows = alias
+                        .types
+                        .into_iter()
+                        .map(|(ty, desc)| {
+                            format!(
+                                "| <code>{}</code> | {} |",
+                                escape_table_cell(&self.format_type(
+                                    &ty,
+                                    &ident_lookup,
+                                    &literal_links
+                                )),
+                                escape_table_cell(&desc.unwrap_or_default())
+                            )
+                        })
+                        .collect::<Vec<_>>();
+
+                    if rows.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            "| Variant | Description |\n| --- | --- |\n{}\n",
+                            rows.join("\n")
+                        )
+                    }
+                }
+            };
 
             if !types.is_empty() {
                 types = format!("## Aliased types\n\n{types}");
             }
 
+            let frontmatter = frontmatter(&format!("Alias {title}"), &desc);
+            let since = since_note(alias.since.as_deref());
             let contents = format!(
-                r#"---
-outline: [2, 3]
----
-
-# Alias `{name}`
+                r#"{frontmatter}# Alias `{title}`
 
-{types_short}
+{since}{types_short}
 
 {desc}
 
 {types}"#
             );
 
-            let write_to = alias_dir.join(format!("{name}.md"));
-            std::fs::write(write_to, contents).unwrap();
+            pages.push((
+                PathBuf::from(aliases_dir).join(format!("{name}.md")),
+                contents,
+            ));
         }
 
         for en in enums {
@@ -245,8 +864,13 @@ outline: [2, 3]
                     .iter()
                     .filter_map(|field| {
                         if let Some(FieldName::Ident(ident)) = field.name.as_ref() {
+                            // Keys are the enum's values, but show what they map to as well
+                            let short_form = format!(
+                                "`\"{ident}\"`{}",
+                                self.format_enum_value(field, &ident_lookup, &literal_links)
+                            );
                             Some(format!(
-                                "### `\"{}\"`\n\n{}\n",
+                                "### `\"{}\"`\n\n{short_form}\n\n{}\n",
                                 ident,
                                 field.description.as_deref().unwrap_or_default()
                             ))
@@ -268,7 +892,10 @@ outline: [2, 3]
                     .iter()
                     .filter_map(|field| {
                         if let Some(FieldName::Ident(ident)) = field.name.as_ref() {
-                            let short_form = format!("`{name}.{ident}` = `{}`", field.value);
+                            let short_form = format!(
+                                "`{name}.{ident}`{}",
+                                self.format_enum_value(field, &ident_lookup, &literal_links)
+                            );
                             Some(format!(
                                 "### `{}`\n\n{short_form}\n\n{}\n",
                                 ident,
@@ -288,15 +915,13 @@ outline: [2, 3]
                 fields
             };
 
+            let frontmatter = frontmatter(&format!("Enum {name}"), &desc);
+            let since = since_note(en.since.as_deref());
             let contents = format!(
-                r"---
-outline: [2, 3]
----
-
-# Enum `{name}`
+                r"{frontmatter}# Enum `{name}`
 {key_badge}
 
-{values_short}
+{since}{values_short}
 
 {desc}
 
@@ -304,257 +929,4048 @@ outline: [2, 3]
 "
             );
 
-            let write_to = enum_dir.join(format!("{name}.md"));
-            std::fs::write(write_to, contents).unwrap();
+            pages.push((
+                PathBuf::from(enums_dir).join(format!("{name}.md")),
+                contents,
+            ));
         }
 
-        let _ = std::fs::remove_dir_all(self.out_dir.join("classes"));
-        let _ = std::fs::remove_dir_all(self.out_dir.join("enums"));
-        let _ = std::fs::remove_dir_all(self.out_dir.join("aliases"));
+        for (_, contents) in pages.iter_mut() {
+            if self.no_empty_sections {
+                *contents = remove_empty_sections(contents);
+            }
 
-        dircpy::copy_dir_advanced(
-            root_dir,
-            &self.out_dir,
-            true,
-            true,
-            true,
-            Vec::new(),
-            vec![".md".to_string()],
-        )
-        .unwrap();
+            *contents = normalize_line_endings(contents);
+        }
+
+        pages
     }
 }
 
-fn sanitize_angle_brackets(markdown: impl ToString) -> String {
-    let mut markdown = markdown.to_string();
+impl VitePressRenderer {
+    /// Generate a VitePress `SidebarItem[]` linking to every class, alias, and enum page,
+    /// grouped by kind and sorted by name. Empty groups are left out.
+    fn generate_sidebar(
+        &self,
+        classes: &[Class],
+        aliases: &[Alias],
+        functions: &[(Function, Option<String>)],
+        enums: &[Enum],
+    ) -> String {
+        let classes_dir = &self.section_dirs.classes;
+        let aliases_dir = &self.section_dirs.aliases;
+        let enums_dir = &self.section_dirs.enums;
+        let groups = [
+            ("Modules", "modules", module_names(classes)),
+            (
+                "Classes",
+                classes_dir.as_str(),
+                classes.iter().map(|class| &class.name).collect(),
+            ),
+            (
+                "Aliases",
+                aliases_dir.as_str(),
+                aliases.iter().map(|alias| &alias.name).collect(),
+            ),
+            (
+                "Enums",
+                enums_dir.as_str(),
+                enums.iter().map(|en| &en.name).collect(),
+            ),
+        ];
 
-    let node = markdown::to_mdast(&markdown, &ParseOptions::default()).unwrap();
+        let mut sidebar = groups
+            .into_iter()
+            .filter_map(|(text, path, mut names): (_, _, Vec<&String>)| {
+                if names.is_empty() {
+                    return None;
+                }
 
-    use markdown::mdast::Node;
+                names.sort();
+                names.dedup();
 
-    fn process(node: &Node, md: &str, indices: &mut Vec<usize>) {
-        match node {
-            Node::Code(_) | Node::InlineCode(_) | Node::Html(_) => (),
-            other => {
-                let has_children =
-                    matches!(other.children(), Some(children) if !children.is_empty());
+                let items = names
+                    .into_iter()
+                    .map(|name| {
+                        serde_json::json!({
+                            "text": name,
+                            "link": format!("{}{path}/{name}", self.base_url),
+                        })
+                    })
+                    .collect::<Vec<_>>();
 
-                if let Some(pos) = other.position() {
-                    if !has_children {
-                        let start_pos = pos.start.offset;
-                        let end_pos = pos.end.offset;
+                Some(serde_json::json!({ "text": text, "items": items }))
+            })
+            .collect::<Vec<_>>();
 
-                        let to_replace_indices = md[start_pos..end_pos]
-                            .match_indices('<')
-                            .map(|(i, _)| i + start_pos);
+        if functions
+            .iter()
+            .any(|(func, _)| !is_class_function(func, classes))
+        {
+            sidebar.push(serde_json::json!({
+                "text": "Functions",
+                "link": format!("{}{FUNCTIONS_PAGE}", self.base_url),
+            }));
+        }
 
-                        indices.extend(to_replace_indices);
-                    }
-                }
+        serde_json::to_string_pretty(&sidebar).unwrap()
+    }
 
-                if let Some(children) = other.children() {
-                    for node in children {
-                        process(node, md, indices);
-                    }
-                }
+    /// Generate a page listing every symbol with an `@since` version, grouped by version
+    /// from newest to oldest.
+    fn generate_version_matrix(
+        &self,
+        classes: &[Class],
+        aliases: &[Alias],
+        functions: &[(Function, Option<String>)],
+        enums: &[Enum],
+        member_links: &HashMap<String, String>,
+    ) -> String {
+        let classes_dir = &self.section_dirs.classes;
+        let aliases_dir = &self.section_dirs.aliases;
+        let enums_dir = &self.section_dirs.enums;
+        let base_url = &self.base_url;
+        // Members link to their heading, or to their page if they don't have one
+        let member_link = |member: &str, page: String| {
+            let link = member_links.get(member).cloned().unwrap_or(page);
+            format!("{base_url}{link}")
+        };
+
+        let mut versions = Vec::<(String, Vec<String>)>::new();
+        let mut add = |since: Option<&String>, symbol: String| {
+            let Some(since) = since.map(|since| normalize_version(since)) else {
+                return;
+            };
+
+            match versions.iter_mut().find(|(version, _)| version == since) {
+                Some((_, symbols)) => symbols.push(symbol),
+                None => versions.push((since.to_string(), vec![symbol])),
+            }
+        };
+
+        for class in classes {
+            let name = &class.name;
+            add(
+                class.since.as_ref(),
+                format!(
+                    r#"- Class <code><a href="{base_url}{classes_dir}/{name}">{name}</a></code>"#
+                ),
+            );
+
+            for field in class.fields() {
+                let field_name = field.ident_type.format_as_table_field_name();
+                let connector = if field_name.starts_with('[') { "" } else { "." };
+                let member = format!("{name}{connector}{field_name}");
+                let link = member_link(&member, format!("{classes_dir}/{name}"));
+                add(
+                    field.since.as_ref(),
+                    format!(r#"- Field <code><a href="{link}">{member}</a></code>"#),
+                );
             }
         }
-    }
 
-    let mut indices = Vec::new();
+        for (function, _) in functions {
+            let name = &function.name;
+            let (member, page) = match function.table.as_ref() {
+                Some(table) if is_class_function(function, classes) => {
+                    (format!("{table}.{name}"), format!("{classes_dir}/{table}"))
+                }
+                Some(table) => (format!("{table}.{name}"), FUNCTIONS_PAGE.to_string()),
+                None => (name.clone(), FUNCTIONS_PAGE.to_string()),
+            };
+            let link = member_link(&member, page);
+            let qualified_name = function.qualified_name();
+            add(
+                function.since.as_ref(),
+                format!(r#"- Function <code><a href="{link}">{qualified_name}</a></code>"#),
+            );
+        }
 
-    process(&node, &markdown, &mut indices);
+        for alias in aliases {
+            let name = &alias.name;
+            add(
+                alias.since.as_ref(),
+                format!(
+                    r#"- Alias <code><a href="{base_url}{aliases_dir}/{name}">{name}</a></code>"#
+                ),
+            );
+        }
 
-    for (num_replaced, index) in indices.into_iter().enumerate() {
-        assert!(
-            markdown.get((index + num_replaced * 3)..(index + num_replaced * 3 + 1)) == Some("<")
-        );
-        markdown.replace_range(
-            (index + num_replaced * 3)..(index + num_replaced * 3 + 1),
-            "&lt;",
-        );
-    }
+        for en in enums {
+            let name = &en.name;
+            add(
+                en.since.as_ref(),
+                format!(r#"- Enum <code><a href="{base_url}{enums_dir}/{name}">{name}</a></code>"#),
+            );
+        }
 
-    markdown
-}
+        versions.sort_by(|(a, _), (b, _)| compare_versions(b, a));
 
-fn generate_function_block(
-    func: &Function,
-    ident_lookup: &HashMap<String, Metatype>,
-    base_url: &str,
-) -> String {
-    let is_method = func.is_method;
-    let badge = if is_method {
-        r#"<Badge type="method" text="method" />"#.to_string()
-    } else {
-        r#"<Badge type="function" text="function" />"#.to_string()
-    };
-    let description = func.description.clone().unwrap_or_default();
+        let versions = versions
+            .into_iter()
+            .map(|(version, symbols)| format!("## {version}\n\n{}\n", symbols.join("\n")))
+            .collect::<Vec<_>>()
+            .join("\n");
 
-    let params_short = func
-        .params
-        .iter()
-        .map(|param| {
-            let nullable = param.ty.nullable.then_some("?").unwrap_or_default();
-            let ty = param.ty.format_with_links(ident_lookup, base_url);
-            format!("{}{nullable}: {}", param.name, ty)
-        })
-        .collect::<Vec<_>>()
-        .join(", ");
+        format!(
+            r#"{}# Versions
 
-    let mut returns_short = func
-        .returns
-        .iter()
-        .map(|ret| {
-            let name = ret
-                .name
-                .as_ref()
-                .map(|name| format!("{name}: "))
-                .unwrap_or_default();
-            // let ty = super::sanitize_angle_brackets(&ret.ty.to_string());
-            let ty = ret.ty.format_with_links(ident_lookup, base_url);
-            format!("{name}{ty}")
-        })
-        .collect::<Vec<_>>()
-        .join(", ");
-
-    if !returns_short.is_empty() {
-        returns_short = format!("\n    -> {returns_short}");
+{versions}"#,
+            frontmatter("Versions", "")
+        )
     }
 
-    let mut params = func
-        .params
-        .iter()
-        .map(|param| {
-            let description = param
-                .description
-                .as_ref()
-                .map(|desc| format!(" - {desc}"))
-                .unwrap_or_default();
-            let nullable = param.ty.nullable.then_some("?").unwrap_or_default();
-            format!(
-                "`{}{nullable}`: <code>{}</code>{}",
-                param.name,
-                param.ty.format_with_links(ident_lookup, base_url),
-                description
-            )
-        })
-        .collect::<Vec<_>>()
-        .join("<br>\n");
+    /// Map `Table.member` to the heading documenting each class field, class function, and
+    /// enum value, like `classes/Window#set-title`, for `@see` and other links to members.
+    ///
+    /// Methods are also mapped as `Class:method`. Class functions with their own pages map
+    /// to the page instead. Fields rendered on a
+    /// single line by `inline_descriptions` have no heading and aren't mapped.
+    fn generate_member_links(
+        &self,
+        classes: &[Class],
+        functions: &[(Function, Option<String>)],
+        enums: &[Enum],
+        function_links: &HashMap<String, String>,
+    ) -> HashMap<String, String> {
+        let classes_dir = &self.section_dirs.classes;
+        let enums_dir = &self.section_dirs.enums;
+        let mut links = function_links.clone();
+
+        for class in classes {
+            let name = &class.name;
+            let mut slugs = HeadingSlugs::default();
+
+            slugs.slug("Fields");
+            for field in class.fields() {
+                let inline = self.inline_descriptions
+                    && !field
+                        .description
+                        .as_deref()
+                        .unwrap_or_default()
+                        .contains('\n');
+                if inline {
+                    continue;
+                }
+
+                let field_name = field.ident_type.format_as_table_field_name();
+                let connector = if field_name.starts_with('[') { "" } else { "." };
+                let anchor = slugs.slug(&field_name);
+                links
+                    .entry(format!("{name}{connector}{field_name}"))
+                    .or_insert_with(|| format!("{classes_dir}/{name}#{anchor}"));
+            }
+
+            slugs.slug("Functions");
+            for (func, _) in functions
+                .iter()
+                .filter(|(func, _)| func.table.as_ref() == Some(name))
+            {
+                let key = format!("{name}.{}", func.name);
+                let link = match function_links.get(&key) {
+                    Some(link) => link.clone(),
+                    None => format!("{classes_dir}/{name}#{}", slugs.slug(&func.name)),
+                };
+
+                // `Class:method` refers to the method even if a field shares its name
+                if func.is_method {
+                    links.insert(format!("{name}:{}", func.name), link.clone());
+                }
+                links.entry(key).or_insert(link);
+            }
+        }
+
+        let mut slugs = HeadingSlugs::default();
+        slugs.slug("Functions");
+        for (func, _) in functions
+            .iter()
+            .filter(|(func, _)| !is_class_function(func, classes))
+        {
+            let link = format!("{FUNCTIONS_PAGE}#{}", slugs.slug(&func.name));
+
+            match func.table.as_ref() {
+                Some(table) => {
+                    if func.is_method {
+                        links.insert(format!("{table}:{}", func.name), link.clone());
+                    }
+                    links
+                        .entry(format!("{table}.{}", func.name))
+                        .or_insert(link);
+                }
+                None => {
+                    links.entry(func.name.clone()).or_insert(link);
+                }
+            }
+        }
+
+        for en in enums {
+            let name = &en.name;
+            let mut slugs = HeadingSlugs::default();
+            slugs.slug(if en.is_key { "Values" } else { "Fields" });
+
+            for field in en.fields.iter() {
+                let Some(FieldName::Ident(ident)) = field.name.as_ref() else {
+                    continue;
+                };
+
+                let anchor = slugs.slug(ident);
+                links
+                    .entry(format!("{name}.{ident}"))
+                    .or_insert_with(|| format!("{enums_dir}/{name}#{anchor}"));
+            }
+        }
 
-    if !params.is_empty() {
-        params = format!("#### Parameters\n\n{params}\n\n");
+        links
     }
 
-    let mut returns = func
-        .returns
-        .iter()
-        .enumerate()
-        .map(|(i, ret)| {
-            let name = ret
-                .name
-                .as_ref()
-                .map(|name| format!("`{name}`: "))
-                .unwrap_or_default();
-            let description = ret
-                .description
-                .as_ref()
-                .map(|desc| format!(" - {desc}"))
-                .unwrap_or_default();
-            format!(
-                "{}. {name}<code>{}</code>{description}",
-                i + 1,
-                ret.ty.format_with_links(ident_lookup, base_url)
-            )
-        })
-        .collect::<Vec<_>>()
-        .join("\n");
+    /// Generate the search index, linking each symbol to its page or heading.
+    fn generate_search_index(
+        &self,
+        classes: &[Class],
+        aliases: &[Alias],
+        functions: &[(Function, Option<String>)],
+        enums: &[Enum],
+        member_links: &HashMap<String, String>,
+    ) -> String {
+        let classes_dir = &self.section_dirs.classes;
+        let aliases_dir = &self.section_dirs.aliases;
+        let enums_dir = &self.section_dirs.enums;
+        let base_url = &self.base_url;
+        let url = |member: &str, page: String| {
+            let link = member_links.get(member).cloned().unwrap_or(page);
+            format!("{base_url}{link}")
+        };
+
+        let mut entries = Vec::new();
+
+        for symbol in search::symbols(
+            classes,
+            functions.iter().map(|(function, _)| function),
+            aliases,
+            enums,
+        ) {
+            let url = match &symbol.item {
+                SymbolItem::Class(class) => format!("{base_url}{classes_dir}/{}", class.name),
+                SymbolItem::Field { class, .. } => {
+                    url(&symbol.name, format!("{classes_dir}/{}", class.name))
+                }
+                SymbolItem::Function(function) => match function.table.as_ref() {
+                    Some(table) if is_class_function(function, classes) => url(
+                        &format!("{table}.{}", function.name),
+                        format!("{classes_dir}/{table}"),
+                    ),
+                    Some(table) => url(
+                        &format!("{table}.{}", function.name),
+                        FUNCTIONS_PAGE.to_string(),
+                    ),
+                    None => url(&function.name, FUNCTIONS_PAGE.to_string()),
+                },
+                SymbolItem::Alias(alias) => format!("{base_url}{aliases_dir}/{}", alias.name),
+                SymbolItem::Enum(en) => format!("{base_url}{enums_dir}/{}", en.name),
+                SymbolItem::EnumValue { en, .. } => {
+                    url(&symbol.name, format!("{enums_dir}/{}", en.name))
+                }
+            };
+
+            entries.push(SearchEntry::new(
+                symbol.name.clone(),
+                symbol.kind(),
+                symbol.description(),
+                url,
+            ));
+        }
 
-    if !returns.is_empty() {
-        returns = format!("#### Returns\n\n{returns}\n\n");
+        search::render_index(&entries)
     }
 
-    let mut sees = func
-        .sees
-        .iter()
-        .filter_map(|see| {
-            let mut belonging_type = Vec::<&str>::new();
-            let mut split = see.ident.split('.').peekable();
-            while let Some(segment) = split.peek() {
-                let test = belonging_type
+    /// Generate the page of a module declared with `@module`, with the module class's
+    /// description and a table of contents linking to the classes and functions in it.
+    fn generate_module_page(
+        &self,
+        module: &str,
+        classes: &[Class],
+        functions: &[(Function, Option<String>)],
+        member_links: &HashMap<String, String>,
+    ) -> String {
+        let classes_dir = &self.section_dirs.classes;
+        let base_url = &self.base_url;
+        let summary = |description: Option<&String>| {
+            description
+                .and_then(|desc| desc.lines().next())
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| format!(" — {line}"))
+                .unwrap_or_default()
+        };
+
+        let module_classes = classes
+            .iter()
+            .filter(|class| class.module.as_deref() == Some(module))
+            .collect::<Vec<_>>();
+
+        let description = module_classes
+            .iter()
+            .find(|class| class.is_module)
+            .and_then(|class| class.description.clone())
+            .unwrap_or_default();
+
+        let class_links = module_classes
+            .iter()
+            .map(|class| {
+                let name = &class.name;
+                format!(
+                    r#"- <code><a href="{base_url}{classes_dir}/{name}">{name}</a></code>{}"#,
+                    summary(class.description.as_ref())
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let function_links = functions
+            .iter()
+            .filter_map(|(function, _)| {
+                let table = function.table.as_ref()?;
+                module_classes
                     .iter()
-                    .copied()
-                    .chain([*segment])
-                    .collect::<Vec<_>>()
-                    .join(".");
-                let exists = ident_lookup.get(&test).is_some();
-                if exists {
-                    belonging_type.push(segment);
-                    split.next();
-                } else {
-                    break;
+                    .any(|class| &class.name == table)
+                    .then_some((function, table))
+            })
+            .map(|(function, table)| {
+                let name = &function.name;
+                let connector = if function.is_method { ":" } else { "." };
+                let link = member_links
+                    .get(&format!("{table}.{name}"))
+                    .cloned()
+                    .unwrap_or_else(|| format!("{classes_dir}/{table}"));
+                format!(
+                    r#"- <code><a href="{base_url}{link}">{table}{connector}{name}</a></code>{}"#,
+                    summary(function.description.as_ref())
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let frontmatter = frontmatter(&format!("Module {module}"), &description);
+        let mut contents = format!(
+            r#"{frontmatter}# Module `{module}`
+
+{description}
+"#
+        );
+
+        if !class_links.is_empty() {
+            contents.push_str(&format!("\n## Classes\n\n{class_links}\n"));
+        }
+
+        if !function_links.is_empty() {
+            contents.push_str(&format!("\n## Functions\n\n{function_links}\n"));
+        }
+
+        contents
+    }
+
+    /// Generate a JSON manifest listing every symbol with its kind, qualified name,
+    /// signature, and the page (and anchor on that page) documenting it.
+    ///
+    /// Functions on tables that aren't classes don't get documented, so they're left out.
+    fn generate_symbols_manifest(
+        &self,
+        classes: &[Class],
+        aliases: &[Alias],
+        functions: &[(Function, Option<String>)],
+        enums: &[Enum],
+        ident_lookup: &HashMap<String, Metatype>,
+        member_links: &HashMap<String, String>,
+    ) -> String {
+        let classes_dir = &self.section_dirs.classes;
+        let aliases_dir = &self.section_dirs.aliases;
+        let enums_dir = &self.section_dirs.enums;
+        // Links look like `classes/Window#title`
+        let symbol = |kind: &str, name: String, signature: String, link: &str| {
+            let (path, anchor) = match link.split_once('#') {
+                Some((path, anchor)) => (path, Some(anchor)),
+                None => (link, None),
+            };
+            serde_json::json!({
+                "kind": kind,
+                "name": name,
+                "signature": signature,
+                "path": format!("{path}.md"),
+                "anchor": anchor,
+            })
+        };
+        let member_link = |member: &str, page: &str| {
+            member_links
+                .get(member)
+                .cloned()
+                .unwrap_or_else(|| page.to_string())
+        };
+
+        let mut symbols = Vec::new();
+
+        for entry in search::symbols(
+            classes,
+            functions.iter().map(|(function, _)| function),
+            aliases,
+            enums,
+        ) {
+            let (signature, link) = match &entry.item {
+                SymbolItem::Class(class) => {
+                    let signature = match class.parent.as_ref() {
+                        Some(parent) => format!("{} : {parent}", class.name),
+                        None => class.name.clone(),
+                    };
+                    (signature, format!("{classes_dir}/{}", class.name))
                 }
-            }
+                SymbolItem::Field {
+                    class,
+                    field,
+                    field_name,
+                } => {
+                    let nullable = field
+                        .ty
+                        .as_ref()
+                        .and_then(|ty| ty.nullable.then_some("?"))
+                        .unwrap_or_default();
+                    let ty = field
+                        .ty
+                        .as_ref()
+                        .map(|ty| format!(": {ty}"))
+                        .unwrap_or_default();
+                    (
+                        format!("{field_name}{nullable}{ty}"),
+                        member_link(&entry.name, &format!("{classes_dir}/{}", class.name)),
+                    )
+                }
+                SymbolItem::Function(function) => {
+                    let name = &function.name;
+                    let (member, page) = match function.table.as_ref() {
+                        Some(table) if matches!(ident_lookup.get(table), Some(Metatype::Class)) => {
+                            (format!("{table}.{name}"), format!("{classes_dir}/{table}"))
+                        }
+                        Some(table) => (format!("{table}.{name}"), FUNCTIONS_PAGE.to_string()),
+                        None => (name.clone(), FUNCTIONS_PAGE.to_string()),
+                    };
 
-            let belonging_type = belonging_type.join(".");
+                    let params = self
+                        .shown_params(function)
+                        .iter()
+                        .map(|param| {
+                            let nullable = param.ty.nullable.then_some("?").unwrap_or_default();
+                            let default = param
+                                .default
+                                .as_ref()
+                                .map(|default| format!(" = {default}"))
+                                .unwrap_or_default();
+                            format!("{}{nullable}: {}{default}", param.name, param.ty)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let returns = function
+                        .returns
+                        .iter()
+                        .map(|ret| match ret.name.as_ref() {
+                            Some(name) => format!("{name}: {}", ret.ty),
+                            None => ret.ty.to_string(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let returns = if returns.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" -> {returns}")
+                    };
 
-            let path = match ident_lookup.get(&belonging_type)? {
-                Metatype::Class => "classes",
-                Metatype::Alias => "aliases",
-                Metatype::Enum => "enums",
+                    (
+                        format!("{}({params}){returns}", entry.name),
+                        member_link(&member, &page),
+                    )
+                }
+                SymbolItem::Alias(alias) => {
+                    let types = alias
+                        .types
+                        .iter()
+                        .map(|(ty, _)| ty.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" | ");
+                    (
+                        format!("{} = {types}", alias.name_with_type_params()),
+                        format!("{aliases_dir}/{}", alias.name),
+                    )
+                }
+                SymbolItem::Enum(en) => (en.name.clone(), format!("{enums_dir}/{}", en.name)),
+                SymbolItem::EnumValue { en, field, ident } => (
+                    format!("{ident} = {}", field.value),
+                    member_link(&entry.name, &format!("{enums_dir}/{}", en.name)),
+                ),
             };
 
-            let mut rest = split.collect::<Vec<_>>().join(".");
-            let mut rest_with_dot = String::new();
+            symbols.push(symbol(entry.kind(), entry.name, signature, &link));
+        }
+
+        let manifest = serde_json::json!({
+            "version": SYMBOLS_SCHEMA_VERSION,
+            "symbols": symbols,
+        });
+
+        serde_json::to_string_pretty(&manifest).unwrap() + "\n"
+    }
+
+    /// Format a field's value as ` = `value``, eliding it if it's too long.
+    fn format_value(&self, value: &str) -> String {
+        match elide_value(value, self.max_value_len) {
+            Some(elided) => format!(
+                " = `{elided}`\n\n*The full value is too long to show here; see the source.*"
+            ),
+            None => format!(" = `{value}`"),
+        }
+    }
+
+    /// Format the fields of a table assigned to a class field as a list, with tables of
+    /// their own listed under them.
+    fn format_nested_fields(
+        &self,
+        fields: &[TsField],
+        indent: &str,
+        ident_lookup: &HashMap<String, Metatype>,
+        literal_links: &HashMap<String, String>,
+    ) -> String {
+        fields
+            .iter()
+            .filter_map(|field| {
+                let Some(FieldName::Ident(name)) = field.name.as_ref() else {
+                    return None;
+                };
+
+                let ty = field
+                    .ty
+                    .as_ref()
+                    .map(|ty| {
+                        format!(
+                            ": <code>{}</code>",
+                            self.format_type(ty, ident_lookup, literal_links)
+                        )
+                    })
+                    .unwrap_or_default();
+
+                // Values spanning multiple lines would break out of the list item
+                let value = if !field.fields.is_empty() {
+                    String::new()
+                } else if let Some((first_line, _)) = field.value.split_once('\n') {
+                    match elide_value(&field.value, 0) {
+                        Some(elided) if field.value.starts_with('{') => format!(" = `{elided}`"),
+                        _ => format!(" = `{} ...`", first_line.trim_end()),
+                    }
+                } else {
+                    let value = elide_value(&field.value, self.max_value_len)
+                        .unwrap_or_else(|| field.value.clone());
+                    format!(" = `{value}`")
+                };
+
+                let description = field
+                    .description
+                    .as_deref()
+                    .map(|description| {
+                        format!(" — {}", description.replace('\n', &format!("\n{indent}  ")))
+                    })
+                    .unwrap_or_default();
+
+                let nested = if field.fields.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        "\n{}",
+                        self.format_nested_fields(
+                            &field.fields,
+                            &format!("{indent}  "),
+                            ident_lookup,
+                            literal_links,
+                        )
+                    )
+                };
+
+                Some(format!(
+                    "{indent}- `{name}`{ty}{value}{description}{nested}"
+                ))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Format an enum value's `@type`, if it has one, followed by its value.
+    ///
+    /// Values spanning multiple lines, like functions and tables, go in a Lua code block
+    /// unless they're long enough to be elided.
+    fn format_enum_value(
+        &self,
+        field: &TsField,
+        ident_lookup: &HashMap<String, Metatype>,
+        literal_links: &HashMap<String, String>,
+    ) -> String {
+        let ty = field
+            .ty
+            .as_ref()
+            .map(|ty| {
+                format!(
+                    ": <code>{}</code>",
+                    self.format_type(ty, ident_lookup, literal_links)
+                )
+            })
+            .unwrap_or_default();
+
+        let value = &field.value;
+        if !value.contains('\n') || elide_value(value, self.max_value_len).is_some() {
+            return format!("{ty}{}", self.format_value(value));
+        }
+
+        let fence = code_fence(value);
+        format!(
+            "{ty}\n\n{fence}lua\n{}\n{fence}",
+            dedent_continuation_lines(value)
+        )
+    }
+
+    /// A paragraph pointing at where a symbol is defined, like ``Source: `init.lua:3` ``.
+    fn format_source(&self, location: Option<&Location>) -> String {
+        let Some(location) = location else {
+            return String::new();
+        };
+
+        let file = location.file.to_string_lossy().replace('\\', "/");
+        let file = file.strip_prefix("./").unwrap_or(&file);
+        let line = location.line;
+
+        match self.source_url_template.as_ref() {
+            Some(template) => {
+                let url = template
+                    .replace("{file}", file)
+                    .replace("{line}", &line.to_string());
+                format!(
+                    "Source: <a href=\"{}\"><code>{}:{line}</code></a>\n\n",
+                    escape_html(&url),
+                    escape_html(file)
+                )
+            }
+            None => format!("Source: `{file}:{line}`\n\n"),
+        }
+    }
+
+    /// Format the members of a union as a bulleted list, each line starting with `indent`.
+    fn format_union_list(
+        &self,
+        members: &[Type],
+        indent: &str,
+        ident_lookup: &HashMap<String, Metatype>,
+        literal_links: &HashMap<String, String>,
+    ) -> String {
+        members
+            .iter()
+            .map(|member| {
+                format!(
+                    "{indent}- <code>{}</code>",
+                    self.format_type(member, ident_lookup, literal_links)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn format_type(
+        &self,
+        ty: &Type,
+        ident_lookup: &HashMap<String, Metatype>,
+        literal_links: &HashMap<String, String>,
+    ) -> String {
+        let options = LinkOptions {
+            verbose: self.verbose_types,
+            literal_links: Some(literal_links),
+            section_dirs: Some(&self.section_dirs),
+        };
+
+        ty.format_with_links(ident_lookup, &self.base_url, options)
+    }
 
-            if !rest.is_empty() {
-                rest_with_dot = format!(".{rest}");
-                rest = format!("#{rest}");
+    /// The parameters of `func` to document, without a leading `self` annotated on a
+    /// method unless `show_self` is set.
+    fn shown_params<'a>(&self, func: &'a Function) -> &'a [Param] {
+        // Methods already take `self`, so an annotated one would be listed twice
+        match func.params.split_first() {
+            Some((first, rest)) if func.is_method && first.name == "self" && !self.show_self => {
+                rest
             }
+            _ => &func.params,
+        }
+    }
+
+    /// Generate the documentation of a function, starting with a heading of the given depth.
+    fn generate_function_block(
+        &self,
+        func: &Function,
+        ident_lookup: &HashMap<String, Metatype>,
+        member_links: &HashMap<String, String>,
+        literal_links: &HashMap<String, String>,
+        depth: usize,
+    ) -> String {
+        let base_url = self.base_url.as_str();
+        let heading = "#".repeat(depth);
+        let subheading = "#".repeat(depth + 1);
+        let is_method = func.is_method;
+        let badge = if is_method {
+            r#"<Badge type="method" text="method" />"#.to_string()
+        } else {
+            r#"<Badge type="function" text="function" />"#.to_string()
+        };
+        let (async_badge, async_keyword) = if func.is_async {
+            (r#" <Badge type="tip" text="async" />"#, "async ")
+        } else {
+            ("", "")
+        };
+        let description = func.description.clone().unwrap_or_default();
+        let deprecated_badge = func
+            .deprecated
+            .as_ref()
+            .map(|_| format!(" {DEPRECATED_BADGE}"))
+            .unwrap_or_default();
+        let custom_badges = custom_badges(&func.badges);
+        let deprecation = func
+            .deprecated
+            .as_deref()
+            .map(deprecation_notice)
+            .unwrap_or_default();
+
+        let explicit_self = is_method && self.method_style == MethodStyle::Dot;
+        let func_params = self.shown_params(func);
+        // An annotated `self` shown with `show_self` already takes the place of the implicit one
+        let implicit_self =
+            explicit_self && func_params.first().is_none_or(|param| param.name != "self");
+
+        let params_short = implicit_self
+            .then(|| "self".to_string())
+            .into_iter()
+            .chain(func_params.iter().map(|param| {
+                let nullable = param.ty.nullable.then_some("?").unwrap_or_default();
+                let ty = self.format_type(&param.ty, ident_lookup, literal_links);
+                let default = param
+                    .default
+                    .as_ref()
+                    .map(|default| format!(" = {}", default.replace('<', "&lt;")))
+                    .unwrap_or_default();
+                format!("{}{nullable}: {ty}{default}", param.name)
+            }))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut returns_short = func
+            .returns
+            .iter()
+            .map(|ret| {
+                let name = ret
+                    .name
+                    .as_ref()
+                    .map(|name| format!("{name}: "))
+                    .unwrap_or_default();
+                // let ty = super::sanitize_angle_brackets(&ret.ty.to_string());
+                let ty = self.format_type(&ret.ty, ident_lookup, literal_links);
+                format!("{name}{ty}")
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        if !returns_short.is_empty() {
+            returns_short = format!("\n    -> {returns_short}");
+        }
 
-            let desc = see
+        let format_param = |param: &Param| {
+            let separator = match param.description.as_ref() {
+                Some(desc) if self.inline_descriptions && !desc.contains('\n') => "—",
+                _ => "-",
+            };
+            let description = param
                 .description
                 .as_ref()
-                .map(|desc| format!(": {desc}"))
+                .map(|desc| format!(" {separator} {desc}"))
+                .unwrap_or_default();
+            let default = param
+                .default
+                .as_ref()
+                .map(|default| format!(" (Default: `{default}`)"))
                 .unwrap_or_default();
+            let nullable = param.ty.nullable.then_some("?").unwrap_or_default();
+            let ty = match string_literal_choices(&param.ty) {
+                Some(choices) => {
+                    let choices = choices
+                        .iter()
+                        .map(|choice| {
+                            format!(
+                                "<code>{}</code>",
+                                self.format_type(choice, ident_lookup, literal_links)
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("one of {choices}")
+                }
+                None => format!(
+                    "<code>{}</code>",
+                    self.format_type(&param.ty, ident_lookup, literal_links)
+                ),
+            };
+            format!("`{}{nullable}`: {ty}{description}{default}", param.name)
+        };
 
-            Some(format!(
-                "- <code><a href=\"{base_url}{path}/{belonging_type}{rest}\">\
-                {belonging_type}{rest_with_dot}</a></code>{desc}",
-            ))
-        })
-        .collect::<Vec<_>>()
-        .join(".");
+        let (required, optional) = if self.group_optional_params {
+            func_params
+                .iter()
+                .partition(|param| !param.ty.nullable && param.default.is_none())
+        } else {
+            (func_params.iter().collect::<Vec<_>>(), Vec::new())
+        };
 
-    if !sees.is_empty() {
-        sees = format!("#### See also\n\n{sees}");
-    }
+        let mut params = required
+            .into_iter()
+            .map(format_param)
+            .collect::<Vec<_>>()
+            .join("<br>\n");
 
-    let table = func
-        .table
-        .as_ref()
-        .map(|table| {
-            let connector = if is_method { ":" } else { "." };
-            format!("{table}{connector}")
-        })
-        .unwrap_or_default();
+        if !params.is_empty() {
+            params.push_str("\n\n");
+        }
 
-    let fn_name = &func.name;
+        if !optional.is_empty() {
+            let optional = optional
+                .into_iter()
+                .map(format_param)
+                .collect::<Vec<_>>()
+                .join("<br>\n");
+            params.push_str(&format!(
+                "{subheading}# Optional parameters\n\n{optional}\n\n"
+            ));
+        }
 
-    #[rustfmt::skip]
-    let ret = format!(
-r#"### {badge} {fn_name}
+        if !params.is_empty() {
+            params = format!("{subheading} Parameters\n\n{params}");
+        }
 
-<div class="language-lua"><pre><code>function {table}{fn_name}({params_short}){returns_short}</code></pre></div>
+        let mut returns = func
+            .returns
+            .iter()
+            .enumerate()
+            .map(|(i, ret)| {
+                let name = ret
+                    .name
+                    .as_ref()
+                    .map(|name| format!("`{name}`: "))
+                    .unwrap_or_default();
+                let description = ret
+                    .description
+                    .as_ref()
+                    .map(|desc| format!(" - {desc}"))
+                    .unwrap_or_default();
 
-{description}
+                if let Some(members) = long_union(Some(&ret.ty)) {
+                    let marker = format!("{}. ", i + 1);
+                    let mut list = self.format_union_list(
+                        members,
+                        &" ".repeat(marker.len()),
+                        ident_lookup,
+                        literal_links,
+                    );
+                    if ret.ty.nullable {
+                        list = format!("{list}\n{}- <code>nil</code>", " ".repeat(marker.len()));
+                    }
+                    return format!("{marker}{name}one of{description}\n{list}");
+                }
+
+                format!(
+                    "{}. {name}<code>{}</code>{description}",
+                    i + 1,
+                    self.format_type(&ret.ty, ident_lookup, literal_links)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if !returns.is_empty() {
+            returns = format!("{subheading} Returns\n\n{returns}\n\n");
+        }
+
+        let mut overloads = func
+            .overloads
+            .iter()
+            .map(|overload| {
+                format!(
+                    r#"<div class="language-lua"><pre><code>{}</code></pre></div>"#,
+                    self.format_type(overload, ident_lookup, literal_links)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        if !overloads.is_empty() {
+            overloads = format!("{subheading} Overloads\n\n{overloads}\n\n");
+        }
+
+        let mut type_params = func
+            .generics
+            .iter()
+            .map(|(name, constraint)| {
+                let constraint = constraint
+                    .as_ref()
+                    .map(|ty| {
+                        format!(
+                            ": <code>{}</code>",
+                            self.format_type(ty, ident_lookup, literal_links)
+                        )
+                    })
+                    .unwrap_or_default();
+                format!("- `{name}`{constraint}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if !type_params.is_empty() {
+            type_params = format!("{subheading} Type parameters\n\n{type_params}\n\n");
+        }
+
+        let mut errors = func
+            .errors
+            .iter()
+            .map(|(ty, desc)| {
+                let description = desc
+                    .as_ref()
+                    .map(|desc| format!(" - {desc}"))
+                    .unwrap_or_default();
+                format!(
+                    "- <code>{}</code>{description}",
+                    self.format_type(ty, ident_lookup, literal_links)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if !errors.is_empty() {
+            errors = format!("{subheading} Errors\n\n{errors}\n\n");
+        }
+
+        let mut sees = func
+            .sees
+            .iter()
+            .filter_map(|see| {
+                let desc = see
+                    .description
+                    .as_ref()
+                    .map(|desc| format!(": {desc}"))
+                    .unwrap_or_default();
+
+                if see.is_url() {
+                    return Some(format!(
+                        "- <a href=\"{url}\">{url}</a>{desc}",
+                        url = escape_html(&see.ident)
+                    ));
+                }
+
+                if let Some(link) = member_links.get(&see.ident) {
+                    return Some(format!(
+                        "- <code><a href=\"{base_url}{link}\">{}</a></code>{desc}",
+                        see.ident
+                    ));
+                }
+
+                // Methods can be referred to as `Class:method`
+                let ident = see.ident.replace(':', ".");
+
+                let mut belonging_type = Vec::<&str>::new();
+                let mut split = ident.split('.').peekable();
+                while let Some(segment) = split.peek() {
+                    let test = belonging_type
+                        .iter()
+                        .copied()
+                        .chain([*segment])
+                        .collect::<Vec<_>>()
+                        .join(".");
+                    let exists = ident_lookup.get(&test).is_some();
+                    if exists {
+                        belonging_type.push(segment);
+                        split.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                let belonging_type = belonging_type.join(".");
+                let rest = split.collect::<Vec<_>>().join(".");
+
+                if let Some(link) = member_links.get(&format!("{belonging_type}.{rest}")) {
+                    return Some(format!(
+                        "- <code><a href=\"{base_url}{link}\">{}</a></code>{desc}",
+                        see.ident
+                    ));
+                }
+
+                let path = self.section_dirs.get(*ident_lookup.get(&belonging_type)?);
+
+                // Members that aren't documented link to the page of their type
+                Some(format!(
+                    "- <code><a href=\"{base_url}{path}/{belonging_type}\">{}</a></code>{desc}",
+                    see.ident
+                ))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if !sees.is_empty() {
+            sees = format!("{subheading} See also\n\n{sees}");
+        }
+
+        let table = func
+            .table
+            .as_ref()
+            .map(|table| {
+                let connector = if is_method && !explicit_self {
+                    ":"
+                } else {
+                    "."
+                };
+                format!("{table}{connector}")
+            })
+            .unwrap_or_default();
+
+        let fn_name = &func.name;
+        let location = self.format_source(Some(&func.location));
+        let since = since_note(func.since.as_deref());
+
+        let source = if self.emit_sources {
+            let fence = code_fence(&func.source);
+            format!(
+                "<details>\n<summary>Source</summary>\n\n{fence}lua\n{}\n{fence}\n\n</details>",
+                func.source
+            )
+        } else {
+            String::new()
+        };
+
+        #[rustfmt::skip]
+        let ret = format!(
+r#"{heading} {badge}{async_badge} {fn_name}{deprecated_badge}{custom_badges}
+
+<div class="language-lua"><pre><code>{async_keyword}function {table}{fn_name}({params_short}){returns_short}</code></pre></div>
+
+{since}{location}{deprecation}{description}
+
+{overloads}
+
+{type_params}
 
 {params}
 
 {returns}
 
-{sees}"#,
-    );
+{errors}
+
+{sees}
+
+{source}"#,
+        );
+
+        ret
+    }
+}
+
+/// Strip the whitespace and the leading `v` from a version like `v1.2.0`, so it's grouped
+/// and shown with `1.2.0`.
+fn normalize_version(version: &str) -> &str {
+    let version = version.trim();
+
+    match version.strip_prefix(['v', 'V']) {
+        Some(number) if number.starts_with(|ch: char| ch.is_ascii_digit()) => number,
+        _ => version,
+    }
+}
+
+/// Compare two versions like `1.2.0` and `v1.10.0-beta`.
+///
+/// Numeric parts are compared numerically and, like semver, a pre-release sorts before
+/// its release.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn compare_parts(a: &str, b: &str) -> Ordering {
+        let mut a = a.split(|ch: char| !ch.is_ascii_alphanumeric());
+        let mut b = b.split(|ch: char| !ch.is_ascii_alphanumeric());
+
+        loop {
+            let ordering = match (a.next(), b.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                    (Ok(a), Ok(b)) => a.cmp(&b),
+                    _ => a.cmp(b),
+                },
+            };
+
+            if ordering.is_ne() {
+                return ordering;
+            }
+        }
+    }
+
+    let split = |version: &'_ str| {
+        let version = normalize_version(version).to_string();
+        match version.split_once('-') {
+            Some((release, pre_release)) => (release.to_string(), Some(pre_release.to_string())),
+            None => (version, None),
+        }
+    };
+
+    let (a_release, a_pre_release) = split(a);
+    let (b_release, b_pre_release) = split(b);
 
-    ret
+    compare_parts(&a_release, &b_release).then_with(|| match (a_pre_release, b_pre_release) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => compare_parts(&a, &b),
+    })
+}
+
+/// The members of `ty` if it's a union too long to read inline.
+fn long_union(ty: Option<&Type>) -> Option<&[Type]> {
+    match &ty?.inner {
+        TypeInner::Union(members) if members.len() > MAX_INLINE_UNION_LEN => Some(members),
+        _ => None,
+    }
+}
+
+/// The members of `ty` if it's a union of only string literals, like `"read" | "write"`.
+fn string_literal_choices(ty: &Type) -> Option<&[Type]> {
+    match &ty.inner {
+        TypeInner::Union(members)
+            if members.iter().all(|member| {
+                matches!(member.inner, TypeInner::Literal(Literal::String(_))) && !member.nullable
+            }) =>
+        {
+            Some(members)
+        }
+        _ => None,
+    }
+}
+
+const DEPRECATED_BADGE: &str = r#"<Badge type="danger" text="deprecated" />"#;
+
+/// Render the version from `@since` as a note, or nothing if there isn't one.
+fn since_note(since: Option<&str>) -> String {
+    since
+        .map(|since| format!("*Since {since}*\n\n"))
+        .unwrap_or_default()
+}
+
+/// Render the `@badge`s of a class or function, each preceded by a space.
+fn custom_badges(badges: &[(String, String)]) -> String {
+    badges
+        .iter()
+        .map(|(ty, text)| {
+            let text = text
+                .replace('&', "&amp;")
+                .replace('"', "&quot;")
+                .replace('<', "&lt;");
+            format!(r#" <Badge type="{ty}" text="{text}" />"#)
+        })
+        .collect()
+}
+
+/// Render the message of a `@deprecated` annotation as a danger block,
+/// or nothing if there's no message.
+fn deprecation_notice(message: &str) -> String {
+    if message.is_empty() {
+        return String::new();
+    }
+
+    format!("::: danger DEPRECATED\n{message}\n:::\n\n")
+}
+
+/// Normalize a base url so it can be used directly as a link prefix.
+///
+/// Empty segments are dropped so the result always ends in exactly one slash,
+/// and values that are neither a URL nor an absolute path get a warning.
+fn normalize_base_url(base_url: &str) -> String {
+    let base_url = base_url.trim();
+
+    let (mut normalized, path) = match base_url.split_once("://") {
+        Some((scheme, rest)) => (format!("{scheme}://"), rest),
+        None => (String::new(), base_url),
+    };
+
+    let is_url = !normalized.is_empty();
+
+    if base_url.contains(|ch: char| ch.is_whitespace() || ch == '?' || ch == '#')
+        || (is_url && path.is_empty())
+        || (!is_url && !path.is_empty() && !path.starts_with('/'))
+    {
+        log::warn!("base url `{base_url}` doesn't look like a URL or a path starting with `/`");
+    }
+
+    if !is_url && (path.is_empty() || path.starts_with('/')) {
+        normalized.push('/');
+    }
+
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        normalized.push_str(segment);
+        normalized.push('/');
+    }
+
+    normalized
+}
+
+/// Remove headings below the page title that are only followed by blank lines
+/// before the next heading of the same or a higher level.
+fn remove_empty_sections(markdown: &str) -> String {
+    let heading_level = |line: &str| {
+        let level = line.chars().take_while(|&ch| ch == '#').count();
+        (level > 0 && line[level..].starts_with(' ')).then_some(level)
+    };
+
+    // Track fenced code blocks so Lua comments like `-- # foo` or shell
+    // comments in examples aren't mistaken for headings
+    let mut in_code_block = false;
+    let lines = markdown
+        .lines()
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+                return (line, None);
+            }
+            (
+                line,
+                (!in_code_block).then(|| heading_level(line)).flatten(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Walk backwards so a section that only contained removed subsections
+    // is removed as well
+    let mut kept = Vec::<(&str, Option<usize>)>::new();
+
+    for (line, level) in lines.into_iter().rev() {
+        if let Some(level) = level.filter(|&level| level > 1) {
+            let next_content = kept.iter().rev().find(|(line, _)| !line.trim().is_empty());
+
+            let is_empty = match next_content {
+                Some((_, Some(next_level))) => *next_level <= level,
+                Some((_, None)) => false,
+                None => true,
+            };
+
+            if is_empty {
+                while kept.last().is_some_and(|(line, _)| line.trim().is_empty()) {
+                    kept.pop();
+                }
+                continue;
+            }
+        }
+
+        kept.push((line, level));
+    }
+
+    let mut markdown = kept
+        .into_iter()
+        .rev()
+        .map(|(line, _)| line)
+        .collect::<Vec<_>>()
+        .join("\n");
+    markdown.push('\n');
+    markdown
+}
+
+/// Escape text so it fits in a single markdown table cell.
+fn escape_table_cell(text: &str) -> String {
+    text.trim().replace('|', "\\|").replace('\n', "<br>")
+}
+
+/// Shorten a value longer than `max_len` characters.
+///
+/// Table constructors become `{ ... }`, anything else is truncated.
+/// Returns `None` if the value already fits.
+fn elide_value(value: &str, max_len: usize) -> Option<String> {
+    if value.chars().count() <= max_len {
+        return None;
+    }
+
+    if value.starts_with('{') {
+        return Some("{ ... }".to_string());
+    }
+
+    let truncated = value.chars().take(max_len).collect::<String>();
+    Some(format!("{} ...", truncated.trim_end()))
+}
+
+/// The frontmatter of a page, with `title` and the first sentence of `description`
+/// as its title and description for VitePress and search engines.
+///
+/// The description is left out if it's empty.
+fn frontmatter(title: &str, description: &str) -> String {
+    let description = search::plain_text(description);
+    let description = match first_sentence(&description) {
+        "" => String::new(),
+        sentence => format!("description: {}\n", yaml_string(sentence)),
+    };
+
+    format!(
+        "---\ntitle: {}\n{description}outline: [2, 3]\n---\n\n",
+        yaml_string(title)
+    )
+}
+
+/// The text up to and including the first `.`, `!`, or `?` that ends a sentence.
+fn first_sentence(text: &str) -> &str {
+    text.char_indices()
+        .find(|&(i, ch)| {
+            matches!(ch, '.' | '!' | '?')
+                && text[i + ch.len_utf8()..]
+                    .chars()
+                    .next()
+                    .is_none_or(char::is_whitespace)
+        })
+        .map_or(text, |(i, ch)| &text[..i + ch.len_utf8()])
+}
+
+/// Quote a string as a YAML double-quoted scalar, so colons, quotes, and leading characters
+/// like `-` or `*` are taken literally.
+fn yaml_string(string: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for ch in string.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            ch if ch.is_control() => quoted.push_str(&format!("\\u{:04X}", ch as u32)),
+            ch => quoted.push(ch),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+fn sanitize_angle_brackets(markdown: impl ToString) -> String {
+    let mut markdown = markdown.to_string();
+
+    let node = markdown::to_mdast(&markdown, &ParseOptions::default()).unwrap();
+
+    use markdown::mdast::Node;
+
+    fn process(node: &Node, md: &str, indices: &mut Vec<usize>) {
+        match node {
+            Node::Code(_) | Node::InlineCode(_) | Node::Html(_) => (),
+            other => {
+                let has_children =
+                    matches!(other.children(), Some(children) if !children.is_empty());
+
+                if let Some(pos) = other.position() {
+                    if !has_children {
+                        let start_pos = pos.start.offset;
+                        let end_pos = pos.end.offset;
+
+                        let to_replace_indices = md[start_pos..end_pos]
+                            .match_indices('<')
+                            .map(|(i, _)| i + start_pos);
+
+                        indices.extend(to_replace_indices);
+                    }
+                }
+
+                if let Some(children) = other.children() {
+                    for node in children {
+                        process(node, md, indices);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut indices = Vec::new();
+
+    process(&node, &markdown, &mut indices);
+
+    for (num_replaced, index) in indices.into_iter().enumerate() {
+        assert!(
+            markdown.get((index + num_replaced * 3)..(index + num_replaced * 3 + 1)) == Some("<")
+        );
+        markdown.replace_range(
+            (index + num_replaced * 3)..(index + num_replaced * 3 + 1),
+            "&lt;",
+        );
+    }
+
+    markdown
+}
+
+/// Slugify heading text into its id the way VitePress does, so links anchor to the heading.
+///
+/// Mirrors `slugify` from `@mdit-vue/shared`: runs of whitespace and punctuation become a
+/// single `-`, leading and trailing `-`s are dropped, a leading digit gets a `_` prefix, and
+/// the result is lowercased. Unlike VitePress, accented letters aren't decomposed first.
+fn heading_slug(text: &str) -> String {
+    const SPECIAL: &str = "~`!@#$%^&*()-_+=[]{}|\\;:\"'“”‘’<>,.?/";
+
+    let mut slug = String::new();
+    for ch in text
+        .chars()
+        .filter(|ch| !ch.is_control() || ch.is_whitespace())
+    {
+        if ch.is_whitespace() || SPECIAL.contains(ch) {
+            if !slug.ends_with('-') {
+                slug.push('-');
+            }
+        } else {
+            slug.push(ch);
+        }
+    }
+
+    let slug = slug.trim_matches('-');
+    let slug = if slug.starts_with(|ch: char| ch.is_ascii_digit()) {
+        format!("_{slug}")
+    } else {
+        slug.to_string()
+    };
+
+    slug.to_lowercase()
+}
+
+/// The ids of the headings on a page, which `markdown-it-anchor` makes unique by
+/// suffixing repeated slugs with `-1`, `-2`, and so on.
+#[derive(Default)]
+struct HeadingSlugs {
+    taken: HashSet<String>,
+}
+
+impl HeadingSlugs {
+    /// Take the id of the next heading on the page.
+    fn slug(&mut self, text: &str) -> String {
+        let slug = heading_slug(text);
+        let mut unique = slug.clone();
+        let mut suffix = 1;
+
+        while !self.taken.insert(unique.clone()) {
+            unique = format!("{slug}-{suffix}");
+            suffix += 1;
+        }
+
+        unique
+    }
+}
+
+/// Whether a function is documented on its class's page instead of the functions page.
+fn is_class_function(func: &Function, classes: &[Class]) -> bool {
+    func.table
+        .as_ref()
+        .is_some_and(|table| classes.iter().any(|class| &class.name == table))
+}
+
+/// The names of the modules classes belong to, in the order they're first declared.
+fn module_names(classes: &[Class]) -> Vec<&String> {
+    let mut names = Vec::new();
+
+    for module in classes.iter().filter_map(|class| class.module.as_ref()) {
+        if !names.contains(&module) {
+            names.push(module);
+        }
+    }
+
+    names
+}
+
+/// Compute a unique page name for each function given its own page.
+///
+/// Functions are qualified by their table (`Table/name`) so that functions with the same
+/// name on different tables don't collide. Functions that still collide, like a function
+/// defined twice on the same table, are ambiguous; they get a numeric suffix and a warning.
+fn function_page_names<'a>(functions: impl IntoIterator<Item = &'a Function>) -> Vec<String> {
+    let mut taken = HashMap::<String, usize>::new();
+
+    functions
+        .into_iter()
+        .map(|func| {
+            let name = match func.table.as_ref() {
+                Some(table) => format!("{table}/{}", func.name),
+                None => func.name.clone(),
+            };
+
+            let count = taken.entry(name.clone()).or_default();
+            *count += 1;
+
+            if *count == 1 {
+                name
+            } else {
+                let qualified_name = name.replace('/', ".");
+                log::warn!(
+                    "function `{qualified_name}` is defined multiple times, \
+                    documenting it as `{qualified_name}-{count}`"
+                );
+                format!("{name}-{count}")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{render::WriteStats, state::parse_str};
+
+    fn render(renderer: &VitePressRenderer, source: &str) -> HashMap<PathBuf, String> {
+        renderer
+            .render_pages(parse_str(source))
+            .into_iter()
+            .collect()
+    }
+
+    fn renderer() -> VitePressRenderer {
+        VitePressRenderer::new(PathBuf::new(), None)
+    }
+
+    mod function_pages {
+        use super::*;
+
+        #[test]
+        fn functions_are_qualified_by_table() {
+            let processor = parse_str(
+                r#"
+---@class Foo
+local Foo = {}
+
+---@class Bar
+local Bar = {}
+
+---Get a foo
+function Foo.get() end
+
+---Get a bar
+function Bar.get() end
+"#,
+            );
+
+            let names = function_page_names(&processor.functions);
+            assert_eq!(names, ["Foo/get", "Bar/get"]);
+        }
+
+        #[test]
+        fn functions_defined_twice_do_not_collide() {
+            let processor = parse_str(
+                r#"
+---@class Foo
+local Foo = {}
+
+---Do the thing
+function Foo.setup() end
+
+---Do the other thing
+function Foo.setup() end
+"#,
+            );
+
+            let names = function_page_names(&processor.functions);
+            assert_eq!(names, ["Foo/setup", "Foo/setup-2"]);
+        }
+
+        thread_local! {
+            static WARNINGS: std::cell::RefCell<Vec<String>> = const {
+                std::cell::RefCell::new(Vec::new())
+            };
+        }
+
+        /// Collects the warnings logged on the current thread, so tests running in
+        /// parallel don't see each other's.
+        struct CaptureWarnings;
+
+        impl log::Log for CaptureWarnings {
+            fn enabled(&self, metadata: &log::Metadata) -> bool {
+                metadata.level() <= log::Level::Warn
+            }
+
+            fn log(&self, record: &log::Record) {
+                if self.enabled(record.metadata()) {
+                    WARNINGS.with_borrow_mut(|warnings| warnings.push(record.args().to_string()));
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        fn warnings_during<T>(f: impl FnOnce() -> T) -> (T, Vec<String>) {
+            static LOGGER: CaptureWarnings = CaptureWarnings;
+            // Another test may have installed it already
+            let _ = log::set_logger(&LOGGER);
+            log::set_max_level(log::LevelFilter::Warn);
+
+            WARNINGS.take();
+            let result = f();
+            (result, WARNINGS.take())
+        }
+
+        const DUPLICATES: &str = r#"
+---@class Foo
+local Foo = {}
+
+---Get a foo
+function Foo.get() end
+
+---Get another foo
+function Foo.get() end
+
+---Get anything
+function get() end
+
+---Get anything else
+function get() end
+"#;
+
+        #[test]
+        fn duplicates_are_only_named_when_functions_get_pages() {
+            let (pages, warnings) = warnings_during(|| render(&renderer(), DUPLICATES));
+
+            assert_eq!(warnings, Vec::<String>::new());
+            assert!(pages
+                .keys()
+                .all(|path| !path.to_string_lossy().contains("-2")));
+        }
+
+        #[test]
+        fn only_class_functions_get_pages() {
+            let (pages, warnings) =
+                warnings_during(|| render(&renderer().function_pages(true), DUPLICATES));
+
+            assert_eq!(
+                warnings,
+                ["function `Foo.get` is defined multiple times, documenting it as `Foo.get-2`"]
+            );
+            assert!(pages.contains_key(&PathBuf::from("classes/Foo/get.md")));
+            assert!(pages.contains_key(&PathBuf::from("classes/Foo/get-2.md")));
+        }
+    }
+
+    mod base_url {
+        use super::*;
+
+        #[test]
+        fn base_urls_end_in_one_slash() {
+            for (base_url, expected) in [
+                ("", "/"),
+                ("/", "/"),
+                ("/repo", "/repo/"),
+                ("/repo/", "/repo/"),
+                ("//repo//", "/repo/"),
+                ("https://host/repo", "https://host/repo/"),
+                ("https://host//repo/", "https://host/repo/"),
+            ] {
+                assert_eq!(normalize_base_url(base_url), expected, "for `{base_url}`");
+            }
+        }
+
+        #[test]
+        fn links_use_the_normalized_base_url() {
+            let pages = render(
+                &VitePressRenderer::new(PathBuf::new(), Some("/repo".into())),
+                r#"
+---@class Window
+---@field parent Window
+local Window = {}
+"#,
+            );
+
+            assert!(pages[&PathBuf::from("classes/Window.md")]
+                .contains(r#"<a href="/repo/classes/Window">Window</a>"#));
+        }
+    }
+
+    mod empty_sections {
+        use super::*;
+
+        #[test]
+        fn empty_headings_are_removed() {
+            assert_eq!(
+                remove_empty_sections(
+                    "# Class\n\n## Fields\n\n### a\n\n## Functions\n\n\n## Other\n\ntext\n\n## Last\n"
+                ),
+                "# Class\n\n## Other\n\ntext\n\n"
+            );
+        }
+
+        #[test]
+        fn headings_in_code_blocks_are_kept() {
+            let markdown = "## Usage\n\n```sh\n# comment\n```\n";
+            assert_eq!(remove_empty_sections(markdown), markdown);
+        }
+
+        #[test]
+        fn empty_headings_in_descriptions_are_only_removed_with_the_flag() {
+            const SOURCE: &str = r#"
+---A window.
+---
+---## Examples
+---
+---## Notes
+---
+---Windows are closed on exit.
+---@class Window
+---@field title string
+local Window = {}
+"#;
+            let path = PathBuf::from("classes/Window.md");
+
+            let kept = &render(&renderer(), SOURCE)[&path];
+            assert!(kept.contains("## Examples\n\n## Notes\n"));
+
+            let removed = &render(&renderer().no_empty_sections(true), SOURCE)[&path];
+            assert!(!removed.contains("## Examples"));
+            assert!(removed.contains("## Notes\n\nWindows are closed on exit."));
+            assert!(removed.contains("## Fields"));
+        }
+    }
+
+    mod generic_aliases {
+        use super::*;
+
+        #[test]
+        fn type_params_are_not_linked() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class T
+local T = {}
+
+---@alias Result<T> T | string
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("aliases/Result.md")];
+            assert!(page.contains("# Alias `Result<T>`"));
+            assert!(page.contains("<code>T | string</code>"));
+            assert!(!page.contains("/classes/T"));
+        }
+    }
+
+    mod line_endings {
+        use super::*;
+
+        #[test]
+        fn pages_have_lf_endings_and_one_trailing_newline() {
+            let pages = render(
+                &renderer().version_matrix(true),
+                "---A window.\r\n---@class Window\r\n---@field id integer\r\n---@since 1.0\r\nlocal Window = {}\r\n\r\n---@alias Mode \"a\"\r\n\r\n---@enum Dir\r\nlocal Dir = { UP = 1 }\r\n",
+            );
+
+            assert_eq!(pages.len(), 5);
+            for (path, contents) in pages {
+                assert!(
+                    !contents.contains('\r'),
+                    "`{}` contains \\r",
+                    path.display()
+                );
+                assert!(contents.ends_with('\n'), "`{}`", path.display());
+                assert!(!contents.ends_with("\n\n"), "`{}`", path.display());
+            }
+        }
+    }
+
+    mod see {
+        use super::*;
+
+        #[test]
+        fn urls_are_linked_directly() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Window
+local Window = {}
+
+---@see https://example.com/windows The window guide
+---@see Window
+function Window.new() end
+"#,
+            );
+
+            assert!(pages[&PathBuf::from("classes/Window.md")].contains(
+                "- <a href=\"https://example.com/windows\">https://example.com/windows</a>: The window guide\n\
+                - <code><a href=\"/classes/Window\">Window</a></code>"
+            ));
+        }
+
+        #[test]
+        fn members_link_to_their_heading() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Window
+---@field max_width integer
+---@field close boolean
+local Window = {}
+
+---@see Window.max_width
+---@see Window:close
+---@see Window.missing
+---@see Layout.Tiled
+function Window.new() end
+
+---Close the window.
+function Window:close() end
+
+---@enum Layout
+local Layout = {
+    Tiled = 1,
+}
+"#,
+            );
+
+            assert!(pages[&PathBuf::from("classes/Window.md")].contains(
+                "- <code><a href=\"/classes/Window#max-width\">Window.max_width</a></code>\n\
+                - <code><a href=\"/classes/Window#close-1\">Window:close</a></code>\n\
+                - <code><a href=\"/classes/Window\">Window.missing</a></code>\n\
+                - <code><a href=\"/enums/Layout#tiled\">Layout.Tiled</a></code>"
+            ));
+        }
+
+        #[test]
+        fn headings_are_slugified_like_vitepress() {
+            assert_eq!(heading_slug("set_title"), "set-title");
+            assert_eq!(heading_slug("setTitle"), "settitle");
+            assert_eq!(heading_slug(r#"["content-type"]"#), "content-type");
+            assert_eq!(heading_slug("  a -- b  "), "a-b");
+            assert_eq!(heading_slug("2d"), "_2d");
+
+            let mut slugs = HeadingSlugs::default();
+            assert_eq!(slugs.slug("close"), "close");
+            assert_eq!(slugs.slug("Close"), "close-1");
+            assert_eq!(slugs.slug("close"), "close-2");
+        }
+    }
+
+    mod group_optional_params {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Window
+local Window = {}
+
+---@param title string?
+---@param width integer
+---@param height integer
+---@default 600
+---@param id integer
+function Window.new(title, width, height, id) end
+"#;
+
+        #[test]
+        fn optional_params_come_after_required_ones() {
+            let pages = render(&renderer().group_optional_params(true), SOURCE);
+
+            assert!(pages[&PathBuf::from("classes/Window.md")].contains(
+                "#### Parameters\n\n\
+                `width`: <code>integer</code><br>\n\
+                `id`: <code>integer</code>\n\n\
+                ##### Optional parameters\n\n\
+                `title?`: <code>string</code><br>\n\
+                `height`: <code>integer</code> (Default: `600`)"
+            ));
+        }
+
+        #[test]
+        fn params_keep_their_order_by_default() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(!page.contains("Optional parameters"));
+            assert!(page.find("`title?`").unwrap() < page.find("`width`").unwrap());
+        }
+    }
+
+    mod verbose_types {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Point
+local Point = {}
+
+---@param coords [number, number, string]
+function Point.new(coords) end
+"#;
+
+        #[test]
+        fn tuples_are_numbered_and_noted_as_fixed_length() {
+            let pages = render(&renderer().verbose_types(true), SOURCE);
+
+            assert!(pages[&PathBuf::from("classes/Point.md")].contains(
+                "`coords`: <code>[1: number, 2: number, 3: string] (fixed-length)</code>"
+            ));
+        }
+
+        #[test]
+        fn tuples_are_terse_by_default() {
+            let pages = render(&renderer(), SOURCE);
+
+            assert!(pages[&PathBuf::from("classes/Point.md")]
+                .contains("`coords`: <code>[number, number, string]</code>"));
+        }
+    }
+
+    mod link_literals {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@enum State
+local State = {
+    focused = "focused",
+    unfocused = "unfocused",
+}
+
+---@class Window
+local Window = {}
+
+---@param state "focused" | "minimized"
+function Window.set_state(state) end
+"#;
+
+        #[test]
+        fn literals_link_to_matching_enum_values() {
+            let pages = render(&renderer().link_literals(true), SOURCE);
+
+            assert!(pages[&PathBuf::from("classes/Window.md")].contains(
+                "`state`: one of <code><a href=\"/enums/State#focused\">\"focused\"</a></code>, \
+                <code>\"minimized\"</code>"
+            ));
+        }
+
+        #[test]
+        fn literals_are_not_linked_by_default() {
+            let pages = render(&renderer(), SOURCE);
+
+            assert!(pages[&PathBuf::from("classes/Window.md")]
+                .contains("`state`: one of <code>\"focused\"</code>, <code>\"minimized\"</code>"));
+        }
+    }
+
+    mod show_self {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Window
+local Window = {}
+
+---@param self Window
+---@param width integer
+function Window:resize(width) end
+
+---@param self Window
+function Window.close(self) end
+"#;
+
+        #[test]
+        fn annotated_self_is_left_out_of_methods() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[Path::new("classes/Window.md")];
+
+            assert!(page.contains("function Window:resize(width: integer)</code>"));
+            assert!(page.contains("#### Parameters\n\n`width`: <code>integer</code>"));
+            assert!(page.contains(
+                "function Window.close(self: <a href=\"/classes/Window\">Window</a>)</code>"
+            ));
+        }
+
+        #[test]
+        fn show_self_keeps_it() {
+            let pages = render(&renderer().show_self(true), SOURCE);
+            let page = &pages[Path::new("classes/Window.md")];
+
+            assert!(page.contains(
+                "function Window:resize(self: <a href=\"/classes/Window\">Window</a>, width: integer)"
+            ));
+            assert!(page.contains(
+                "`self`: <code><a href=\"/classes/Window\">Window</a></code><br>\n`width`"
+            ));
+        }
+
+        #[test]
+        fn symbols_manifest_matches() {
+            let pages = render(&renderer().symbols_manifest(true), SOURCE);
+
+            assert!(pages[Path::new("symbols.json")]
+                .contains("\"signature\": \"Window:resize(width: integer)\""));
+        }
+    }
+
+    mod literal_choices {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class File
+local File = {}
+
+---@param mode "read" | "write" | "append" How to open the file
+---@param name string | "default"
+---@param flag "a" | "b" | nil
+function File.open(mode, name, flag) end
+"#;
+
+        #[test]
+        fn string_literal_unions_list_their_choices() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[Path::new("classes/File.md")];
+
+            assert!(page.contains(
+                "`mode`: one of <code>\"read\"</code>, <code>\"write\"</code>, \
+                <code>\"append\"</code> - How to open the file"
+            ));
+            assert!(page.contains("function File.open(mode: \"read\" | \"write\" | \"append\", "));
+        }
+
+        #[test]
+        fn mixed_unions_render_as_types() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[Path::new("classes/File.md")];
+
+            assert!(page.contains("`name`: <code>string | \"default\"</code>"));
+            assert!(page.contains("`flag`: <code>\"a\" | \"b\" | nil</code>"));
+        }
+    }
+
+    mod deprecated {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Window
+---@deprecated Use `Surface` instead.
+---@field title string
+---@deprecated
+local Window = {}
+
+---@deprecated Use `Window.get` instead.
+function Window.find() end
+"#;
+
+        #[test]
+        fn deprecated_items_get_a_badge() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(
+                page.contains("# Class `Window` <Badge type=\"danger\" text=\"deprecated\" />\n")
+            );
+            assert!(page.contains("### title <Badge type=\"danger\" text=\"deprecated\" />\n"));
+            assert!(page.contains(
+                "### <Badge type=\"function\" text=\"function\" /> find \
+                <Badge type=\"danger\" text=\"deprecated\" />\n"
+            ));
+        }
+
+        #[test]
+        fn deprecation_messages_are_rendered_in_a_block() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains("::: danger DEPRECATED\nUse `Surface` instead.\n:::\n"));
+            assert!(page.contains("::: danger DEPRECATED\nUse `Window.get` instead.\n:::\n"));
+        }
+    }
+
+    mod async_functions {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Window
+local Window = {}
+
+---@async
+---@return string
+function Window.wait_for_title() end
+
+---@return string
+function Window.title() end
+"#;
+
+        #[test]
+        fn async_functions_get_a_badge_and_keyword() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains(
+                "### <Badge type=\"function\" text=\"function\" /> \
+                <Badge type=\"tip\" text=\"async\" /> wait_for_title\n"
+            ));
+            assert!(page.contains("<code>async function Window.wait_for_title()"));
+        }
+
+        #[test]
+        fn other_functions_are_unaffected() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains("### <Badge type=\"function\" text=\"function\" /> title\n"));
+            assert!(page.contains("<code>function Window.title()"));
+        }
+    }
+
+    mod overloads {
+        use super::*;
+
+        #[test]
+        fn overloads_render_between_the_signature_and_parameters() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Window
+local Window = {}
+
+---Get a window.
+---@overload fun(id: integer): Window
+---@overload fun(title: string): Window?
+---@param filter fun(window: Window): boolean
+---@return Window?
+function Window.get(filter) end
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains(
+                "#### Overloads\n\n\
+                <div class=\"language-lua\"><pre><code>fun(id: integer): \
+                <a href=\"/classes/Window\">Window</a></code></pre></div>\n\n\
+                <div class=\"language-lua\"><pre><code>fun(title: string): \
+                <a href=\"/classes/Window\">Window</a>?</code></pre></div>"
+            ));
+            assert!(page.find("Get a window.").unwrap() < page.find("#### Overloads").unwrap());
+            assert!(page.find("#### Overloads").unwrap() < page.find("#### Parameters").unwrap());
+        }
+    }
+
+    mod generics {
+        use super::*;
+
+        #[test]
+        fn type_params_are_listed_and_not_linked() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Window
+local Window = {}
+
+---@generic T: Window
+---@param window T
+---@return T
+function Window.clone(window) end
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains(
+                "#### Type parameters\n\n\
+                - `T`: <code><a href=\"/classes/Window\">Window</a></code>"
+            ));
+            assert!(page.contains("`window`: <code>T</code>"));
+            assert!(page.contains("1. <code>T</code>"));
+        }
+    }
+
+    mod method_style {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Window
+local Window = {}
+
+---@param title string
+function Window:set_title(title) end
+"#;
+
+        #[test]
+        fn methods_use_a_colon_by_default() {
+            let pages = render(&renderer(), SOURCE);
+
+            assert!(pages[&PathBuf::from("classes/Window.md")]
+                .contains("<code>function Window:set_title(title: string)</code>"));
+        }
+
+        #[test]
+        fn dot_style_shows_self_explicitly() {
+            let pages = render(&renderer().method_style(MethodStyle::Dot), SOURCE);
+
+            assert!(pages[&PathBuf::from("classes/Window.md")]
+                .contains("<code>function Window.set_title(self, title: string)</code>"));
+        }
+
+        #[test]
+        fn an_annotated_self_replaces_the_implicit_one() {
+            let pages = render(
+                &renderer().method_style(MethodStyle::Dot).show_self(true),
+                r#"
+---@class Window
+local Window = {}
+
+---@param self Window
+---@param title string
+function Window:set_title(title) end
+"#,
+            );
+
+            assert!(pages[&PathBuf::from("classes/Window.md")].contains(
+                "<code>function Window.set_title(self: <a href=\"/classes/Window\">Window</a>, title: string)</code>"
+            ));
+        }
+    }
+
+    mod sidebar {
+        use super::*;
+
+        #[test]
+        fn sidebar_groups_are_sorted_and_skip_empty_ones() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Window
+local Window = {}
+
+---@class Output
+local Output = {}
+
+---@alias Direction "left" | "right"
+"#,
+            );
+
+            let sidebar =
+                serde_json::from_str::<serde_json::Value>(&pages[&PathBuf::from("sidebar.json")])
+                    .unwrap();
+
+            assert_eq!(
+                sidebar,
+                serde_json::json!([
+                    {
+                        "text": "Classes",
+                        "items": [
+                            { "text": "Output", "link": "/classes/Output" },
+                            { "text": "Window", "link": "/classes/Window" },
+                        ],
+                    },
+                    {
+                        "text": "Aliases",
+                        "items": [{ "text": "Direction", "link": "/aliases/Direction" }],
+                    },
+                ])
+            );
+        }
+    }
+
+    mod key_enums {
+        use super::*;
+
+        #[test]
+        fn keys_are_shown_with_their_values() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@enum (key) Layer
+local Layer = {
+    ---The bottom layer.
+    background = 0,
+    top = 2,
+}
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("enums/Layer.md")];
+
+            assert!(page.contains("`\"background\"` | `\"top\"`"));
+            assert!(page
+                .contains("### `\"background\"`\n\n`\"background\"` = `0`\n\nThe bottom layer.\n"));
+            assert!(page.contains("### `\"top\"`\n\n`\"top\"` = `2`\n"));
+        }
+    }
+
+    mod varargs {
+        use super::*;
+
+        #[test]
+        fn varargs_params_are_documented() {
+            let source = r#"
+---@class Math
+local Math = {}
+
+---Sum numbers.
+---@param first integer
+---@param ... integer the numbers
+function Math.sum(first, ...) end
+"#;
+            let processor = parse_str(source);
+            assert!(processor.check_strict_types().is_empty());
+
+            let pages = render(&renderer(), source);
+            let math = &pages[&PathBuf::from("classes/Math.md")];
+
+            assert!(math.contains("function Math.sum(first: integer, ...: integer)"));
+            assert!(math.contains("`...`: <code>integer</code> - the numbers"));
+        }
+
+        #[test]
+        fn varargs_returns_are_documented() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Str
+local Str = {}
+
+---Match a pattern.
+---@param s string
+---@return string ... the captures
+function Str.match(s) end
+
+---@return ...
+function Str.pass() end
+"#,
+            );
+            let page = &pages[&PathBuf::from("classes/Str.md")];
+
+            assert!(page.contains("function Str.match(s: string)\n    -> ...: string</code>"));
+            assert!(page.contains("1. `...`: <code>string</code> - the captures\n"));
+            assert!(page.contains("function Str.pass()\n    -> ...: any</code>"));
+            assert!(page.contains("1. `...`: <code>any</code>\n"));
+        }
+    }
+
+    mod inline_defaults {
+        use super::*;
+
+        #[test]
+        fn defaults_are_shown_in_the_signature_and_parameters() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Window
+local Window = {}
+
+---Resize the window.
+---@param width integer [default=800] The width
+---@param height integer
+function Window.resize(width, height) end
+"#,
+            );
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains("function Window.resize(width: integer = 800, height: integer)"));
+            assert!(page.contains("`width`: <code>integer</code> - The width (Default: `800`)"));
+        }
+    }
+
+    mod modules {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---Windowing utilities.
+---@module 'ui'
+---@class Ui
+local Ui = {}
+
+---Open a window.
+---@return Window
+function Ui.open() end
+
+---A window.
+---@class Window
+local Window = {}
+
+---Close the window.
+function Window:close() end
+"#;
+
+        #[test]
+        fn classes_after_a_module_belong_to_it() {
+            let processor = parse_str(SOURCE);
+
+            let ui = &processor.classes[0];
+            assert!(ui.is_module);
+            assert_eq!(ui.module.as_deref(), Some("ui"));
+
+            let window = &processor.classes[1];
+            assert!(!window.is_module);
+            assert_eq!(window.module.as_deref(), Some("ui"));
+
+            let processor = parse_str("---@class Loose\nlocal Loose = {}\n");
+            assert_eq!(processor.classes[0].module, None);
+        }
+
+        #[test]
+        fn modules_get_a_page_with_their_contents() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[&PathBuf::from("modules/ui.md")];
+
+            assert!(page.contains("# Module `ui`\n\nWindowing utilities.\n"));
+            assert!(page.contains(
+                "## Classes\n\n\
+                - <code><a href=\"/classes/Ui\">Ui</a></code> — Windowing utilities.\n\
+                - <code><a href=\"/classes/Window\">Window</a></code> — A window.\n"
+            ));
+            assert!(page.contains(
+                "## Functions\n\n\
+                - <code><a href=\"/classes/Ui#open\">Ui.open</a></code> — Open a window.\n\
+                - <code><a href=\"/classes/Window#close\">Window:close</a></code> — Close the window.\n"
+            ));
+
+            let sidebar: serde_json::Value =
+                serde_json::from_str(&pages[&PathBuf::from("sidebar.json")]).unwrap();
+            assert_eq!(sidebar[0]["text"], "Modules");
+            assert_eq!(sidebar[0]["items"][0]["link"], "/modules/ui");
+        }
+    }
+
+    mod cyclic_types {
+        use super::*;
+
+        #[test]
+        fn self_referential_types_render_as_links() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@alias Node { value: integer, next: Node }
+
+---@alias Even { next: Odd }
+---@alias Odd { next: Even }
+
+---@class List
+---@field head Node
+---@field even Even
+"#,
+            );
+
+            let node = r#"<a href="/aliases/Node">Node</a>"#;
+            let even = r#"<a href="/aliases/Even">Even</a>"#;
+            let odd = r#"<a href="/aliases/Odd">Odd</a>"#;
+
+            assert!(pages[&PathBuf::from("aliases/Node.md")]
+                .contains(&format!("{{ value: integer, next: {node} }}")));
+            assert!(
+                pages[&PathBuf::from("aliases/Even.md")].contains(&format!("{{ next: {odd} }}"))
+            );
+            assert!(
+                pages[&PathBuf::from("aliases/Odd.md")].contains(&format!("{{ next: {even} }}"))
+            );
+            assert!(pages[&PathBuf::from("classes/List.md")].contains(node));
+        }
+    }
+
+    mod source_links {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Window
+---@field title string
+local Window = {}
+
+---Close the window.
+function Window.close() end
+"#;
+
+        #[test]
+        fn locations_are_code_spans_without_a_template() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains("Source: `test.lua:2`"));
+            assert!(page.contains("`title`: <code>string</code>\n\nSource: `test.lua:3`"));
+            assert!(page.contains("Source: `test.lua:7`\n\nClose the window."));
+        }
+
+        #[test]
+        fn locations_are_linked_with_a_template() {
+            let renderer = renderer().source_url_template(Some(
+                "https://github.com/user/repo/blob/main/{file}#L{line}".to_string(),
+            ));
+            let pages = render(&renderer, SOURCE);
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains(
+                "Source: <a href=\"https://github.com/user/repo/blob/main/test.lua#L7\">\
+                <code>test.lua:7</code></a>"
+            ));
+        }
+
+        #[test]
+        fn linked_urls_are_escaped() {
+            let renderer = renderer().source_url_template(Some(
+                "https://host/view?file={file}&line={line}\"".to_string(),
+            ));
+            let pages = render(
+                &renderer,
+                r#"
+---@see https://host/search?q=a&kind="window"
+function open() end
+"#,
+            );
+            let page = &pages[&PathBuf::from("functions.md")];
+
+            assert!(page
+                .contains("Source: <a href=\"https://host/view?file=test.lua&amp;line=3&quot;\">"));
+            assert!(page.contains(
+                "<a href=\"https://host/search?q=a&amp;kind=&quot;window&quot;\">\
+                https://host/search?q=a&amp;kind=&quot;window&quot;</a>"
+            ));
+        }
+    }
+
+    mod symbols_manifest {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Window : Widget
+---@field title string?
+local Window = {}
+
+---Resize the window.
+---@param width integer
+---@return boolean ok
+function Window:resize(width) end
+
+---@alias Direction "left" | "right"
+
+---@enum State
+local State = {
+    focused = 1,
+}
+
+---Not on a table
+function helper() end
+
+---Not on a class
+function M.other() end
+"#;
+
+        fn manifest(renderer: &VitePressRenderer) -> serde_json::Value {
+            let pages = render(renderer, SOURCE);
+            serde_json::from_str(&pages[&PathBuf::from("symbols.json")]).unwrap()
+        }
+
+        #[test]
+        fn symbols_are_listed_with_their_pages() {
+            let manifest = manifest(&renderer().symbols_manifest(true));
+
+            assert_eq!(manifest["version"], 1);
+            assert_eq!(
+                manifest["symbols"],
+                serde_json::json!([
+                    {
+                        "kind": "class",
+                        "name": "Window",
+                        "signature": "Window : Widget",
+                        "path": "classes/Window.md",
+                        "anchor": null,
+                    },
+                    {
+                        "kind": "field",
+                        "name": "Window.title",
+                        "signature": "title?: string",
+                        "path": "classes/Window.md",
+                        "anchor": "title",
+                    },
+                    {
+                        "kind": "method",
+                        "name": "Window:resize",
+                        "signature": "Window:resize(width: integer) -> ok: boolean",
+                        "path": "classes/Window.md",
+                        "anchor": "resize",
+                    },
+                    {
+                        "kind": "function",
+                        "name": "helper",
+                        "signature": "helper()",
+                        "path": "functions.md",
+                        "anchor": "helper",
+                    },
+                    {
+                        "kind": "function",
+                        "name": "M.other",
+                        "signature": "M.other()",
+                        "path": "functions.md",
+                        "anchor": "other",
+                    },
+                    {
+                        "kind": "alias",
+                        "name": "Direction",
+                        "signature": "Direction = \"left\" | \"right\"",
+                        "path": "aliases/Direction.md",
+                        "anchor": null,
+                    },
+                    {
+                        "kind": "enum",
+                        "name": "State",
+                        "signature": "State",
+                        "path": "enums/State.md",
+                        "anchor": null,
+                    },
+                    {
+                        "kind": "enum_value",
+                        "name": "State.focused",
+                        "signature": "focused = 1",
+                        "path": "enums/State.md",
+                        "anchor": "focused",
+                    },
+                ])
+            );
+        }
+
+        #[test]
+        fn functions_with_their_own_pages_link_to_them() {
+            let manifest = manifest(&renderer().symbols_manifest(true).function_pages(true));
+
+            let resize = &manifest["symbols"][2];
+            assert_eq!(resize["path"], "classes/Window/resize.md");
+            assert_eq!(resize["anchor"], serde_json::Value::Null);
+        }
+
+        #[test]
+        fn manifest_is_opt_in() {
+            let pages = render(&renderer(), SOURCE);
+            assert!(!pages.contains_key(&PathBuf::from("symbols.json")));
+        }
+    }
+
+    mod search_index {
+        use super::*;
+
+        #[test]
+        fn symbols_link_to_their_headings() {
+            let pages = render(
+                &VitePressRenderer::new(PathBuf::new(), Some("/docs/".into())).search_index(true),
+                r#"
+---A window, which holds **one** buffer.
+---@class Window
+---@field max_width integer The widest it gets
+local Window = {}
+
+---Close the window.
+function Window:close() end
+"#,
+            );
+            let index: serde_json::Value =
+                serde_json::from_str(&pages[&PathBuf::from("search-index.json")]).unwrap();
+
+            assert_eq!(
+                index,
+                serde_json::json!([
+                    {
+                        "name": "Window",
+                        "kind": "class",
+                        "snippet": "A window, which holds one buffer.",
+                        "url": "/docs/classes/Window",
+                    },
+                    {
+                        "name": "Window.max_width",
+                        "kind": "field",
+                        "snippet": "The widest it gets",
+                        "url": "/docs/classes/Window#max-width",
+                    },
+                    {
+                        "name": "Window:close",
+                        "kind": "method",
+                        "snippet": "Close the window.",
+                        "url": "/docs/classes/Window#close",
+                    },
+                ])
+            );
+        }
+    }
+
+    mod enum_values {
+        use super::*;
+
+        #[test]
+        fn enums_of_functions_show_each_function() {
+            let source = r#"
+---@enum Handlers
+local Handlers = {
+    ---Runs on click.
+    on_click = function(button)
+        print(button)
+    end,
+    on_key = function() end,
+}
+"#;
+            assert!(parse_str(source).functions.is_empty());
+
+            let pages = render(&renderer(), source);
+            let page = &pages[&PathBuf::from("enums/Handlers.md")];
+
+            assert!(page.contains(
+                "`Handlers.on_click`\n\n```lua\nfunction(button)\n    print(button)\nend\n```\n\nRuns on click.\n"
+            ));
+            assert!(page.contains("`Handlers.on_key` = `function() end`\n"));
+        }
+
+        #[test]
+        fn enums_of_tables_show_their_type_and_value() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Layout
+
+---@enum Layouts
+local Layouts = {
+    ---@type Layout
+    tiled = { gap = 4 },
+    floating = {
+        gap = 0,
+        snap = true,
+    },
+    count = 2,
+}
+"#,
+            );
+            let page = &pages[&PathBuf::from("enums/Layouts.md")];
+
+            assert!(page.contains(
+                "`Layouts.tiled`: <code><a href=\"/classes/Layout\">Layout</a></code> = `{ gap = 4 }`\n"
+            ));
+            assert!(page.contains(
+                "`Layouts.floating`\n\n```lua\n{\n    gap = 0,\n    snap = true,\n}\n```\n"
+            ));
+            assert!(page.contains("`Layouts.count` = `2`\n"));
+        }
+    }
+
+    mod diagnostics {
+        use super::*;
+
+        #[test]
+        fn descriptions_continue_past_diagnostics() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Window
+local Window = {}
+
+---Close the window.
+---@diagnostic disable-next-line: undefined-global
+---Buffers in it stay loaded.
+---Focus moves to the previous window.
+---@param force boolean
+function Window.close(force) end
+"#,
+            );
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains(
+                "Close the window.\nBuffers in it stay loaded.\nFocus moves to the previous window.\n"
+            ));
+            assert!(!page.contains("diagnostic"));
+        }
+    }
+
+    mod union_lists {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Window
+---@field mode "tiled" | "floating" | "fullscreen" | "maximized" | "minimized" The window mode.
+---@field layer "top" | "bottom"
+local Window = {}
+
+---@return "tiled" | "floating" | "fullscreen" | "maximized" | "minimized" | nil mode The window mode.
+---@return boolean
+function Window:state() end
+"#;
+
+        #[test]
+        fn long_field_unions_are_listed() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains(
+                "`mode`: one of\n\n- <code>\"tiled\"</code>\n- <code>\"floating\"</code>\n- <code>\"fullscreen\"</code>\n- <code>\"maximized\"</code>\n- <code>\"minimized\"</code>\n\n"
+            ));
+        }
+
+        #[test]
+        fn short_unions_stay_inline() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains("`layer`: <code>\"top\" | \"bottom\"</code>"));
+            assert!(page.contains("2. <code>boolean</code>"));
+        }
+
+        #[test]
+        fn long_return_unions_are_listed_under_the_return() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page
+                .contains("1. `mode`: one of - The window mode.\n   - <code>\"tiled\"</code>\n"));
+            assert!(page.contains("   - <code>nil</code>\n2. <code>boolean</code>"));
+        }
+    }
+
+    mod free_functions {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Window
+local Window = {}
+
+---Close the window.
+function Window:close() end
+
+---Open a window.
+---@param title string
+---@return Window
+---@see close_all
+function open(title) end
+
+---Close every window.
+function close_all() end
+"#;
+
+        #[test]
+        fn functions_without_a_table_get_a_page() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[&PathBuf::from("functions.md")];
+
+            assert!(page.starts_with(
+                "---\ntitle: \"Functions\"\noutline: [2, 3]\n---\n\n# Functions\n\n## "
+            ));
+            assert!(page.contains("function open(title: string)"));
+            assert!(page.contains("### Parameters"));
+            assert!(page.contains("function close_all()"));
+            assert!(page.contains(r#"<a href="/functions#close-all">close_all</a>"#));
+            assert!(!page.contains("Window:close"));
+
+            let window = &pages[&PathBuf::from("classes/Window.md")];
+            assert!(!window.contains("function open"));
+        }
+
+        #[test]
+        fn the_page_is_in_the_sidebar() {
+            let pages = render(&renderer(), SOURCE);
+            let sidebar: serde_json::Value =
+                serde_json::from_str(&pages[&PathBuf::from("sidebar.json")]).unwrap();
+
+            assert_eq!(
+                sidebar.as_array().unwrap().last().unwrap(),
+                &serde_json::json!({ "text": "Functions", "link": "/functions" })
+            );
+        }
+
+        #[test]
+        fn no_page_without_free_functions() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Window
+local Window = {}
+
+---Close the window.
+function Window:close() end
+"#,
+            );
+
+            assert!(!pages.contains_key(&PathBuf::from("functions.md")));
+            assert!(!pages[&PathBuf::from("sidebar.json")].contains("Functions"));
+        }
+
+        #[test]
+        fn functions_on_module_tables_get_the_page() {
+            let pages = render(
+                &renderer().search_index(true),
+                r#"
+local M = {}
+
+---Get a value.
+---@see M.set
+function M.get() end
+
+---Set a value.
+function M.set() end
+"#,
+            );
+            let page = &pages[&PathBuf::from("functions.md")];
+
+            assert!(page.contains("function M.get()"));
+            assert!(page.contains("function M.set()"));
+            assert!(page.contains(r#"<a href="/functions#set">M.set</a>"#));
+
+            let index = &pages[&PathBuf::from("search-index.json")];
+            assert!(index.contains(r#""url": "/functions#get""#));
+        }
+    }
+
+    mod dry_run {
+        use super::*;
+
+        #[test]
+        fn pages_are_listed_without_writing_them() {
+            let dir = tempfile::tempdir().unwrap();
+            let out_dir = dir.path().join("out");
+            let renderer = VitePressRenderer::new(out_dir.clone(), None);
+
+            let mut paths = renderer.dry_run(parse_str(
+                r#"
+---@class Window
+local Window = {}
+
+---@alias Direction "left" | "right"
+"#,
+            ));
+            paths.sort();
+
+            assert_eq!(
+                paths,
+                [
+                    out_dir.join("aliases/Direction.md"),
+                    out_dir.join("classes/Window.md"),
+                    out_dir.join("sidebar.json"),
+                ]
+            );
+            assert!(!out_dir.exists());
+        }
+    }
+
+    mod section_dirs {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@alias Mode "a" | "b"
+
+---@enum Layout
+local Layout = {
+    Tiled = 1,
+}
+
+---@class Window
+---@field mode Mode
+---@field layout Layout
+local Window = {}
+
+---@class Other
+local Other = {}
+
+---@param win Window
+function Other.close(win) end
+"#;
+
+        fn custom_dirs() -> SectionDirs {
+            SectionDirs {
+                classes: "api/classes".to_string(),
+                aliases: "api/aliases".to_string(),
+                enums: "api/enums".to_string(),
+            }
+        }
+
+        #[test]
+        fn pages_are_written_to_the_configured_dirs() {
+            let pages = render(&renderer().section_dirs(custom_dirs()), SOURCE);
+
+            assert!(pages.contains_key(Path::new("api/classes/Window.md")));
+            assert!(pages.contains_key(Path::new("api/aliases/Mode.md")));
+            assert!(pages.contains_key(Path::new("api/enums/Layout.md")));
+            assert!(!pages.keys().any(|path| path.starts_with("classes")));
+        }
+
+        #[test]
+        fn links_follow_the_configured_dirs() {
+            let pages = render(
+                &VitePressRenderer::new(PathBuf::new(), Some("/docs/".into()))
+                    .section_dirs(custom_dirs()),
+                SOURCE,
+            );
+
+            let window = &pages[Path::new("api/classes/Window.md")];
+            assert!(window.contains(r#"<a href="/docs/api/aliases/Mode">Mode</a>"#));
+            assert!(window.contains(r#"<a href="/docs/api/enums/Layout">Layout</a>"#));
+
+            let other = &pages[Path::new("api/classes/Other.md")];
+            assert!(other.contains(r#"<a href="/docs/api/classes/Window">Window</a>"#));
+
+            let sidebar = &pages[Path::new("sidebar.json")];
+            assert!(sidebar.contains(r#""link": "/docs/api/classes/Window""#));
+            assert!(sidebar.contains(r#""link": "/docs/api/enums/Layout""#));
+        }
+
+        #[test]
+        fn default_dirs_are_unchanged() {
+            let pages = render(&renderer(), SOURCE);
+
+            assert!(pages.contains_key(Path::new("classes/Window.md")));
+            assert!(pages.contains_key(Path::new("aliases/Mode.md")));
+            assert!(pages.contains_key(Path::new("enums/Layout.md")));
+        }
+    }
+
+    mod frontmatter {
+        use super::*;
+
+        #[test]
+        fn pages_have_a_title_and_the_first_sentence_as_description() {
+            let pages = render(
+                &renderer(),
+                r#"
+---A window on the screen. It can be moved
+---and resized.
+---@class Window
+local Window = {}
+
+---How a window is laid out!
+---@enum Layout
+local Layout = {
+    Tiled = 1,
+}
+"#,
+            );
+
+            assert!(pages[Path::new("classes/Window.md")].starts_with(
+                "---\ntitle: \"Class Window\"\ndescription: \"A window on the screen.\"\noutline: [2, 3]\n---\n\n# Class `Window`"
+            ));
+            assert!(pages[Path::new("enums/Layout.md")].starts_with(
+                "---\ntitle: \"Enum Layout\"\ndescription: \"How a window is laid out!\"\noutline: [2, 3]\n---\n\n# Enum `Layout`"
+            ));
+        }
+
+        #[test]
+        fn undocumented_symbols_have_no_description() {
+            let pages = render(&renderer(), "---@alias Mode string\n");
+
+            assert!(pages[Path::new("aliases/Mode.md")]
+                .starts_with("---\ntitle: \"Alias Mode\"\noutline: [2, 3]\n---\n\n# Alias `Mode`"));
+        }
+
+        #[test]
+        fn descriptions_are_plain_text_without_markdown() {
+            assert_eq!(
+                frontmatter("Class Window", "Opens a **window** with [`open`](/open).\n\nMore."),
+                "---\ntitle: \"Class Window\"\ndescription: \"Opens a window with open.\"\noutline: [2, 3]\n---\n\n"
+            );
+        }
+
+        #[test]
+        fn yaml_special_characters_are_escaped() {
+            assert_eq!(yaml_string(r#"Mode: "a" \ b"#), r#""Mode: \"a\" \\ b""#);
+            assert_eq!(yaml_string("- item\tnext"), r#""- item\tnext""#);
+            assert_eq!(yaml_string("bell\u{7}"), r#""bell\u0007""#);
+        }
+
+        #[test]
+        fn sentences_end_at_punctuation_followed_by_whitespace() {
+            assert_eq!(
+                first_sentence("Uses v1.2 of the API. More."),
+                "Uses v1.2 of the API."
+            );
+            assert_eq!(first_sentence("No punctuation"), "No punctuation");
+            assert_eq!(first_sentence("Ends here."), "Ends here.");
+        }
+    }
+
+    mod badges {
+        use super::*;
+
+        #[test]
+        fn badges_are_rendered_after_the_heading() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Window
+---@badge warning experimental
+local Window = {}
+
+---@badge info "server" only
+---@return nil
+function Window.close() end
+"#,
+            );
+
+            let page = &pages[Path::new("classes/Window.md")];
+            assert!(
+                page.contains(r#"# Class `Window` <Badge type="warning" text="experimental" />"#)
+            );
+            assert!(page.contains(r#"close <Badge type="info" text="&quot;server&quot; only" />"#));
+        }
+    }
+
+    mod nested_fields {
+        use super::*;
+
+        const CONFIG: &str = r#"
+---@class Config
+local config = {
+    ---Keys bound in the picker.
+    keybinds = {
+        ---Close the picker
+        quit = "q",
+        ---Movement keys
+        movement = {
+            up = "k",
+        },
+    },
+}
+"#;
+
+        #[test]
+        fn table_fields_are_expanded_in_a_collapsible_section() {
+            let pages = render(&renderer(), CONFIG);
+            let page = &pages[Path::new("classes/Config.md")];
+
+            assert!(page.contains(
+                "<details>\n<summary>Fields of <code>keybinds</code></summary>\n\n\
+                 - `quit` = `\"q\"` — Close the picker\n\
+                 - `movement` — Movement keys\n  - `up` = `\"k\"`\n\n</details>"
+            ));
+            assert!(!page.contains("`keybinds` = "));
+        }
+
+        #[test]
+        fn inline_descriptions_list_table_fields_under_the_field() {
+            let pages = render(&renderer().inline_descriptions(true), CONFIG);
+            let page = &pages[Path::new("classes/Config.md")];
+
+            assert!(page.contains(
+                "- `keybinds` — Keys bound in the picker.\n  - `quit` = `\"q\"` — Close the picker\n"
+            ));
+        }
+    }
+
+    mod since_notes {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Window
+---@since 1.2.0
+---@field title string
+---@since 1.3.0
+local Window = {}
+
+---@since 2.0.0-beta
+---@return nil
+function Window.close() end
+
+---@enum Layout
+---@since 0.9
+local Layout = {
+    Tiled = 1,
+}
+"#;
+
+        #[test]
+        fn symbols_note_the_version_that_introduced_them() {
+            let pages = render(&renderer(), SOURCE);
+
+            let page = &pages[Path::new("classes/Window.md")];
+            assert!(page.contains("# Class `Window`\n\n\n*Since 1.2.0*\n\nSource: `test.lua:2`"));
+            assert!(page.contains("`title`: <code>string</code>\n\n*Since 1.3.0*\n\n"));
+            assert!(page.contains("</code></pre></div>\n\n*Since 2.0.0-beta*\n\n"));
+
+            let page = &pages[Path::new("enums/Layout.md")];
+            assert!(page.contains("*Since 0.9*"));
+        }
+
+        #[test]
+        fn inline_fields_note_the_version_after_their_description() {
+            let pages = render(&renderer().inline_descriptions(true), SOURCE);
+
+            assert!(pages[Path::new("classes/Window.md")]
+                .contains("- `title`: <code>string</code> *(Since 1.3.0)*\n"));
+        }
+
+        #[test]
+        fn symbols_without_since_have_no_note() {
+            let pages = render(&renderer(), "---@class Window\nlocal Window = {}\n");
+
+            assert!(!pages[Path::new("classes/Window.md")].contains("Since"));
+        }
+    }
+
+    mod determinism {
+        use super::*;
+
+        #[test]
+        fn rendering_the_same_source_twice_is_byte_identical() {
+            let source = r#"
+---@alias Mode "c" | "a"
+---| "b" # B
+
+---@enum Layout
+local Layout = { Tiled = "a", Floating = "b", Max = "c" }
+
+---@class Window
+---@field mode Mode
+---@field layout Layout
+---@since 1.0
+local Window = {}
+
+---@param mode "a" | "b" | "c" | "d" | "e"
+---@return Mode
+---@see Layout
+function Window.set(mode) end
+
+---@since 1.0
+---@return nil
+function open() end
+"#;
+            let renderer = || {
+                renderer()
+                    .link_literals(true)
+                    .symbols_manifest(true)
+                    .search_index(true)
+                    .version_matrix(true)
+            };
+
+            let first = render(&renderer(), source);
+            let second = render(&renderer(), source);
+
+            let mut paths = first.keys().collect::<Vec<_>>();
+            paths.sort();
+            for path in paths {
+                assert_eq!(first[path], second[path], "{} differs", path.display());
+            }
+            assert_eq!(first.len(), second.len());
+        }
+    }
+
+    mod errors {
+        use super::*;
+
+        #[test]
+        fn errors_get_their_own_section() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class FileError
+local FileError = {}
+
+---@class File
+local File = {}
+
+---@return string
+---@error FileError If the file doesn't exist.
+function File.read() end
+"#,
+            );
+
+            assert!(pages[&PathBuf::from("classes/File.md")].contains(
+                "#### Errors\n\n\
+                - <code><a href=\"/classes/FileError\">FileError</a></code> - If the file doesn't exist."
+            ));
+        }
+    }
+
+    mod diff {
+        use super::*;
+
+        #[test]
+        fn changed_symbols_show_up_in_the_diff() {
+            let dir = tempfile::tempdir().unwrap();
+
+            VitePressRenderer::new(dir.path().to_path_buf(), None).render(parse_str(
+                r#"
+---@class Window
+---@field title string
+local Window = {}
+"#,
+            ));
+
+            let renderer = VitePressRenderer::new(dir.path().to_path_buf(), None);
+            let source = r#"
+---@class Window
+---@field title string?
+local Window = {}
+"#;
+
+            let diff = renderer.diff(parse_str(source));
+            assert!(diff.contains("--- a/classes/Window.md\n+++ b/classes/Window.md\n"));
+            assert!(diff.contains("\n-### title\n"));
+            assert!(diff.contains(r#"+### title <Badge type="danger" text="nullable" />"#));
+
+            VitePressRenderer::new(dir.path().to_path_buf(), None).render(parse_str(source));
+            assert_eq!(renderer.diff(parse_str(source)), "");
+        }
+    }
+
+    mod manifest {
+        use super::*;
+
+        #[test]
+        fn only_generated_pages_are_removed() {
+            let dir = tempfile::tempdir().unwrap();
+            let out_dir = dir.path();
+
+            VitePressRenderer::new(out_dir.to_path_buf(), None).render(parse_str(
+                r#"
+---@class Window
+local Window = {}
+
+---@class Old
+local Old = {}
+"#,
+            ));
+            std::fs::write(out_dir.join("classes/custom.md"), "# Custom\n").unwrap();
+
+            let source = r#"
+---@class Window
+local Window = {}
+"#;
+            let renderer = VitePressRenderer::new(out_dir.to_path_buf(), None);
+            assert!(renderer
+                .diff(parse_str(source))
+                .contains("--- a/classes/Old.md\n+++ /dev/null\n"));
+            assert!(!renderer.diff(parse_str(source)).contains("custom.md"));
+
+            VitePressRenderer::new(out_dir.to_path_buf(), None).render(parse_str(source));
+
+            assert!(out_dir.join("classes/Window.md").exists());
+            assert!(!out_dir.join("classes/Old.md").exists());
+            assert_eq!(
+                std::fs::read_to_string(out_dir.join("classes/custom.md")).unwrap(),
+                "# Custom\n"
+            );
+            assert_eq!(
+                std::fs::read_to_string(out_dir.join(".lcat-manifest")).unwrap(),
+                "classes/Window.md\nsidebar.json\n"
+            );
+        }
+
+        #[test]
+        fn rerendering_unchanged_sources_writes_nothing() {
+            let dir = tempfile::tempdir().unwrap();
+            let out_dir = dir.path();
+            let source = r#"
+---@class Window
+local Window = {}
+
+---@class Tag
+local Tag = {}
+"#;
+
+            let first =
+                VitePressRenderer::new(out_dir.to_path_buf(), None).render(parse_str(source));
+            assert_eq!(first.unchanged, 0);
+
+            assert_eq!(
+                VitePressRenderer::new(out_dir.to_path_buf(), None).render(parse_str(source)),
+                WriteStats {
+                    changed: 0,
+                    unchanged: first.changed
+                }
+            );
+
+            let stats = VitePressRenderer::new(out_dir.to_path_buf(), None).render(parse_str(
+                &source.replace("---@class Tag", "---A tag.\n---@class Tag"),
+            ));
+            assert_eq!(stats.changed, 1);
+        }
+
+        #[test]
+        fn emptied_directories_are_removed() {
+            let dir = tempfile::tempdir().unwrap();
+            let out_dir = dir.path();
+
+            VitePressRenderer::new(out_dir.to_path_buf(), None).render(parse_str(
+                r#"
+---@enum State
+local State = { focused = 1 }
+"#,
+            ));
+            assert!(out_dir.join("enums/State.md").exists());
+
+            VitePressRenderer::new(out_dir.to_path_buf(), None).render(parse_str(""));
+
+            assert!(!out_dir.join("enums").exists());
+        }
+    }
+
+    mod inline_descriptions {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Window
+---@field title string The title
+---@field id integer
+---@default 0
+---The layout.
+---
+---Changes when the window is moved.
+---@field layout string
+local Window = {}
+
+---@param width integer The width
+function Window:resize(width) end
+"#;
+
+        #[test]
+        fn descriptions_are_blocks_by_default() {
+            let pages = render(&renderer(), SOURCE);
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains(
+                "### title\n\n`title`: <code>string</code>\n\nSource: `test.lua:3`\n\nThe title\n"
+            ));
+            assert!(page.contains("`width`: <code>integer</code> - The width"));
+        }
+
+        #[test]
+        fn one_line_descriptions_are_inlined() {
+            let pages = render(&renderer().inline_descriptions(true), SOURCE);
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains("- `title`: <code>string</code> — The title\n"));
+            assert!(!page.contains("### title"));
+            assert!(page.contains("- `id`: <code>integer</code> (Default: `0`)\n"));
+            assert!(page.contains("`width`: <code>integer</code> — The width"));
+        }
+
+        #[test]
+        fn multi_line_descriptions_still_expand() {
+            let pages = render(&renderer().inline_descriptions(true), SOURCE);
+            let page = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(page.contains(
+                "### layout\n\n`layout`: <code>string</code>\n\nSource: `test.lua:9`\n\nThe layout.\n\nChanges when the window is moved.\n"
+            ));
+        }
+    }
+
+    mod nullable {
+        use super::*;
+
+        use crate::annotation::parse_type_annotation;
+
+        #[test]
+        fn nullable_function_params_mark_the_param() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Timer
+local Timer = {}
+
+---@param cb? fun(): nil
+---@param on_error fun(): nil?
+function Timer.start(cb, on_error) end
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("classes/Timer.md")];
+            assert!(page.contains("function Timer.start(cb?: fun(): nil, on_error: fun(): nil?)"));
+            assert!(page.contains("`cb?`: <code>fun(): nil</code>"));
+            assert!(page.contains("`on_error`: <code>fun(): nil?</code>"));
+        }
+
+        #[test]
+        fn nullable_function_returns_are_parenthesized() {
+            let ty = parse_type_annotation("fun(): (fun(): nil)?")
+                .unwrap()
+                .remove(0);
+
+            assert_eq!(ty.to_string(), "fun(): (fun(): nil)?");
+            assert_eq!(
+                ty.format_with_links(&HashMap::new(), "/", LinkOptions::default()),
+                "fun(): (fun(): nil)?"
+            );
+        }
+    }
+
+    mod mixed_unions {
+        use super::*;
+
+        #[test]
+        fn unions_of_functions_and_tables_render_both_members() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Effect
+---@field run (fun(): nil) | { cleanup: fun(): nil }
+local Effect = {}
+"#,
+            );
+
+            assert!(pages[&PathBuf::from("classes/Effect.md")]
+                .contains("`run`: <code>(fun(): nil) | { cleanup: fun(): nil }</code>"));
+        }
+    }
+
+    mod operators {
+        use super::*;
+
+        #[test]
+        fn every_arity_of_an_operator_is_rendered() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Vector
+---@operator sub: Vector Negate the vector
+---@operator sub(Vector): Vector
+local Vector = {}
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("classes/Vector.md")];
+            assert!(page.contains(
+                r#"- <code>-vector → <a href="/classes/Vector">Vector</a></code> - Negate the vector"#
+            ));
+            assert!(page.contains(
+                r#"- <code>vector - <a href="/classes/Vector">Vector</a> → <a href="/classes/Vector">Vector</a></code>"#
+            ));
+        }
+
+        #[test]
+        fn operators_are_rendered_as_expressions() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Vec
+---@operator add(Vec): Vec
+---@operator mul(number): Vec
+---@operator div(number): Vec
+---@operator unm: Vec
+---@operator concat(string): string
+---@operator len: integer
+---@operator call(number): number
+---@operator index(integer): number
+---@operator lt(Vec): boolean
+---@operator close: nil
+local Vec = {}
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("classes/Vec.md")];
+            let vec = r#"<a href="/classes/Vec">Vec</a>"#;
+
+            for expected in [
+                format!("<code>vec + {vec} → {vec}</code>"),
+                format!("<code>vec * number → {vec}</code>"),
+                format!("<code>vec / number → {vec}</code>"),
+                format!("<code>-vec → {vec}</code>"),
+                "<code>vec .. string → string</code>".to_string(),
+                "<code>#vec → integer</code>".to_string(),
+                "<code>vec(number) → number</code>".to_string(),
+                "<code>vec[integer] → number</code>".to_string(),
+                format!("<code>vec &lt; {vec} → boolean</code>"),
+                "<code>close → nil</code>".to_string(),
+            ] {
+                assert!(page.contains(&expected), "missing `{expected}` in:\n{page}");
+            }
+        }
+    }
+
+    mod parents {
+        use super::*;
+
+        #[test]
+        fn generic_parents_link_their_type_arguments() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Handler
+local Handler = {}
+
+---@class Registry : table<string, Handler>
+local Registry = {}
+"#,
+            );
+
+            assert!(pages[&PathBuf::from("classes/Registry.md")].contains(
+                r#"# Class `Registry` : <code>table&lt;string, <a href="/classes/Handler">Handler</a>></code>"#
+            ));
+        }
+
+        #[test]
+        fn inherited_fields_link_to_their_parent() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Widget
+---@field width integer
+---@field height integer
+---@field private id integer
+
+---@class Container : Widget
+---@field children Widget[]
+
+---@class Window : Container
+---@field height number
+---@field title string
+"#,
+            );
+
+            let window = &pages[&PathBuf::from("classes/Window.md")];
+
+            assert!(window.contains(
+                "### Inherited from `Container`\n\n\
+                - <code><a href=\"/classes/Container\">children</a></code>: \
+                <code><a href=\"/classes/Widget\">Widget</a>[]</code>\n"
+            ));
+            assert!(window.contains(
+                "### Inherited from `Widget`\n\n\
+                - <code><a href=\"/classes/Widget\">width</a></code>: <code>integer</code>\n"
+            ));
+            assert!(!window.contains(">id<"));
+        }
+
+        #[test]
+        fn classes_with_only_inherited_fields_have_a_fields_section() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Widget
+---@field width integer
+
+---@class Button : Widget
+"#,
+            );
+
+            assert!(pages[&PathBuf::from("classes/Button.md")]
+                .contains("## Fields\n\n### Inherited from `Widget`"));
+        }
+
+        #[test]
+        fn inheritance_cycles_terminate() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class A : B
+---@field a integer
+
+---@class B : A
+---@field b integer
+"#,
+            );
+
+            let a = &pages[&PathBuf::from("classes/A.md")];
+
+            assert!(a.contains("### Inherited from `B`"));
+            assert!(!a.contains("### Inherited from `A`"));
+        }
+    }
+
+    mod function_pages_mode {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Window
+local Window = {}
+
+---Resize the window.
+---@param width integer
+function Window:resize(width) end
+
+---Close the window.
+---@see Window.resize
+function Window.close() end
+"#;
+
+        #[test]
+        fn functions_are_inlined_by_default() {
+            let pages = render(&renderer(), SOURCE);
+
+            // The class page and the sidebar
+            assert_eq!(pages.len(), 2);
+            assert!(pages[&PathBuf::from("classes/Window.md")].contains("### "));
+        }
+
+        #[test]
+        fn functions_get_their_own_pages() {
+            let pages = render(&renderer().function_pages(true), SOURCE);
+
+            let class_page = &pages[&PathBuf::from("classes/Window.md")];
+            assert!(class_page
+                .contains(r#"- <code><a href="/classes/Window/resize">Window:resize</a></code>"#));
+            assert!(class_page
+                .contains(r#"- <code><a href="/classes/Window/close">Window.close</a></code>"#));
+            assert!(!class_page.contains("Resize the window."));
+
+            let resize_page = &pages[&PathBuf::from("classes/Window/resize.md")];
+            assert!(resize_page.contains("# <Badge type=\"method\" text=\"method\" /> resize"));
+            assert!(resize_page.contains("## Parameters"));
+
+            let close_page = &pages[&PathBuf::from("classes/Window/close.md")];
+            assert!(close_page
+                .contains(r#"- <code><a href="/classes/Window/resize">Window.resize</a></code>"#));
+        }
+    }
+
+    mod alias_layout {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Window
+local Window = {}
+
+---How to focus things
+---@alias FocusMode
+---| "click" # Focus on click
+---Focus when the mouse hovers,
+---like in X11
+---| "hover"
+---| Window # Focus a specific window
+---| fun(): boolean|nil # Decide dynamically
+"#;
+
+        #[test]
+        fn sections_layout_uses_headings() {
+            let pages = render(&renderer(), SOURCE);
+
+            let page = &pages[&PathBuf::from("aliases/FocusMode.md")];
+            assert!(page.contains("### <code>\"click\"</code>\n\nFocus on click"));
+            assert!(!page.contains("| Variant | Description |"));
+        }
+
+        #[test]
+        fn table_layout_renders_rows() {
+            let pages = render(&renderer().alias_layout(AliasLayout::Table), SOURCE);
+
+            let page = &pages[&PathBuf::from("aliases/FocusMode.md")];
+            assert!(page.contains("| Variant | Description |\n| --- | --- |\n"));
+            assert!(page.contains("| <code>\"click\"</code> | Focus on click |"));
+            assert!(page.contains(
+                "| <code>\"hover\"</code> | Focus when the mouse hovers,<br>like in X11 |"
+            ));
+            assert!(page.contains(
+                r#"| <code><a href="/classes/Window">Window</a></code> | Focus a specific window |"#
+            ));
+            assert!(page.contains(r"| <code>fun(): boolean \| nil</code> | Decide dynamically |"));
+            assert!(!page.contains("### <code>"));
+        }
+    }
+
+    mod sources {
+        use super::*;
+
+        const SOURCE: &str = r#"
+---@class Foo
+local Foo = {}
+
+---Add two numbers
+---@param a integer
+---@param b integer
+function Foo.add(a, b)
+    return a + b
+end
+"#;
+
+        #[test]
+        fn sources_are_emitted() {
+            let pages = render(&renderer().emit_sources(true), SOURCE);
+
+            let page = &pages[&PathBuf::from("classes/Foo.md")];
+            assert!(page.contains("<summary>Source</summary>"));
+            assert!(page.contains("```lua\nfunction Foo.add(a, b)\n    return a + b\nend\n```"));
+        }
+
+        #[test]
+        fn sources_are_not_emitted_by_default() {
+            let pages = render(&renderer(), SOURCE);
+
+            let page = &pages[&PathBuf::from("classes/Foo.md")];
+            assert!(!page.contains("return a + b"));
+        }
+
+        #[test]
+        fn fences_are_longer_than_backtick_runs_in_the_source() {
+            let pages = render(
+                &renderer().emit_sources(true),
+                r#"
+---Print a fenced block
+function fenced()
+    print("```lua\n````\n")
+end
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("functions.md")];
+            assert!(page.contains(
+                "\n`````lua\nfunction fenced()\n    print(\"```lua\\n````\\n\")\nend\n`````\n"
+            ));
+        }
+    }
+
+    mod version_matrix {
+        use super::*;
+
+        #[test]
+        fn versions_compare_numerically() {
+            let mut versions = ["1.10.0", "v1.2.0", "1.2.0-beta", "0.9", "1.2.0-alpha.2"];
+            versions.sort_by(|a, b| compare_versions(a, b));
+            assert_eq!(
+                versions,
+                ["0.9", "1.2.0-alpha.2", "1.2.0-beta", "v1.2.0", "1.10.0"]
+            );
+        }
+
+        #[test]
+        fn symbols_are_grouped_by_version() {
+            let pages = render(
+                &renderer().version_matrix(true),
+                r#"
+---@class Window
+---@since 0.9.0
+---@field id integer
+---@since 1.10.0
+---@field title string
+local Window = {}
+
+---Close the window
+---@since 1.2.0
+function Window:close() end
+
+---Open a window
+---@since 1.10.0
+function open() end
+
+---@alias Layout "tiled" | "floating"
+---@since 1.2.0
+
+---Not versioned
+function untracked() end
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("versions.md")];
+
+            let v1_10 = page.find("## 1.10.0").unwrap();
+            let v1_2 = page.find("## 1.2.0").unwrap();
+            let v0_9 = page.find("## 0.9.0").unwrap();
+            assert!(v1_10 < v1_2 && v1_2 < v0_9);
+
+            let section = |start: usize, end: usize| &page[start..end];
+
+            let newest = section(v1_10, v1_2);
+            assert!(newest
+                .contains(r#"- Field <code><a href="/classes/Window#id">Window.id</a></code>"#));
+            assert!(
+                newest.contains(r#"- Function <code><a href="/functions#open">open</a></code>"#)
+            );
+
+            let middle = section(v1_2, v0_9);
+            assert!(middle.contains(
+                r#"- Function <code><a href="/classes/Window#close">Window:close</a></code>"#
+            ));
+            assert!(middle.contains(r#"- Alias <code><a href="/aliases/Layout">Layout</a></code>"#));
+
+            let oldest = section(v0_9, page.len());
+            assert!(oldest.contains(r#"- Class <code><a href="/classes/Window">Window</a></code>"#));
+            assert!(!page.contains("Window.title"));
+
+            assert!(!page.contains("untracked"));
+        }
+
+        #[test]
+        fn a_leading_v_is_grouped_with_the_bare_version() {
+            let pages = render(
+                &renderer().version_matrix(true),
+                r#"
+---@since v1.10.0
+function open() end
+
+---@since 1.10.0
+function close() end
+
+---@since V1.2.0
+function move() end
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("versions.md")];
+
+            assert_eq!(page.matches("## 1.10.0\n").count(), 1);
+            assert!(!page.contains("## v"));
+            assert!(!page.contains("## V"));
+            assert!(page.find("## 1.10.0").unwrap() < page.find("## 1.2.0").unwrap());
+
+            let newest = &page[page.find("## 1.10.0").unwrap()..page.find("## 1.2.0").unwrap()];
+            assert!(newest.contains(">open</a>") && newest.contains(">close</a>"));
+        }
+    }
+
+    mod descriptions {
+        use super::*;
+
+        #[test]
+        fn code_blocks_in_class_descriptions_are_preserved() {
+            let pages = render(
+                &renderer(),
+                r#"
+---A registry of handlers, stored as a table<string, Handler>.
+---
+---```lua
+---local registry = Registry.new()
+---
+---if #registry < 2 then
+---    ---@type table<K, V>
+---    local map = registry:get()
+---end
+---```
+---
+---Use `table<K, V>` inline too.
+---@class Registry
+local Registry = {}
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("classes/Registry.md")];
+            assert!(page.contains(
+                "```lua
+local registry = Registry.new()
+
+if #registry < 2 then
+    ---@type table<K, V>
+    local map = registry:get()
+end
+```"
+            ));
+            assert!(page.contains("Use `table<K, V>` inline too."));
+            assert!(page.contains("stored as a table&lt;string, Handler>."));
+        }
+    }
+
+    mod values {
+        use super::*;
+
+        #[test]
+        fn short_values_are_not_elided() {
+            let pages = render(
+                &renderer(),
+                r#"
+---@class Config
+local Config = {
+    ---The gaps
+    gaps = { 1, 2, 3 },
+}
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("classes/Config.md")];
+            assert!(page.contains("= `{ 1, 2, 3 }`"));
+        }
+
+        #[test]
+        fn long_values_are_elided() {
+            let pages = render(
+                &renderer().max_value_len(10),
+                r#"
+---@class Config
+local Config = {
+    ---The gaps
+    gaps = { 1, 2, 3, 4, 5, 6, 7, 8, 9, 10 },
+    ---The name
+    name = "a very long name",
+}
+
+---@enum Mode
+local Mode = {
+    Tiled = "tiled tiled tiled",
+}
+"#,
+            );
+
+            let page = &pages[&PathBuf::from("classes/Config.md")];
+            assert!(page.contains("= `{ ... }`"));
+            assert!(!page.contains("{ 1, 2, 3"));
+            assert!(page.contains(r#"= `"a very lo ...`"#));
+            assert!(page.contains("see the source"));
+
+            let page = &pages[&PathBuf::from("enums/Mode.md")];
+            assert!(page.contains(r#"`Mode.Tiled` = `"tiled til ...`"#));
+        }
+    }
 }
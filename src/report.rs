@@ -0,0 +1,566 @@
+use std::{path::PathBuf, time::Duration};
+
+use serde::Serialize;
+
+use crate::{
+    processor::{ProcessErrorKind, Processor},
+    render::WriteStats,
+    state::FileStats,
+    transform::is_public,
+};
+
+/// The formats a run report can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    /// A `report.json` file in the output directory.
+    Json,
+}
+
+/// A machine-readable summary of a run, for dashboards and CI checks.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub counts: Counts,
+    /// The same numbers as `--coverage-json`.
+    pub coverage: CoverageByKind,
+    pub timings: Timings,
+    pub files: Vec<FileReport>,
+    /// Problems with the documentation, like annotations that failed to parse
+    /// or parameters lacking types.
+    pub diagnostics: Vec<String>,
+}
+
+/// How many of each symbol were documented, after pruning and transforms.
+#[derive(Debug, Serialize)]
+pub struct Counts {
+    pub classes: usize,
+    pub aliases: usize,
+    pub enums: usize,
+    pub functions: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Timings {
+    pub parse_ms: f64,
+    pub render_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub classes: Vec<String>,
+    pub aliases: Vec<String>,
+    pub enums: Vec<String>,
+    pub functions: Vec<String>,
+    pub parse_ms: f64,
+}
+
+impl Report {
+    /// Build a report of everything up to rendering.
+    ///
+    /// The render time is set with [`Report::set_render_time`] once rendering is done.
+    pub fn new(processor: &Processor, files: &[FileStats]) -> Self {
+        Self {
+            counts: Counts {
+                classes: processor.classes.len(),
+                aliases: processor.aliases.len(),
+                enums: processor.enums.len(),
+                functions: processor.functions.len(),
+            },
+            coverage: coverage_by_kind(processor, files),
+            timings: Timings {
+                parse_ms: files.iter().map(|file| millis(file.parse_time)).sum(),
+                render_ms: 0.0,
+            },
+            files: files
+                .iter()
+                .map(|file| FileReport {
+                    path: file.path.clone(),
+                    classes: file.classes.clone(),
+                    aliases: file.aliases.clone(),
+                    enums: file.enums.clone(),
+                    functions: file.functions.clone(),
+                    parse_ms: millis(file.parse_time),
+                })
+                .collect(),
+            diagnostics: files
+                .iter()
+                .flat_map(|file| {
+                    file.errors
+                        .iter()
+                        .map(|error| format!("{error}: {}", error.message))
+                })
+                .chain(processor.check_strict_types())
+                .collect(),
+        }
+    }
+
+    pub fn set_render_time(&mut self, render_time: Duration) {
+        self.timings.render_ms = millis(render_time);
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap() + "\n"
+    }
+}
+
+/// How many public symbols of one kind have non-empty descriptions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct KindCoverage {
+    pub documented: usize,
+    pub total: usize,
+    /// The percentage of symbols with descriptions, which is 100 with no symbols.
+    pub percent: f64,
+}
+
+impl KindCoverage {
+    /// Count a symbol with `description`, returning whether it's documented.
+    fn count(&mut self, description: Option<&str>) -> bool {
+        let documented = description.is_some_and(|description| !description.trim().is_empty());
+
+        self.total += 1;
+        self.documented += usize::from(documented);
+        documented
+    }
+
+    fn update_percent(&mut self) {
+        self.percent = if self.total == 0 {
+            100.0
+        } else {
+            self.documented as f64 / self.total as f64 * 100.0
+        };
+    }
+}
+
+impl std::fmt::Display for KindCoverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.1}% ({}/{})",
+            self.percent, self.documented, self.total
+        )
+    }
+}
+
+/// Description coverage of the public symbols, broken down by kind.
+#[derive(Debug, Serialize)]
+pub struct CoverageByKind {
+    pub total: KindCoverage,
+    pub classes: KindCoverage,
+    pub functions: KindCoverage,
+    pub fields: KindCoverage,
+    pub aliases: KindCoverage,
+    pub enums: KindCoverage,
+    /// The names of undocumented symbols, with the location for functions.
+    #[serde(skip)]
+    pub undocumented: Vec<String>,
+}
+
+impl CoverageByKind {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap() + "\n"
+    }
+}
+
+impl std::fmt::Display for CoverageByKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "documentation coverage: {}", self.total)?;
+        writeln!(f, "  classes:   {}", self.classes)?;
+        writeln!(f, "  functions: {}", self.functions)?;
+        writeln!(f, "  fields:    {}", self.fields)?;
+        writeln!(f, "  aliases:   {}", self.aliases)?;
+        write!(f, "  enums:     {}", self.enums)
+    }
+}
+
+/// Count the public classes, functions, fields, aliases, and enums that have descriptions.
+///
+/// This is the one coverage metric behind `--coverage`, `--min-coverage`, and the run report.
+/// Fields and functions are public by [`is_public`] and
+/// [`is_public_function`](crate::transform::is_public_function), and functions
+/// are every public function tree-sitter found, so ones without any doc comment count too.
+pub fn coverage_by_kind(processor: &Processor, files: &[FileStats]) -> CoverageByKind {
+    let mut undocumented = Vec::new();
+
+    let mut classes = KindCoverage::default();
+    for class in processor.classes.iter() {
+        if !classes.count(class.description.as_deref()) {
+            undocumented.push(class.name.clone());
+        }
+    }
+
+    // Functions are matched to their documentation by file and line, as documented
+    // functions may be renamed to the class their table belongs to
+    let mut functions = KindCoverage::default();
+    for file in files.iter() {
+        for function in file.public_functions.iter() {
+            let description = processor
+                .functions
+                .iter()
+                .find(|func| func.location.file == file.path && func.location.line == function.line)
+                .and_then(|func| func.description.as_deref());
+
+            if !functions.count(description) {
+                undocumented.push(format!(
+                    "{} ({}:{})",
+                    function.name,
+                    file.path.display(),
+                    function.line
+                ));
+            }
+        }
+    }
+
+    let mut fields = KindCoverage::default();
+    for class in processor.classes.iter() {
+        for field in class.fields() {
+            if is_public(field.scope) && !fields.count(field.description.as_deref()) {
+                let name = field.ident_type.format_as_table_field_name();
+                let separator = if name.starts_with('[') { "" } else { "." };
+                undocumented.push(format!("{}{separator}{name}", class.name));
+            }
+        }
+    }
+
+    let mut aliases = KindCoverage::default();
+    for alias in processor.aliases.iter() {
+        if !aliases.count(alias.description.as_deref()) {
+            undocumented.push(alias.name.clone());
+        }
+    }
+
+    let mut enums = KindCoverage::default();
+    for r#enum in processor.enums.iter() {
+        if !enums.count(r#enum.description.as_deref()) {
+            undocumented.push(r#enum.name.clone());
+        }
+    }
+
+    let mut kinds = [classes, functions, fields, aliases, enums];
+    kinds.iter_mut().for_each(KindCoverage::update_percent);
+
+    let mut total = KindCoverage {
+        documented: kinds.iter().map(|kind| kind.documented).sum(),
+        total: kinds.iter().map(|kind| kind.total).sum(),
+        percent: 0.0,
+    };
+    total.update_percent();
+
+    let [classes, functions, fields, aliases, enums] = kinds;
+
+    CoverageByKind {
+        total,
+        classes,
+        functions,
+        fields,
+        aliases,
+        enums,
+        undocumented,
+    }
+}
+
+/// A one-line summary of a run for the terminal, like
+/// `parsed 2 files: 1 class, 3 functions, 0 aliases, 1 enum`.
+///
+/// Annotations that were skipped because they failed to parse or are unknown are counted too.
+pub fn summary(processor: &Processor, files: &[FileStats]) -> String {
+    let mut summary = format!(
+        "parsed {}: {}, {}, {}, {}",
+        plural(files.len(), "file", "files"),
+        plural(processor.classes.len(), "class", "classes"),
+        plural(processor.functions.len(), "function", "functions"),
+        plural(processor.aliases.len(), "alias", "aliases"),
+        plural(processor.enums.len(), "enum", "enums"),
+    );
+
+    let errors = files.iter().flat_map(|file| file.errors.iter());
+    let syntax_errors = errors
+        .clone()
+        .filter(|error| error.kind == ProcessErrorKind::SyntaxError)
+        .count();
+    let skipped = errors.count() - syntax_errors;
+
+    if skipped > 0 {
+        summary.push_str(&format!(
+            ", skipped {}",
+            plural(skipped, "annotation", "annotations")
+        ));
+    }
+
+    if syntax_errors > 0 {
+        summary.push_str(&format!(
+            ", found {}",
+            plural(syntax_errors, "syntax error", "syntax errors")
+        ));
+    }
+
+    summary
+}
+
+/// Summarize how many files a render wrote and how many were already up to date.
+pub fn written(stats: WriteStats) -> String {
+    format!(
+        "wrote {}, {} unchanged",
+        plural(stats.changed, "changed file", "changed files"),
+        stats.unchanged
+    )
+}
+
+fn plural(count: usize, singular: &str, plural: &str) -> String {
+    format!("{count} {}", if count == 1 { singular } else { plural })
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::state::parse_files;
+
+    mod counts {
+        use super::*;
+
+        const FIXTURE: &str = r#"
+---@class Window
+local Window = {}
+
+---@alias Direction "left" | "right"
+
+---@enum State
+local State = {
+    focused = 1,
+}
+
+---Close the window.
+function Window.close() end
+
+function Window.undocumented() end
+
+local function helper() end
+
+Window.on_close(function() end)
+"#;
+
+        #[test]
+        fn counts_match_the_fixture() {
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("window.lua");
+            std::fs::write(&file, FIXTURE).unwrap();
+
+            let (processor, files) = parse_files(vec![file.clone()], None).unwrap();
+            let report = Report::new(&processor, &files);
+
+            assert_eq!(report.counts.classes, 1);
+            assert_eq!(report.counts.aliases, 1);
+            assert_eq!(report.counts.enums, 1);
+            assert_eq!(report.counts.functions, 1);
+
+            // Neither the local function nor the callback passed to `on_close` is counted
+            assert_eq!(report.coverage.functions.documented, 1);
+            assert_eq!(report.coverage.functions.total, 2);
+
+            assert_eq!(report.files[0].path, file);
+            assert_eq!(report.files[0].classes, ["Window"]);
+            assert_eq!(report.files[0].functions, ["Window.close"]);
+
+            let json = serde_json::from_str::<serde_json::Value>(&report.to_json()).unwrap();
+            assert_eq!(json["counts"]["functions"], 1);
+            assert_eq!(json["coverage"]["functions"]["total"], 2);
+        }
+
+        #[test]
+        fn summary_counts_symbols_and_skipped_annotations() {
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("window.lua");
+            std::fs::write(
+                &file,
+                format!("{FIXTURE}\n---@parm x integer\nfunction f(x) end\n"),
+            )
+            .unwrap();
+
+            let (processor, files) = parse_files(vec![file], None).unwrap();
+
+            assert_eq!(
+                summary(&processor, &files),
+                "parsed 1 file: 1 class, 2 functions, 1 alias, 1 enum, skipped 1 annotation"
+            );
+        }
+
+        #[test]
+        fn written_counts_changed_and_unchanged_files() {
+            assert_eq!(
+                written(WriteStats {
+                    changed: 1,
+                    unchanged: 12
+                }),
+                "wrote 1 changed file, 12 unchanged"
+            );
+        }
+    }
+
+    mod coverage_by_kind {
+        use super::*;
+
+        fn coverage(source: &str) -> CoverageByKind {
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("window.lua");
+            std::fs::write(&file, source).unwrap();
+
+            let (processor, files) = parse_files(vec![file], None).unwrap();
+            coverage_by_kind(&processor, &files)
+        }
+
+        const SOURCE: &str = r#"
+---A window
+---@class Window
+---@field id integer
+---@field private handle integer
+---@field protected parent Window
+local Window = {
+    ---The title
+    title = "",
+}
+
+---
+---@alias Direction "left" | "right"
+
+---@enum State
+local State = {
+    focused = 1,
+}
+
+---Close the window.
+function Window.close() end
+
+function Window.undocumented() end
+
+function _internal() end
+"#;
+
+        #[test]
+        fn symbols_are_counted_by_kind() {
+            let coverage = coverage(SOURCE);
+
+            assert_eq!(
+                (coverage.classes.documented, coverage.classes.total),
+                (1, 1)
+            );
+            assert_eq!(
+                (coverage.functions.documented, coverage.functions.total),
+                (1, 2)
+            );
+            assert_eq!((coverage.fields.documented, coverage.fields.total), (1, 2));
+            assert_eq!(
+                (coverage.aliases.documented, coverage.aliases.total),
+                (0, 1)
+            );
+            assert_eq!((coverage.enums.documented, coverage.enums.total), (0, 1));
+            assert_eq!((coverage.total.documented, coverage.total.total), (3, 7));
+
+            assert_eq!(coverage.undocumented.len(), 4);
+            assert!(coverage.undocumented[0].starts_with("Window.undocumented ("));
+            assert!(coverage.undocumented[0].ends_with("window.lua:23)"));
+            assert_eq!(
+                coverage.undocumented[1..],
+                ["Window.id", "Direction", "State"]
+            );
+        }
+
+        #[test]
+        fn coverage_prints_as_text_and_json() {
+            let coverage = coverage(SOURCE);
+
+            assert_eq!(
+                coverage.to_string(),
+                "documentation coverage: 42.9% (3/7)\n  classes:   100.0% (1/1)\n  functions: 50.0% (1/2)\n  fields:    50.0% (1/2)\n  aliases:   0.0% (0/1)\n  enums:     0.0% (0/1)"
+            );
+
+            let json: serde_json::Value = serde_json::from_str(&coverage.to_json()).unwrap();
+            assert_eq!(json["functions"]["documented"], 1);
+            assert_eq!(json["functions"]["total"], 2);
+            assert_eq!(json["enums"]["percent"], 0.0);
+        }
+
+        #[test]
+        fn private_and_local_functions_are_ignored() {
+            let coverage = coverage(
+                r#"
+---A window.
+---@class Window
+local Window = {}
+
+---Close the window.
+function Window:close() end
+
+function Window._private() end
+
+---@private
+function Window.internal() end
+
+local function helper() end
+
+local focus = function() end
+"#,
+            );
+
+            assert_eq!(coverage.functions.total, 1);
+            assert!(coverage.undocumented.is_empty());
+        }
+
+        #[test]
+        fn coverage_just_below_and_above_a_threshold() {
+            // 3 of 4 documented
+            let below = coverage(
+                r#"
+---A window.
+---@class Window
+local Window = {}
+
+---Close the window.
+function Window.close() end
+
+---Focus the window.
+function Window.focus() end
+
+function Window.undocumented() end
+"#,
+            );
+
+            assert_eq!(below.total.total, 4);
+            assert!(below.total.percent < 76.0);
+
+            // 4 of 5 documented
+            let above = coverage(
+                r#"
+---A window.
+---@class Window
+local Window = {}
+
+---Close the window.
+function Window.close() end
+
+---Focus the window.
+function Window.focus() end
+
+---Hide the window.
+Window.hide = function() end
+
+function Window.undocumented() end
+"#,
+            );
+
+            assert_eq!(above.total.total, 5);
+            assert!(above.total.percent >= 76.0);
+        }
+
+        #[test]
+        fn no_symbols_is_full_coverage() {
+            let coverage = coverage("");
+
+            assert_eq!(coverage.total.percent, 100.0);
+            assert_eq!(coverage.functions.percent, 100.0);
+        }
+    }
+}
@@ -0,0 +1,402 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    annotation::Class,
+    processor::Processor,
+    transform::is_public,
+    types::{unquote, Literal, Type, TypeInner},
+};
+
+/// Generate a JSON Schema for validating values of the given class, like a user's config file.
+///
+/// Classes the class refers to are put in `$defs`, while aliases and enums are inlined.
+/// Nullable and defaulted fields are optional and everything else is required. Nullable
+/// types also accept `null`. Types without a JSON equivalent, like functions, accept
+/// anything and are never required.
+///
+/// Returns `None` if there is no class with the given name.
+pub fn json_schema(processor: &Processor, class: &str) -> Option<Value> {
+    let class = processor.classes.iter().find(|cls| cls.name == class)?;
+
+    let mut generator = SchemaGenerator {
+        processor,
+        root: &class.name,
+        defs: BTreeMap::new(),
+        expanding_aliases: HashSet::new(),
+    };
+
+    let mut schema = generator.class_schema(class);
+    generator.defs.remove(&class.name);
+
+    let object = schema.as_object_mut().unwrap();
+    object.insert(
+        "$schema".to_string(),
+        json!("https://json-schema.org/draft/2020-12/schema"),
+    );
+    object.insert("title".to_string(), json!(class.name));
+
+    if !generator.defs.is_empty() {
+        object.insert(
+            "$defs".to_string(),
+            Value::Object(generator.defs.into_iter().collect()),
+        );
+    }
+
+    Some(schema)
+}
+
+struct SchemaGenerator<'a> {
+    processor: &'a Processor,
+    /// The class the schema is for, which references point to with `#` instead of `$defs`.
+    root: &'a str,
+    /// The schemas of referenced classes. A class maps to `Null` while it's being generated
+    /// so that self-referential classes don't recurse forever.
+    defs: BTreeMap<String, Value>,
+    expanding_aliases: HashSet<String>,
+}
+
+impl<'a> SchemaGenerator<'a> {
+    fn class_schema(&mut self, class: &'a Class) -> Value {
+        self.defs.insert(class.name.clone(), Value::Null);
+
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        let mut additional_properties = None;
+
+        for cls in std::iter::once(class).chain(self.processor.class_parents(class)) {
+            for field in cls.fields() {
+                if !is_public(field.scope) {
+                    continue;
+                }
+
+                let ty = field.ty.clone().unwrap_or(Type::ANY);
+                let mut schema = self.type_schema(&ty);
+
+                if let Some(desc) = field.description.as_ref() {
+                    if let Some(object) = schema.as_object_mut() {
+                        object.insert("description".to_string(), json!(desc));
+                    }
+                }
+
+                match &field.ident_type.inner {
+                    TypeInner::Literal(Literal::String(name)) => {
+                        let name = unquote(name);
+
+                        if properties.contains_key(name) {
+                            continue;
+                        }
+
+                        if !ty.nullable && field.default.is_none() && has_json_equivalent(&ty) {
+                            required.push(json!(name));
+                        }
+
+                        properties.insert(name.to_string(), schema);
+                    }
+                    _ => {
+                        additional_properties.get_or_insert(schema);
+                    }
+                }
+            }
+        }
+
+        let mut schema = Map::new();
+        schema.insert("type".to_string(), json!("object"));
+
+        if let Some(desc) = class.description.as_ref() {
+            schema.insert("description".to_string(), json!(desc));
+        }
+
+        schema.insert("properties".to_string(), Value::Object(properties));
+
+        if !required.is_empty() {
+            schema.insert("required".to_string(), Value::Array(required));
+        }
+
+        match additional_properties {
+            Some(additional_properties) => {
+                schema.insert("additionalProperties".to_string(), additional_properties);
+            }
+            None if class.exact => {
+                schema.insert("additionalProperties".to_string(), json!(false));
+            }
+            None => (),
+        }
+
+        let schema = Value::Object(schema);
+        self.defs.insert(class.name.clone(), schema.clone());
+        schema
+    }
+
+    fn type_schema(&mut self, ty: &Type) -> Value {
+        let schema = match &ty.inner {
+            TypeInner::Boolean => json!({ "type": "boolean" }),
+            TypeInner::String => json!({ "type": "string" }),
+            TypeInner::Number => json!({ "type": "number" }),
+            TypeInner::Integer => json!({ "type": "integer" }),
+            TypeInner::Nil => json!({ "type": "null" }),
+            TypeInner::Literal(Literal::Boolean(boolean)) => json!({ "const": boolean }),
+            TypeInner::Literal(Literal::String(string)) => json!({ "const": unquote(string) }),
+            TypeInner::Literal(Literal::Number(number)) => json!({ "const": number }),
+            TypeInner::Literal(Literal::Integer(integer)) => json!({ "const": integer }),
+            TypeInner::Array(ty) => json!({ "type": "array", "items": self.type_schema(ty) }),
+            TypeInner::Tuple(types) => {
+                let items = types
+                    .iter()
+                    .map(|ty| self.type_schema(ty))
+                    .collect::<Vec<_>>();
+                json!({ "type": "array", "prefixItems": items, "items": false })
+            }
+            TypeInner::Table => match ty.generics.as_slice() {
+                [key, value] if matches!(key.inner, TypeInner::Integer) => {
+                    json!({ "type": "array", "items": self.type_schema(value) })
+                }
+                [_, value] => {
+                    json!({ "type": "object", "additionalProperties": self.type_schema(value) })
+                }
+                _ => json!({ "type": ["object", "array"] }),
+            },
+            TypeInner::TableDef(table) => {
+                let mut properties = Map::new();
+
+                for (key, value) in table.fields.iter() {
+                    if let TypeInner::Literal(Literal::String(name)) = &key.inner {
+                        properties.insert(unquote(name).to_string(), self.type_schema(value));
+                    }
+                }
+
+                json!({ "type": "object", "properties": properties })
+            }
+            TypeInner::Union(types) => {
+                let any_of = types
+                    .iter()
+                    .map(|ty| self.type_schema(ty))
+                    .collect::<Vec<_>>();
+                json!({ "anyOf": any_of })
+            }
+            TypeInner::Intersection(types) => {
+                let all_of = types
+                    .iter()
+                    .map(|ty| self.type_schema(ty))
+                    .collect::<Vec<_>>();
+                json!({ "allOf": all_of })
+            }
+            TypeInner::UserDefined(name) => self.user_defined_schema(name),
+            TypeInner::Any
+            | TypeInner::Function { .. }
+            | TypeInner::Thread
+            | TypeInner::Userdata
+            | TypeInner::LightUserdata
+            | TypeInner::Generic(_) => json!({}),
+        };
+
+        // A schema that accepts anything already accepts `null`
+        if ty.nullable && schema != json!({}) {
+            json!({ "anyOf": [schema, { "type": "null" }] })
+        } else {
+            schema
+        }
+    }
+
+    fn user_defined_schema(&mut self, name: &str) -> Value {
+        let processor = self.processor;
+
+        if name == self.root {
+            return json!({ "$ref": "#" });
+        }
+
+        if let Some(class) = processor.classes.iter().find(|cls| cls.name == name) {
+            if !self.defs.contains_key(name) {
+                self.class_schema(class);
+            }
+
+            return json!({ "$ref": format!("#/$defs/{name}") });
+        }
+
+        if let Some(alias) = processor.aliases.iter().find(|alias| alias.name == name) {
+            if !self.expanding_aliases.insert(name.to_string()) {
+                return json!({});
+            }
+
+            let any_of = alias
+                .types
+                .iter()
+                .map(|(ty, _)| self.type_schema(ty))
+                .collect::<Vec<_>>();
+
+            self.expanding_aliases.remove(name);
+
+            return match <[Value; 1]>::try_from(any_of) {
+                Ok([schema]) => schema,
+                Err(any_of) => json!({ "anyOf": any_of }),
+            };
+        }
+
+        if let Some(r#enum) = processor.enums.iter().find(|en| en.name == name) {
+            let values = r#enum
+                .fields
+                .iter()
+                .filter_map(|field| {
+                    if r#enum.is_key {
+                        field.name.as_ref().map(|name| json!(name.to_string()))
+                    } else {
+                        serde_json::from_str::<Value>(&field.value).ok()
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            if values.is_empty() {
+                return json!({});
+            }
+
+            return json!({ "enum": values });
+        }
+
+        json!({})
+    }
+}
+
+/// Whether a config can hold a value of the type, which isn't true of functions and
+/// other runtime-only values.
+fn has_json_equivalent(ty: &Type) -> bool {
+    !matches!(
+        ty.inner,
+        TypeInner::Function { .. }
+            | TypeInner::Thread
+            | TypeInner::Userdata
+            | TypeInner::LightUserdata
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::state::parse_str;
+
+    mod json_schema {
+        use super::*;
+
+        #[test]
+        fn config_classes_map_to_object_schemas() {
+            let processor = parse_str(
+                r#"
+---@class WindowRule
+---@field floating boolean
+
+---Configuration for the compositor.
+---@class Config
+---@field name string The name of the output.
+---@field scale number?
+---@field tags string[]
+---@field rules WindowRule[]
+---@field mode "fullscreen" | "windowed"
+"#,
+            );
+
+            let schema = json_schema(&processor, "Config").unwrap();
+
+            assert_eq!(
+                schema,
+                json!({
+                    "$schema": "https://json-schema.org/draft/2020-12/schema",
+                    "title": "Config",
+                    "type": "object",
+                    "description": "Configuration for the compositor.",
+                    "properties": {
+                        "name": { "type": "string", "description": "The name of the output." },
+                        "scale": { "anyOf": [{ "type": "number" }, { "type": "null" }] },
+                        "tags": { "type": "array", "items": { "type": "string" } },
+                        "rules": { "type": "array", "items": { "$ref": "#/$defs/WindowRule" } },
+                        "mode": { "anyOf": [{ "const": "fullscreen" }, { "const": "windowed" }] },
+                    },
+                    "required": ["name", "tags", "rules", "mode"],
+                    "$defs": {
+                        "WindowRule": {
+                            "type": "object",
+                            "properties": { "floating": { "type": "boolean" } },
+                            "required": ["floating"],
+                        },
+                    },
+                })
+            );
+        }
+
+        #[test]
+        fn references_to_the_root_class_point_at_the_root() {
+            let processor = parse_str(
+                r#"
+---@class Edge
+---@field to Node
+
+---@class Node
+---@field children Node[]
+---@field edges Edge[]
+"#,
+            );
+
+            let schema = json_schema(&processor, "Node").unwrap();
+
+            assert_eq!(
+                schema["properties"]["children"],
+                json!({ "type": "array", "items": { "$ref": "#" } })
+            );
+            assert_eq!(
+                schema["$defs"]["Edge"]["properties"]["to"],
+                json!({ "$ref": "#" })
+            );
+            assert!(schema["$defs"].get("Node").is_none());
+        }
+
+        #[test]
+        fn nullable_fields_accept_null() {
+            let processor = parse_str(
+                r#"
+---@class Config
+---@field child? Config The nested config.
+---@field name string?
+"#,
+            );
+
+            let schema = json_schema(&processor, "Config").unwrap();
+
+            assert_eq!(
+                schema["properties"]["child"],
+                json!({
+                    "anyOf": [{ "$ref": "#" }, { "type": "null" }],
+                    "description": "The nested config.",
+                })
+            );
+            assert_eq!(
+                schema["properties"]["name"],
+                json!({ "anyOf": [{ "type": "string" }, { "type": "null" }] })
+            );
+            assert!(schema.get("required").is_none());
+        }
+
+        #[test]
+        fn fields_without_a_json_equivalent_are_not_required() {
+            let processor = parse_str(
+                r#"
+---@class Config
+---@field name string
+---@field on_open fun()
+---@field handle userdata
+"#,
+            );
+
+            let schema = json_schema(&processor, "Config").unwrap();
+
+            assert_eq!(schema["properties"]["on_open"], json!({}));
+            assert_eq!(schema["required"], json!(["name"]));
+        }
+
+        #[test]
+        fn unknown_classes_have_no_schema() {
+            let processor = parse_str("---@class Config\n");
+
+            assert!(json_schema(&processor, "Missing").is_none());
+        }
+    }
+}
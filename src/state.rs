@@ -1,25 +1,537 @@
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
 use anyhow::Context;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use rayon::prelude::*;
 
-use crate::{processor::Processor, treesitter::parse_blocks};
+use crate::{
+    cache::BlockCache,
+    processor::{ProcessError, Processor},
+    transform::is_public_function,
+    treesitter::{parse_blocks, public_functions, syntax_errors, ParsedSource, PublicFunction},
+};
 
-pub fn parse_files(paths: Vec<PathBuf>) -> anyhow::Result<Processor> {
+/// The extensions of files found under `--dir`, in addition to ones passed with `--ext`.
+pub const DEFAULT_EXTENSIONS: [&str; 2] = ["lua", "luau"];
+
+/// What parsing a single file found and how long it took.
+#[derive(Debug, Clone)]
+pub struct FileStats {
+    pub path: PathBuf,
+    pub classes: Vec<String>,
+    pub aliases: Vec<String>,
+    pub enums: Vec<String>,
+    /// The qualified names of the documented functions.
+    pub functions: Vec<String>,
+    /// Every public function the file declares, documented or not, without ones declared
+    /// `@private`, `@protected`, or `@package`.
+    pub public_functions: Vec<PublicFunction>,
+    /// Annotations that failed to parse and were skipped.
+    pub errors: Vec<ProcessError>,
+    pub parse_time: Duration,
+}
+
+/// Build a set of `--exclude` globs. `*` doesn't match across `/` while `**` does.
+pub fn exclude_globs(patterns: &[String]) -> anyhow::Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .with_context(|| format!("invalid exclude pattern `{pattern}`"))?;
+        builder.add(glob);
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Find every Lua file under `dir`, skipping paths whose path relative to `dir`
+/// matches `exclude`.
+///
+/// Lua files are ones with a [default extension](DEFAULT_EXTENSIONS) or one of `extensions`,
+/// which may start with a dot.
+///
+/// Excluded directories, like ones matching `tests/**`, aren't descended into. Files are
+/// returned in the same order on every platform, sorted by name within each directory.
+pub fn find_lua_files(dir: &Path, exclude: &GlobSet, extensions: &[String]) -> Vec<PathBuf> {
+    let is_excluded = |entry: &walkdir::DirEntry| {
+        let Ok(relative) = entry.path().strip_prefix(dir) else {
+            return false;
+        };
+
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+
+        // `dir/**` only matches the directory's contents, so also try it with a slash
+        exclude.is_match(relative)
+            || (entry.file_type().is_dir() && exclude.is_match(relative.join("")))
+    };
+
+    let mut files = Vec::new();
+
+    for entry in walkdir::WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| !is_excluded(entry))
+    {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                log::warn!("{err}");
+                continue;
+            }
+        };
+
+        let has_extension = entry.path().extension().is_some_and(|ext| {
+            DEFAULT_EXTENSIONS.iter().any(|default| ext == *default)
+                || extensions
+                    .iter()
+                    .any(|extra| ext == extra.trim_start_matches('.'))
+        });
+
+        if has_extension {
+            files.push(entry.into_path());
+        }
+    }
+
+    files
+}
+
+/// Where the source of a file comes from.
+#[derive(Debug, Clone)]
+pub enum Input {
+    File(PathBuf),
+    /// Lua piped to stdin, documented as if it were in a file at `name`.
+    Stdin {
+        name: PathBuf,
+    },
+}
+
+impl Input {
+    /// The input for a file argument, where `-` means stdin.
+    pub fn from_arg(path: PathBuf, stdin_name: &Path) -> Self {
+        if path == Path::new("-") {
+            Input::Stdin {
+                name: stdin_name.to_path_buf(),
+            }
+        } else {
+            Input::File(path)
+        }
+    }
+
+    /// Read the source, returning it along with the path it's documented under.
+    fn read(self) -> anyhow::Result<(PathBuf, String)> {
+        match self {
+            Input::File(path) => {
+                let contents = std::fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read `{}`", path.display()))?;
+                Ok((path, contents))
+            }
+            Input::Stdin { name } => {
+                let contents =
+                    std::io::read_to_string(std::io::stdin()).context("failed to read stdin")?;
+                Ok((name, contents))
+            }
+        }
+    }
+}
+
+impl From<PathBuf> for Input {
+    fn from(path: PathBuf) -> Self {
+        Input::File(path)
+    }
+}
+
+/// Parse and process every file, returning the stats of each file along with the processor.
+///
+/// Files are parsed in parallel on rayon's thread pool, then processed in the order
+/// they were given so the output doesn't depend on thread scheduling.
+///
+/// If a cache is given, files whose contents were parsed before skip tree-sitter parsing.
+pub fn parse_files(
+    inputs: impl IntoIterator<Item = impl Into<Input>>,
+    cache: Option<&BlockCache>,
+) -> anyhow::Result<(Processor, Vec<FileStats>)> {
+    let inputs = inputs.into_iter().map(Into::into).collect::<Vec<Input>>();
+
+    // `Parser` isn't `Sync`, so every thread gets its own
+    let parsed = inputs
+        .into_par_iter()
+        .map_init(new_parser, |ts_parser, input| {
+            let ts_parser = ts_parser.as_mut().map_err(|err| anyhow::anyhow!("{err}"))?;
+            let start = Instant::now();
+            let (path, contents) = input.read()?;
+            let parsed = parse_source(ts_parser, &contents, cache)?;
+            anyhow::Ok((path, contents, parsed, start.elapsed()))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut processor = Processor::default();
+    let stats = parsed
+        .into_iter()
+        .map(|(path, contents, parsed, parse_time)| {
+            process_source(&path, &contents, parsed, parse_time, &mut processor)
+        })
+        .collect();
+
+    processor.merge_classes();
+    processor.sort_by_declaration();
+
+    Ok((processor, stats))
+}
+
+fn new_parser() -> anyhow::Result<tree_sitter::Parser> {
     let mut ts_parser = tree_sitter::Parser::new();
     ts_parser.set_language(&tree_sitter_lua::language())?;
+    Ok(ts_parser)
+}
+
+fn parse_source(
+    ts_parser: &mut tree_sitter::Parser,
+    contents: &str,
+    cache: Option<&BlockCache>,
+) -> anyhow::Result<ParsedSource> {
+    if let Some(parsed) = cache.and_then(|cache| cache.get(contents)) {
+        return Ok(parsed);
+    }
+
+    let tree = ts_parser.parse(contents, None).context("parse failed")?;
+    let mut cursor = tree.walk();
+
+    let parsed = ParsedSource {
+        blocks: parse_blocks(&mut cursor, contents.as_bytes(), false),
+        public_functions: public_functions(tree.root_node(), contents.as_bytes()),
+        syntax_errors: syntax_errors(tree.root_node()),
+    };
+
+    if let Some(cache) = cache {
+        cache.insert(contents, &parsed);
+    }
+
+    Ok(parsed)
+}
+
+/// Process a parsed file. `parse_time` is how long parsing took, which processing time
+/// is added to.
+fn process_source(
+    path: &Path,
+    contents: &str,
+    parsed: ParsedSource,
+    parse_time: Duration,
+    processor: &mut Processor,
+) -> FileStats {
+    let start = Instant::now();
+
+    let classes = processor.classes.len();
+    let aliases = processor.aliases.len();
+    let enums = processor.enums.len();
+    let functions = processor.functions.len();
+
+    // tree-sitter-lua doesn't know Luau's type syntax, so every typed Luau file would
+    // have errors
+    let is_luau = path.extension().is_some_and(|ext| ext == "luau");
+    let syntax_errors = parsed
+        .syntax_errors
+        .iter()
+        .filter(|_| !is_luau)
+        .map(|error| ProcessError::syntax(path, error));
+
+    let errors = processor
+        .process_blocks(parsed.blocks, path)
+        .into_iter()
+        .chain(syntax_errors)
+        .map(|error| error.with_source_code(contents))
+        .collect();
+
+    let stats = FileStats {
+        path: path.to_path_buf(),
+        classes: processor.classes[classes..]
+            .iter()
+            .map(|class| class.name.clone())
+            .collect(),
+        aliases: processor.aliases[aliases..]
+            .iter()
+            .map(|alias| alias.name.clone())
+            .collect(),
+        enums: processor.enums[enums..]
+            .iter()
+            .map(|r#enum| r#enum.name.clone())
+            .collect(),
+        functions: processor.functions[functions..]
+            .iter()
+            .map(|function| function.qualified_name())
+            .collect(),
+        // Documented functions can still be declared `@private` and the like
+        public_functions: parsed
+            .public_functions
+            .into_iter()
+            .filter(|function| {
+                !processor.functions[functions..]
+                    .iter()
+                    .any(|func| func.location.line == function.line && !is_public_function(func))
+            })
+            .collect(),
+        errors,
+        parse_time: parse_time + start.elapsed(),
+    };
+
+    log::debug!(
+        "{}: {} classes, {} aliases, {} enums, {} functions, {} skipped annotations in {:.2?}",
+        path.display(),
+        stats.classes.len(),
+        stats.aliases.len(),
+        stats.enums.len(),
+        stats.functions.len(),
+        stats.errors.len(),
+        stats.parse_time,
+    );
+
+    stats
+}
+
+/// Parse a single Lua source string. Used in tests.
+#[cfg(test)]
+pub fn parse_str(contents: &str) -> Processor {
+    let mut ts_parser = new_parser().unwrap();
+    let parsed = parse_source(&mut ts_parser, contents, None).unwrap();
 
     let mut processor = Processor::default();
+    process_source(
+        Path::new("test.lua"),
+        contents,
+        parsed,
+        Duration::ZERO,
+        &mut processor,
+    );
+    processor.merge_classes();
+    processor.sort_by_declaration();
+
+    processor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod exclude {
+        use super::*;
+
+        #[test]
+        fn excluded_files_and_directories_are_skipped() {
+            let dir = tempfile::tempdir().unwrap();
+
+            for path in [
+                "init.lua",
+                "api/window.lua",
+                "tests/window_spec.lua",
+                "tests/fixtures/config.lua",
+                "deps/json.lua",
+                "deps/nested/keep.lua",
+                "README.md",
+            ] {
+                let path = dir.path().join(path);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(path, "").unwrap();
+            }
+
+            let exclude =
+                exclude_globs(&["tests/**".to_string(), "deps/*.lua".to_string()]).unwrap();
+
+            let files = find_lua_files(dir.path(), &exclude, &[])
+                .into_iter()
+                .map(|path| path.strip_prefix(dir.path()).unwrap().to_path_buf())
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                files,
+                [
+                    PathBuf::from("api/window.lua"),
+                    PathBuf::from("deps/nested/keep.lua"),
+                    PathBuf::from("init.lua"),
+                ]
+            );
+        }
+
+        #[test]
+        fn invalid_patterns_are_an_error() {
+            assert!(exclude_globs(&["a/[".to_string()]).is_err());
+        }
+    }
+
+    mod parallel {
+        use super::*;
+
+        #[test]
+        fn files_are_processed_in_the_given_order() {
+            let dir = tempfile::tempdir().unwrap();
+
+            let paths = (0..64)
+                .map(|i| {
+                    let path = dir.path().join(format!("class{i}.lua"));
+                    std::fs::write(
+                        &path,
+                        format!("---@class Class{i}\nlocal Class{i} = {{}}\n\n---Get it.\nfunction Class{i}.get() end\n"),
+                    )
+                    .unwrap();
+                    path
+                })
+                .collect::<Vec<_>>();
 
-    for path in paths {
-        let contents = std::fs::read_to_string(&path)?;
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(8)
+                .build()
+                .unwrap();
+            let (processor, stats) = pool.install(|| parse_files(paths.clone(), None)).unwrap();
 
-        let tree = ts_parser.parse(&contents, None).context("parse failed")?;
-        let mut cursor = tree.walk();
+            assert_eq!(
+                processor
+                    .classes
+                    .iter()
+                    .map(|class| class.name.clone())
+                    .collect::<Vec<_>>(),
+                (0..64).map(|i| format!("Class{i}")).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                stats
+                    .iter()
+                    .map(|file| file.path.clone())
+                    .collect::<Vec<_>>(),
+                paths
+            );
+            assert_eq!(stats[10].functions, ["Class10.get"]);
+        }
+    }
+
+    mod extensions {
+        use super::*;
+
+        #[test]
+        fn lua_luau_and_extra_extensions_are_found() {
+            let dir = tempfile::tempdir().unwrap();
+
+            for path in ["init.lua", "types.luau", "config.rockspec", "README.md"] {
+                std::fs::write(dir.path().join(path), "").unwrap();
+            }
+
+            let find = |extensions: &[String]| {
+                find_lua_files(dir.path(), &GlobSet::empty(), extensions)
+                    .into_iter()
+                    .map(|path| path.strip_prefix(dir.path()).unwrap().to_path_buf())
+                    .collect::<Vec<_>>()
+            };
+
+            assert_eq!(
+                find(&[]),
+                [PathBuf::from("init.lua"), PathBuf::from("types.luau")]
+            );
+            assert_eq!(
+                find(&[".rockspec".to_string()]),
+                [
+                    PathBuf::from("config.rockspec"),
+                    PathBuf::from("init.lua"),
+                    PathBuf::from("types.luau"),
+                ]
+            );
+        }
+
+        #[test]
+        fn luau_type_syntax_does_not_hide_annotations() {
+            let processor = parse_str(
+                r#"
+---@class Point
+---@field x number
+local Point = {}
+
+---Make a point.
+---@param x number
+---@return Point
+function Point.new(x: number): Point
+    return setmetatable({ x = x }, Point)
+end
+"#,
+            );
+
+            assert_eq!(processor.classes[0].name, "Point");
+            assert_eq!(processor.classes[0].lsp_fields.len(), 1);
+        }
+    }
+
+    mod stdin {
+        use super::*;
 
-        let blocks = parse_blocks(&mut cursor, contents.as_bytes(), false);
+        #[test]
+        fn dash_reads_stdin_under_the_given_name() {
+            let name = Path::new("plugin.lua");
 
-        processor.process_blocks(blocks);
+            assert!(matches!(
+                Input::from_arg(PathBuf::from("-"), name),
+                Input::Stdin { name } if name == Path::new("plugin.lua")
+            ));
+            assert!(matches!(
+                Input::from_arg(PathBuf::from("init.lua"), name),
+                Input::File(path) if path == Path::new("init.lua")
+            ));
+        }
     }
 
-    Ok(processor)
+    mod syntax_errors {
+        use super::*;
+
+        use crate::processor::ProcessErrorKind;
+
+        fn syntax_errors(name: &str, contents: &str) -> Vec<ProcessError> {
+            let mut ts_parser = new_parser().unwrap();
+            let parsed = parse_source(&mut ts_parser, contents, None).unwrap();
+
+            process_source(
+                Path::new(name),
+                contents,
+                parsed,
+                Duration::ZERO,
+                &mut Processor::default(),
+            )
+            .errors
+            .into_iter()
+            .filter(|error| error.kind == ProcessErrorKind::SyntaxError)
+            .collect()
+        }
+
+        #[test]
+        fn unparseable_lua_is_reported_with_its_line() {
+            let errors = syntax_errors(
+                "test.lua",
+                "---@class Window\nlocal Window = {}\n\nlocal x = = 1\n",
+            );
+
+            assert!(!errors.is_empty());
+            assert!(errors.iter().all(|error| error.line == 4));
+            assert_eq!(errors[0].to_string(), "test.lua:4: Lua syntax error");
+        }
+
+        #[test]
+        fn missing_tokens_say_what_was_expected() {
+            let errors = syntax_errors("test.lua", "function f()\n    return 1\n");
+
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].message, "expected `end`");
+        }
+
+        #[test]
+        fn valid_lua_has_no_syntax_errors() {
+            assert!(syntax_errors("test.lua", "local function f() return 1 end\n").is_empty());
+        }
+
+        #[test]
+        fn luau_files_are_not_checked() {
+            let source = "function Point.new(x: number): Point\n    return x\nend\n";
+
+            assert!(!syntax_errors("test.lua", source).is_empty());
+            assert!(syntax_errors("test.luau", source).is_empty());
+        }
+    }
 }
@@ -0,0 +1,310 @@
+use crate::{
+    annotation::{Class, Function, Scope},
+    processor::Processor,
+    treesitter::FieldName,
+    types::{Literal, TypeInner},
+};
+
+/// A pass that modifies the processed documentation before it's rendered,
+/// like filtering, renaming, or enriching symbols.
+pub trait Transform {
+    fn transform(&self, processor: &mut Processor);
+}
+
+/// Transforms that run in order between parsing and rendering.
+#[derive(Default)]
+pub struct Transforms {
+    transforms: Vec<Box<dyn Transform>>,
+}
+
+impl Transforms {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a transform to run after all previously registered ones.
+    pub fn register(&mut self, transform: impl Transform + 'static) -> &mut Self {
+        self.transforms.push(Box::new(transform));
+        self
+    }
+
+    pub fn run(&self, processor: &mut Processor) {
+        for transform in self.transforms.iter() {
+            transform.transform(processor);
+        }
+    }
+}
+
+/// Which symbols are documented, by their declared visibility.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Visibility {
+    /// Everything, whatever its visibility
+    #[default]
+    All,
+    /// Only the public API, without `private`, `protected`, or `package` fields and
+    /// functions, or functions whose names start with an underscore
+    Public,
+}
+
+/// Removes fields declared `private` from classes.
+pub struct StripPrivate;
+
+impl Transform for StripPrivate {
+    fn transform(&self, processor: &mut Processor) {
+        for class in processor.classes.iter_mut() {
+            strip_fields(class, |scope| matches!(scope, Some(Scope::Private)));
+        }
+    }
+}
+
+/// Removes everything that isn't public API: fields and functions declared `private`,
+/// `protected`, or `package`, and functions whose names start with an underscore.
+pub struct PublicOnly;
+
+impl Transform for PublicOnly {
+    fn transform(&self, processor: &mut Processor) {
+        for class in processor.classes.iter_mut() {
+            strip_fields(class, |scope| !is_public(scope));
+        }
+
+        processor.functions.retain(is_public_function);
+    }
+}
+
+/// Whether a symbol with the given scope is public API. Symbols without a scope are public.
+pub fn is_public(scope: Option<Scope>) -> bool {
+    matches!(scope, None | Some(Scope::Public))
+}
+
+/// Whether `function` is public API: its scope is public and its name doesn't start
+/// with an underscore.
+pub fn is_public_function(function: &Function) -> bool {
+    is_public(function.scope) && !function.name.starts_with('_')
+}
+
+/// Remove the `@field`s of `class` whose scope matches `strip`.
+fn strip_fields(class: &mut Class, strip: impl Fn(Option<Scope>) -> bool) {
+    let mut stripped_names = Vec::new();
+
+    class.lsp_fields.retain(|field| {
+        if !strip(field.scope) {
+            return true;
+        }
+
+        if let TypeInner::Literal(Literal::String(name)) = &field.ident_type.inner {
+            stripped_names.push(name.clone());
+        }
+
+        false
+    });
+
+    // Assignments of stripped fields would otherwise show up as public fields
+    class.ts_fields.retain(|field| match field.name.as_ref() {
+        Some(FieldName::Ident(name)) => !stripped_names.contains(name),
+        _ => true,
+    });
+}
+
+/// Removes functions declared `local`, which can't be called from outside their file.
+pub struct StripLocals;
+
+impl Transform for StripLocals {
+    fn transform(&self, processor: &mut Processor) {
+        processor.functions.retain(|function| !function.is_local);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::state::parse_str;
+
+    mod transforms {
+        use super::*;
+
+        struct Rename {
+            from: &'static str,
+            to: &'static str,
+        }
+
+        impl Transform for Rename {
+            fn transform(&self, processor: &mut Processor) {
+                for class in processor.classes.iter_mut() {
+                    if class.name == self.from {
+                        class.name = self.to.to_string();
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn registered_transforms_run_in_order() {
+            let mut processor = parse_str(
+                r#"
+---@class Window
+local Window = {}
+"#,
+            );
+
+            let mut transforms = Transforms::new();
+            transforms
+                .register(Rename {
+                    from: "Window",
+                    to: "Surface",
+                })
+                .register(Rename {
+                    from: "Surface",
+                    to: "Toplevel",
+                });
+            transforms.run(&mut processor);
+
+            assert_eq!(processor.classes[0].name, "Toplevel");
+        }
+
+        #[test]
+        fn strip_private_removes_private_fields() {
+            let mut processor = parse_str(
+                r#"
+---@class Window
+---@field private handle integer
+---@field title string
+local Window = {
+    handle = 0,
+}
+"#,
+            );
+
+            StripPrivate.transform(&mut processor);
+
+            let fields = processor.classes[0].fields();
+            assert_eq!(fields.len(), 1);
+            assert_eq!(fields[0].ident_type.to_string(), "title");
+        }
+
+        #[test]
+        fn public_only_removes_non_public_fields_and_functions() {
+            let mut processor = parse_str(
+                r#"
+---@class Window
+---@field private handle integer
+---@field protected parent Window
+---@field package id integer
+---@field public title string
+---@field width integer
+local Window = {
+    handle = 0,
+    id = 1,
+}
+
+---@private
+function Window:focus_internal() end
+
+---@package
+function Window.from_handle(handle) end
+
+---Redraw the window.
+function Window:_refresh() end
+
+---@public
+function Window:close() end
+
+---Resize the window.
+function Window:resize() end
+"#,
+            );
+
+            assert_eq!(processor.functions.len(), 5);
+
+            PublicOnly.transform(&mut processor);
+
+            let fields = processor.classes[0]
+                .fields()
+                .iter()
+                .map(|field| field.ident_type.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(fields, ["title", "width"]);
+
+            let functions = processor
+                .functions
+                .iter()
+                .map(|function| function.name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(functions, ["close", "resize"]);
+        }
+
+        #[test]
+        fn types_only_stripped_symbols_use_are_pruned_afterwards() {
+            let mut processor = parse_str(
+                r#"
+---@alias Handle integer
+
+---@class Window
+---@field private handle Handle
+---@field title string
+local Window = {}
+"#,
+            );
+
+            StripPrivate.transform(&mut processor);
+            processor.prune_unused();
+
+            assert!(processor.aliases.is_empty());
+        }
+
+        #[test]
+        fn strip_locals_keeps_only_globals() {
+            let mut processor = parse_str(
+                r#"
+---@class Window
+local Window = {}
+
+---Close the window.
+function Window:close() end
+
+---Open a window.
+function open() end
+
+---Clamp a size.
+local function clamp(size) end
+
+---Round a size.
+local round = function(size) end
+
+---Scale a size.
+local scale, shrink = function(size) end, function(size) end
+
+---Resize the window.
+Window.resize = function(width, height) end
+"#,
+            );
+
+            let is_local = processor
+                .functions
+                .iter()
+                .map(|function| (function.qualified_name(), function.is_local))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                is_local,
+                [
+                    ("Window:close".to_string(), false),
+                    ("open".to_string(), false),
+                    ("clamp".to_string(), true),
+                    ("round".to_string(), true),
+                    ("scale".to_string(), true),
+                    ("shrink".to_string(), true),
+                    ("Window.resize".to_string(), false),
+                ]
+            );
+
+            StripLocals.transform(&mut processor);
+
+            let names = processor
+                .functions
+                .iter()
+                .map(|function| function.qualified_name())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["Window:close", "open", "Window.resize"]);
+        }
+    }
+}
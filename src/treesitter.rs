@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use tree_sitter::{Node, TreeCursor};
 
 use crate::node_types::NodeType;
@@ -17,7 +18,7 @@ fn parse_lsp_comment_block<'a>(
 
     let mut current_end_line = current.range().end_point.row;
 
-    if current.kind() != NodeType::COMMENT {
+    if current.kind() != NodeType::COMMENT || is_trailing_comment(current) {
         let block = parse_anyway.then_some(LspCommentBlock {
             comments: Vec::new(),
             commented_node: Some(current),
@@ -34,7 +35,7 @@ fn parse_lsp_comment_block<'a>(
         return (None, cursor.goto_next_sibling());
     }
 
-    comments.push(current_text.strip_prefix("---").unwrap().to_string());
+    comments.push(Comment::new(current, current_text));
 
     let still_stuff_left = loop {
         if !cursor.goto_next_sibling() {
@@ -61,7 +62,7 @@ fn parse_lsp_comment_block<'a>(
         };
 
         if text.starts_with("---") {
-            comments.push(text.strip_prefix("---").unwrap().to_string());
+            comments.push(Comment::new(current, text));
         }
     };
 
@@ -74,13 +75,21 @@ fn parse_lsp_comment_block<'a>(
     )
 }
 
+/// Whether `node` is a comment at the end of a line with other code, like `x = 1, -- comment`.
+fn is_trailing_comment(node: Node) -> bool {
+    node.kind() == NodeType::COMMENT
+        && node
+            .prev_sibling()
+            .is_some_and(|prev| prev.end_position().row == node.start_position().row)
+}
+
 #[derive(Debug)]
 struct LspCommentBlock<'a> {
-    comments: Vec<String>,
+    comments: Vec<Comment>,
     commented_node: Option<Node<'a>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Block {
     Table(TableBlock),
     Field(FieldBlock),
@@ -88,26 +97,53 @@ pub enum Block {
     Free(FreeBlock),
 }
 
-#[derive(Debug, Clone)]
+/// A `---` comment with the dashes stripped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comment {
+    pub text: String,
+    /// The 1-based line the comment is on.
+    pub line: usize,
+    /// The byte offset in the source of the text after the dashes.
+    pub offset: usize,
+}
+
+impl Comment {
+    fn new(node: Node, text: &str) -> Self {
+        Self {
+            text: text.strip_prefix("---").unwrap().to_string(),
+            line: node.start_position().row + 1,
+            offset: node.start_byte() + "---".len(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FreeBlock {
-    pub annotations: Vec<String>,
+    pub annotations: Vec<Comment>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TableBlock {
-    pub annotations: Vec<String>,
+    pub annotations: Vec<Comment>,
     pub name: String,
     pub fields: Vec<Block>,
+    pub value: String,
+    /// The 1-based line the table starts on.
+    pub line: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldBlock {
-    pub annotations: Vec<String>,
+    pub annotations: Vec<Comment>,
     pub name: Option<FieldName>,
     pub value: String,
+    /// A comment at the end of the field's line, like `x = 1, -- comment`.
+    pub trailing_comment: Option<String>,
+    /// The 1-based line the field starts on.
+    pub line: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FieldName {
     Ident(String),
     Value(String),
@@ -124,21 +160,125 @@ impl std::fmt::Display for FieldName {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionBlock {
-    pub annotations: Vec<String>,
+    pub annotations: Vec<Comment>,
     pub table: Option<String>,
     pub name: String,
     pub params: Vec<FunctionParam>,
     pub is_method: bool,
+    /// Whether the function is declared `local`, like `local function f() end` or
+    /// `local f = function() end`.
+    #[serde(default)]
+    pub is_local: bool,
+    /// The source text of the whole function statement.
+    pub source: String,
+    /// The source text of the function expression when it's assigned, like
+    /// `function() end` in `f = function() end`.
+    #[serde(default)]
+    pub value: Option<String>,
+    /// The 1-based line the function starts on.
+    pub line: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum FunctionParam {
     Ident(String),
     Varargs,
 }
 
+/// The blocks parsed from a Lua source along with the public functions it declares,
+/// documented or not.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedSource {
+    pub blocks: Vec<Block>,
+    pub public_functions: Vec<PublicFunction>,
+    /// Where tree-sitter couldn't parse the source, in which case the documentation
+    /// around it may be missing or wrong.
+    pub syntax_errors: Vec<SyntaxError>,
+}
+
+/// An `ERROR` or `MISSING` node in a parsed source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyntaxError {
+    /// The 1-based line the error starts on.
+    pub line: usize,
+    /// The byte offset in the source the error starts at.
+    pub offset: usize,
+    /// The length of the unparseable text in bytes, which is 0 for missing tokens.
+    pub len: usize,
+    /// The kind of node tree-sitter expected, like `end`, if the error is a missing token.
+    pub missing: Option<String>,
+}
+
+/// A function declared outside of a `local` statement whose name doesn't start with `_`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublicFunction {
+    /// The name as written in the source, like `Window.close` or `Window:close`.
+    pub name: String,
+    /// The 1-based line the function starts on.
+    pub line: usize,
+}
+
+/// Find the public functions declared in a node and its descendants, documented or not.
+pub fn public_functions(node: Node, source: &[u8]) -> Vec<PublicFunction> {
+    let is_public_statement = match node.kind() {
+        NodeType::FUNCTION_DECLARATION => {
+            node.child(0).is_some_and(|child| child.kind() != "local")
+        }
+        NodeType::ASSIGNMENT_STATEMENT => node
+            .parent()
+            .is_some_and(|parent| parent.kind() != NodeType::VARIABLE_DECLARATION),
+        _ => false,
+    };
+
+    let mut functions = Vec::new();
+
+    if let Some(block) = is_public_statement
+        .then(|| parse_function_block(node, source, &[]))
+        .flatten()
+        .filter(|block| !block.name.starts_with('_'))
+    {
+        let connector = if block.is_method { ":" } else { "." };
+        functions.push(PublicFunction {
+            name: match block.table {
+                Some(table) => format!("{table}{connector}{}", block.name),
+                None => block.name,
+            },
+            line: block.line,
+        });
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        functions.extend(public_functions(child, source));
+    }
+
+    functions
+}
+
+/// Find the `ERROR` and `MISSING` nodes in a node and its descendants.
+///
+/// Errors nested in another error aren't reported separately.
+pub fn syntax_errors(node: Node) -> Vec<SyntaxError> {
+    if node.is_error() || node.is_missing() {
+        return vec![SyntaxError {
+            line: node.start_position().row + 1,
+            offset: node.start_byte(),
+            len: node.end_byte() - node.start_byte(),
+            missing: node.is_missing().then(|| node.kind().to_string()),
+        }];
+    }
+
+    if !node.has_error() {
+        return Vec::new();
+    }
+
+    let mut cursor = node.walk();
+    let errors = node.children(&mut cursor).flat_map(syntax_errors).collect();
+    errors
+}
+
 pub fn parse_blocks(cursor: &mut TreeCursor, source: &[u8], parse_all: bool) -> Vec<Block> {
     let mut blocks = Vec::new();
 
@@ -146,7 +286,15 @@ pub fn parse_blocks(cursor: &mut TreeCursor, source: &[u8], parse_all: bool) ->
         let (block, still_stuff_left) = parse_lsp_comment_block(cursor, source, parse_all);
         if let Some(block) = block {
             if let Some(node) = block.commented_node {
-                if let Some(table_block) = parse_table_block(node, source, &block.comments) {
+                let pairs = assignment_pairs(node);
+                if pairs.len() > 1 {
+                    blocks.extend(parse_assignment_blocks(
+                        node,
+                        &pairs,
+                        source,
+                        block.comments,
+                    ));
+                } else if let Some(table_block) = parse_table_block(node, source, &block.comments) {
                     blocks.push(Block::Table(table_block));
                 } else if let Some(fn_block) = parse_function_block(node, source, &block.comments) {
                     blocks.push(Block::Function(fn_block));
@@ -196,59 +344,115 @@ macro_rules! ensure {
     };
 }
 
-pub fn parse_table_block(
-    mut node: Node,
-    source: &[u8],
-    annotations: &[String],
-) -> Option<TableBlock> {
+/// Pair each target of an assignment statement with the value assigned to it.
+///
+/// Targets without a value, like `b` in `local a, b = {}`, are left out.
+fn assignment_pairs(mut node: Node) -> Vec<(Node, Node)> {
     if node.kind() == NodeType::VARIABLE_DECLARATION {
-        let asm_stmt = node.named_child(0)?;
-        ensure!(asm_stmt.kind() == NodeType::ASSIGNMENT_STATEMENT);
-        node = asm_stmt;
+        match node.named_child(0) {
+            Some(asm_stmt) if asm_stmt.kind() == NodeType::ASSIGNMENT_STATEMENT => node = asm_stmt,
+            _ => return Vec::new(),
+        }
     }
 
-    if node.kind() == NodeType::ASSIGNMENT_STATEMENT {
-        let var_list = node.named_child(0)?;
-        ensure!(var_list.kind() == NodeType::VARIABLE_LIST);
-        let expr_list = node.named_child(1)?;
-        ensure!(expr_list.kind() == NodeType::EXPRESSION_LIST);
-        let name = var_list.child_by_field_name("name")?;
-        let value = expr_list.child_by_field_name("value")?;
-        ensure!(value.kind() == NodeType::TABLE_CONSTRUCTOR);
-        let mut cursor = value.walk();
-        let fields = if !cursor.goto_first_child() {
-            Vec::new()
-        } else {
-            parse_blocks(&mut cursor, source, true)
-        };
-        return Some(TableBlock {
-            annotations: annotations.to_vec(),
-            name: name.utf8_text(source).unwrap().to_string(),
-            fields,
-        });
+    if node.kind() != NodeType::ASSIGNMENT_STATEMENT {
+        return Vec::new();
+    }
+
+    let (Some(var_list), Some(expr_list)) = (node.named_child(0), node.named_child(1)) else {
+        return Vec::new();
+    };
+    if var_list.kind() != NodeType::VARIABLE_LIST || expr_list.kind() != NodeType::EXPRESSION_LIST {
+        return Vec::new();
+    }
+
+    let mut var_cursor = var_list.walk();
+    let mut expr_cursor = expr_list.walk();
+    var_list
+        .named_children(&mut var_cursor)
+        .zip(expr_list.named_children(&mut expr_cursor))
+        .collect()
+}
+
+/// Parse a block for every target of a multiple assignment like `local a, b = {}, function() end`.
+///
+/// The annotations above the statement go to the first target.
+fn parse_assignment_blocks(
+    node: Node,
+    pairs: &[(Node, Node)],
+    source: &[u8],
+    annotations: Vec<Comment>,
+) -> Vec<Block> {
+    let statement_source = node.utf8_text(source).unwrap();
+    let mut annotations = Some(annotations);
+    let mut blocks = Vec::new();
+
+    for &(name, value) in pairs {
+        let annotations = annotations.take().unwrap_or_default();
+
+        if let Some(table_block) = table_block(name, value, node, source, &annotations) {
+            blocks.push(Block::Table(table_block));
+            continue;
+        }
+
+        if let Some(fn_block) =
+            assigned_function_block(name, value, statement_source, source, &annotations)
+        {
+            blocks.push(Block::Function(FunctionBlock {
+                is_local: node.kind() == NodeType::VARIABLE_DECLARATION,
+                ..fn_block
+            }));
+        } else if !annotations.is_empty() {
+            blocks.push(Block::Free(FreeBlock { annotations }));
+        }
+
+        let mut child_cursor = value.walk();
+        if child_cursor.goto_first_child() {
+            blocks.extend(parse_blocks(&mut child_cursor, source, false));
+        }
+    }
+
+    blocks
+}
+
+fn table_block(
+    name: Node,
+    value: Node,
+    statement: Node,
+    source: &[u8],
+    annotations: &[Comment],
+) -> Option<TableBlock> {
+    ensure!(value.kind() == NodeType::TABLE_CONSTRUCTOR);
+    let mut cursor = value.walk();
+    let fields = if !cursor.goto_first_child() {
+        Vec::new()
+    } else {
+        parse_blocks(&mut cursor, source, true)
+    };
+    Some(TableBlock {
+        annotations: annotations.to_vec(),
+        name: name.utf8_text(source).unwrap().to_string(),
+        fields,
+        value: value.utf8_text(source).unwrap().to_string(),
+        line: statement.start_position().row + 1,
+    })
+}
+
+pub fn parse_table_block(node: Node, source: &[u8], annotations: &[Comment]) -> Option<TableBlock> {
+    if let Some(&(name, value)) = assignment_pairs(node).first() {
+        return table_block(name, value, node, source, annotations);
     }
 
     if node.kind() == NodeType::FIELD {
         let name = node.child_by_field_name("name")?;
         let value = node.child_by_field_name("value")?;
-        ensure!(value.kind() == NodeType::TABLE_CONSTRUCTOR);
-        let mut cursor = value.walk();
-        let fields = if !cursor.goto_first_child() {
-            Vec::new()
-        } else {
-            parse_blocks(&mut cursor, source, true)
-        };
-        return Some(TableBlock {
-            annotations: annotations.to_vec(),
-            name: name.utf8_text(source).unwrap().to_string(),
-            fields,
-        });
+        return table_block(name, value, node, source, annotations);
     }
 
     None
 }
 
-pub fn parse_field_block(node: Node, source: &[u8], annotations: &[String]) -> Option<FieldBlock> {
+pub fn parse_field_block(node: Node, source: &[u8], annotations: &[Comment]) -> Option<FieldBlock> {
     ensure!(node.kind() == NodeType::FIELD);
     let name = node.child_by_field_name("name");
     let value = node.child_by_field_name("value")?;
@@ -261,64 +465,94 @@ pub fn parse_field_block(node: Node, source: &[u8], annotations: &[String]) -> O
         }
     });
 
+    let trailing_comment = std::iter::successors(node.next_sibling(), |node| node.next_sibling())
+        .find(|sibling| sibling.is_named())
+        .filter(|sibling| is_trailing_comment(*sibling))
+        .and_then(|comment| {
+            let text = comment
+                .utf8_text(source)
+                .unwrap()
+                .trim_start_matches('-')
+                .trim();
+            (!text.is_empty()).then(|| text.to_string())
+        });
+
     Some(FieldBlock {
         annotations: annotations.to_vec(),
         name: field_name,
         value: value.utf8_text(source).unwrap().to_string(),
+        trailing_comment,
+        line: node.start_position().row + 1,
     })
 }
 
-pub fn parse_function_block(
-    mut node: Node,
+fn function_definition_block(
+    node: Node,
+    table: Option<Node>,
+    name: Node,
+    statement_source: &str,
     source: &[u8],
-    annotations: &[String],
+    annotations: &[Comment],
 ) -> Option<FunctionBlock> {
-    let parse_function_definition = |node: Node, table: Option<Node>, name: Node| {
-        ensure!(node.kind() == NodeType::FUNCTION_DEFINITION);
-        let parameters = node.child_by_field_name("parameters")?;
-        assert_eq!(parameters.kind(), NodeType::PARAMETERS);
-        let mut cursor = parameters.walk();
-        let params = parameters
-            .named_children(&mut cursor)
-            .flat_map(|param| match param.kind() {
-                NodeType::IDENTIFIER => Some(FunctionParam::Ident(
-                    param.utf8_text(source).unwrap().to_string(),
-                )),
-                NodeType::VARARG_EXPRESSION => Some(FunctionParam::Varargs),
-                _ => None,
-            });
-        Some(FunctionBlock {
-            annotations: annotations.to_vec(),
-            table: table.map(|table| table.utf8_text(source).unwrap().to_string()),
-            name: name.utf8_text(source).unwrap().to_string(),
-            params: params.collect(),
-            is_method: false,
-        })
-    };
+    ensure!(node.kind() == NodeType::FUNCTION_DEFINITION);
+    let parameters = node.child_by_field_name("parameters")?;
+    assert_eq!(parameters.kind(), NodeType::PARAMETERS);
+    let mut cursor = parameters.walk();
+    let params = parameters
+        .named_children(&mut cursor)
+        .flat_map(|param| match param.kind() {
+            NodeType::IDENTIFIER => Some(FunctionParam::Ident(
+                param.utf8_text(source).unwrap().to_string(),
+            )),
+            NodeType::VARARG_EXPRESSION => Some(FunctionParam::Varargs),
+            _ => None,
+        });
+    Some(FunctionBlock {
+        annotations: annotations.to_vec(),
+        table: table.map(|table| table.utf8_text(source).unwrap().to_string()),
+        name: name.utf8_text(source).unwrap().to_string(),
+        params: params.collect(),
+        is_method: false,
+        is_local: false,
+        source: statement_source.to_string(),
+        value: Some(node.utf8_text(source).unwrap().to_string()),
+        line: node.start_position().row + 1,
+    })
+}
 
-    if node.kind() == NodeType::VARIABLE_DECLARATION {
-        let asm_stmt = node.named_child(0)?;
-        ensure!(asm_stmt.kind() == NodeType::ASSIGNMENT_STATEMENT);
-        node = asm_stmt;
-    }
+/// Parse a function assigned to a target like `f` or `M.f`.
+fn assigned_function_block(
+    mut name: Node,
+    value: Node,
+    statement_source: &str,
+    source: &[u8],
+    annotations: &[Comment],
+) -> Option<FunctionBlock> {
+    let table = if name.kind() == NodeType::DOT_INDEX_EXPRESSION {
+        let table = name.child_by_field_name("table")?;
+        name = name.child_by_field_name("field")?;
+        Some(table)
+    } else {
+        None
+    };
 
-    if node.kind() == NodeType::ASSIGNMENT_STATEMENT {
-        let var_list = node.named_child(0)?;
-        ensure!(var_list.kind() == NodeType::VARIABLE_LIST);
-        let expr_list = node.named_child(1)?;
-        ensure!(expr_list.kind() == NodeType::EXPRESSION_LIST);
-        let mut name = var_list.child_by_field_name("name")?;
-
-        let table = if name.kind() == NodeType::DOT_INDEX_EXPRESSION {
-            let table = name.child_by_field_name("table")?;
-            name = name.child_by_field_name("field")?;
-            Some(table)
-        } else {
-            None
-        };
+    function_definition_block(value, table, name, statement_source, source, annotations)
+}
 
-        let value = expr_list.child_by_field_name("value")?;
-        return parse_function_definition(value, table, name);
+pub fn parse_function_block(
+    node: Node,
+    source: &[u8],
+    annotations: &[Comment],
+) -> Option<FunctionBlock> {
+    let statement_source = node.utf8_text(source).unwrap();
+
+    if let Some(&(name, value)) = assignment_pairs(node).first() {
+        return assigned_function_block(name, value, statement_source, source, annotations).map(
+            |block| FunctionBlock {
+                is_local: node.kind() == NodeType::VARIABLE_DECLARATION,
+                ..block
+            },
+        );
     }
 
     if node.kind() == NodeType::FUNCTION_DECLARATION {
@@ -355,14 +589,17 @@ pub fn parse_function_block(
             name: name.utf8_text(source).unwrap().to_string(),
             params: params.collect(),
             is_method,
+            is_local: node.child(0).is_some_and(|child| child.kind() == "local"),
+            source: statement_source.to_string(),
+            value: None,
+            line: node.start_position().row + 1,
         });
     }
 
     if node.kind() == NodeType::FIELD {
         let name = node.child_by_field_name("name")?;
         let value = node.child_by_field_name("value")?;
-        ensure!(value.kind() == NodeType::FUNCTION_DEFINITION);
-        return parse_function_definition(value, None, name);
+        return function_definition_block(value, None, name, statement_source, source, annotations);
     }
 
     None
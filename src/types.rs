@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use replace_with::replace_with;
+use serde::Serialize;
 
 pub const NIL: &str = "nil";
 pub const ANY: &str = "any";
@@ -18,8 +19,9 @@ pub mod literals {
     pub const FALSE: &str = "false";
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Type {
+    #[serde(flatten)]
     pub inner: TypeInner,
     pub generics: Vec<Type>,
     pub nullable: bool,
@@ -32,6 +34,46 @@ pub enum Metatype {
     Enum,
 }
 
+impl Metatype {
+    /// The default name of the output subdirectory pages of this kind are written to.
+    pub fn default_dir(self) -> &'static str {
+        match self {
+            Metatype::Class => "classes",
+            Metatype::Alias => "aliases",
+            Metatype::Enum => "enums",
+        }
+    }
+}
+
+/// The names of the output subdirectories class, alias, and enum pages are written to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionDirs {
+    pub classes: String,
+    pub aliases: String,
+    pub enums: String,
+}
+
+impl Default for SectionDirs {
+    fn default() -> Self {
+        Self {
+            classes: Metatype::Class.default_dir().to_string(),
+            aliases: Metatype::Alias.default_dir().to_string(),
+            enums: Metatype::Enum.default_dir().to_string(),
+        }
+    }
+}
+
+impl SectionDirs {
+    /// The subdirectory pages of `metatype` are written to.
+    pub fn get(&self, metatype: Metatype) -> &str {
+        match metatype {
+            Metatype::Class => &self.classes,
+            Metatype::Alias => &self.aliases,
+            Metatype::Enum => &self.enums,
+        }
+    }
+}
+
 impl Type {
     pub const NIL: Self = Type {
         inner: TypeInner::Nil,
@@ -100,6 +142,9 @@ impl Type {
         self.nullable = true;
     }
 
+    /// A union of `types`, kept in the order given.
+    ///
+    /// Members are never sorted or deduplicated, so parsed unions render in source order.
     pub fn union(types: impl IntoIterator<Item = Type>) -> Self {
         Self {
             inner: TypeInner::Union(types.into_iter().collect()),
@@ -108,6 +153,14 @@ impl Type {
         }
     }
 
+    pub fn intersection(types: impl IntoIterator<Item = Type>) -> Self {
+        Self {
+            inner: TypeInner::Intersection(types.into_iter().collect()),
+            generics: Vec::new(),
+            nullable: false,
+        }
+    }
+
     pub fn string_literal(string: impl ToString) -> Self {
         Self {
             inner: TypeInner::Literal(Literal::String(string.to_string())),
@@ -168,6 +221,43 @@ impl Type {
         self.generics.push(generic);
     }
 
+    /// Mark an already formatted type as nullable if it is.
+    ///
+    /// Functions, unions, and intersections are parenthesized so the `?` applies to the
+    /// whole type instead of their last return or member.
+    pub fn format_nullable(&self, formatted: String) -> String {
+        match (&self.inner, self.nullable) {
+            (_, false) => formatted,
+            (
+                TypeInner::Function { .. } | TypeInner::Union(_) | TypeInner::Intersection(_),
+                true,
+            ) => format!("({formatted})?"),
+            (_, true) => format!("{formatted}?"),
+        }
+    }
+
+    /// Parenthesize a formatted function, union, or intersection type that's followed by
+    /// more of a type, like `(fun(): nil)[]` or `(fun(): nil) | string`, which would
+    /// otherwise be read as part of the function's return type or of the union.
+    fn parenthesize_compound(&self, formatted: String) -> String {
+        match &self.inner {
+            TypeInner::Function { .. } | TypeInner::Union(_) | TypeInner::Intersection(_) => {
+                format!("({formatted})")
+            }
+            _ => formatted,
+        }
+    }
+
+    /// Parenthesize a formatted member of a union.
+    ///
+    /// Intersections bind tighter than `|`, so they're left alone.
+    fn parenthesize_union_member(&self, formatted: String) -> String {
+        match &self.inner {
+            TypeInner::Intersection(_) => formatted,
+            _ => self.parenthesize_compound(formatted),
+        }
+    }
+
     pub fn format_as_table_field_name(&self) -> String {
         if !self.generics.is_empty() {
             format!("[{self}]")
@@ -188,12 +278,13 @@ impl Type {
                 | TypeInner::Userdata
                 | TypeInner::LightUserdata
                 | TypeInner::Union(_)
+                | TypeInner::Intersection(_)
                 | TypeInner::Array(_)
                 | TypeInner::Tuple(_)
-                | TypeInner::TableDef(_) => format!("[{self}]"),
-                TypeInner::UserDefined(_) | TypeInner::Literal(Literal::String(_)) => {
-                    self.to_string()
-                }
+                | TypeInner::TableDef(_)
+                | TypeInner::UserDefined(_)
+                | TypeInner::Generic(_) => format!("[{self}]"),
+                TypeInner::Literal(Literal::String(_)) => self.to_string(),
             }
         }
     }
@@ -202,6 +293,7 @@ impl Type {
         &self,
         ident_lookup: &HashMap<String, Metatype>,
         base_url: &str,
+        options: LinkOptions,
     ) -> String {
         let repr = match &self.inner {
             TypeInner::Nil => "nil".into(),
@@ -211,12 +303,22 @@ impl Type {
             TypeInner::Number => "number".into(),
             TypeInner::Integer => "integer".into(),
             TypeInner::Table => "table".into(),
-            TypeInner::Literal(lit) => match lit {
-                Literal::Boolean(boolean) => boolean.to_string(),
-                Literal::String(string) => string.clone(),
-                Literal::Number(number) => number.to_string(),
-                Literal::Integer(integer) => integer.to_string(),
-            },
+            TypeInner::Literal(lit) => {
+                let repr = match lit {
+                    Literal::Boolean(boolean) => boolean.to_string(),
+                    Literal::String(string) => string.clone(),
+                    Literal::Number(number) => number.to_string(),
+                    Literal::Integer(integer) => integer.to_string(),
+                };
+
+                match options
+                    .literal_links
+                    .and_then(|links| links.get(&lit.link_key()))
+                {
+                    Some(link) => format!(r#"<a href="{base_url}{link}">{repr}</a>"#),
+                    None => repr,
+                }
+            }
             TypeInner::Function { args, ret } => {
                 let args = args
                     .iter()
@@ -224,7 +326,7 @@ impl Type {
                         let nullable = ty.nullable.then_some("?").unwrap_or_default();
                         format!(
                             "{name}{nullable}: {}",
-                            ty.format_with_links(ident_lookup, base_url)
+                            ty.format_with_links(ident_lookup, base_url, options)
                         )
                     })
                     .collect::<Vec<_>>()
@@ -233,13 +335,16 @@ impl Type {
                 let mut returns = ret
                     .iter()
                     .map(|(name, ty)| {
-                        let nullable = ty.nullable.then_some("?").unwrap_or_default();
                         format!(
-                            "{}{}{nullable}",
+                            "{}{}",
                             name.as_ref()
                                 .map(|name| format!("{name}: "))
                                 .unwrap_or_default(),
-                            ty.format_with_links(ident_lookup, base_url)
+                            ty.format_nullable(ty.format_with_links(
+                                ident_lookup,
+                                base_url,
+                                options
+                            ))
                         )
                     })
                     .collect::<Vec<_>>()
@@ -256,16 +361,49 @@ impl Type {
             TypeInner::LightUserdata => "lightuserdata".into(),
             TypeInner::Union(union) => union
                 .iter()
-                .map(|ty| ty.format_with_links(ident_lookup, base_url))
+                .enumerate()
+                .map(|(i, ty)| {
+                    let formatted = ty.format_with_links(ident_lookup, base_url, options);
+                    if i + 1 < union.len() {
+                        ty.parenthesize_union_member(formatted)
+                    } else {
+                        formatted
+                    }
+                })
                 .collect::<Vec<_>>()
                 .join(" | "),
+            TypeInner::Intersection(types) => types
+                .iter()
+                .map(|ty| {
+                    ty.parenthesize_compound(ty.format_with_links(ident_lookup, base_url, options))
+                })
+                .collect::<Vec<_>>()
+                .join(" & "),
             TypeInner::Array(ty) => {
-                format!("{}[]", ty.format_with_links(ident_lookup, base_url))
+                format!(
+                    "{}[]",
+                    ty.parenthesize_compound(ty.format_with_links(ident_lookup, base_url, options))
+                )
+            }
+            TypeInner::Tuple(tuple) if options.verbose => {
+                let tys = tuple
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ty)| {
+                        format!(
+                            "{}: {}",
+                            i + 1,
+                            ty.format_with_links(ident_lookup, base_url, options)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("[{tys}] (fixed-length)")
             }
             TypeInner::Tuple(tuple) => {
                 let tys = tuple
                     .iter()
-                    .map(|ty| ty.format_with_links(ident_lookup, base_url))
+                    .map(|ty| ty.format_with_links(ident_lookup, base_url, options))
                     .collect::<Vec<_>>()
                     .join(", ");
                 format!("[{tys}]")
@@ -275,12 +413,13 @@ impl Type {
                     .fields
                     .iter()
                     .map(|(name, ty)| {
-                        // WARN: might be cyclic
+                        // Named types are never expanded, so self-referential tables like
+                        // `---@alias Node { next: Node }` end at a link to the name
                         // TODO: add links to name
                         format!(
                             "{}: {}",
                             name.format_as_table_field_name(),
-                            ty.format_with_links(ident_lookup, base_url)
+                            ty.format_with_links(ident_lookup, base_url, options)
                         )
                     })
                     .collect::<Vec<_>>()
@@ -288,13 +427,13 @@ impl Type {
 
                 format!("{{ {fields} }}")
             }
+            TypeInner::Generic(name) => name.clone(),
             TypeInner::UserDefined(name) => {
                 if let Some(metatype) = ident_lookup.get(name) {
-                    let path = match metatype {
-                        // TODO: support arbitrary (nested) sections
-                        Metatype::Class => "classes",
-                        Metatype::Alias => "aliases",
-                        Metatype::Enum => "enums",
+                    // TODO: support arbitrary (nested) sections
+                    let path = match options.section_dirs {
+                        Some(dirs) => dirs.get(*metatype),
+                        None => metatype.default_dir(),
                     };
                     // ???????? VitePress throws an element has missing tag error if the character
                     // directly after a tag is an underscore
@@ -315,7 +454,7 @@ impl Type {
         let mut generics = self
             .generics
             .iter()
-            .map(|ty| ty.format_with_links(ident_lookup, base_url))
+            .map(|ty| ty.format_with_links(ident_lookup, base_url, options))
             .collect::<Vec<_>>()
             .join(", ");
 
@@ -326,13 +465,112 @@ impl Type {
         format!("{repr}{generics}")
     }
 
-    pub fn is_user_defined(&self) -> bool {
-        matches!(&self.inner, TypeInner::UserDefined(_))
+    /// Call `f` on this type and every type nested inside it.
+    pub fn walk(&self, f: &mut impl FnMut(&Type)) {
+        f(self);
+
+        match &self.inner {
+            TypeInner::Function { args, ret } => {
+                for (_, ty) in args {
+                    ty.walk(f);
+                }
+                for (_, ty) in ret {
+                    ty.walk(f);
+                }
+            }
+            TypeInner::Union(types) | TypeInner::Intersection(types) | TypeInner::Tuple(types) => {
+                for ty in types {
+                    ty.walk(f);
+                }
+            }
+            TypeInner::Array(ty) => ty.walk(f),
+            TypeInner::TableDef(table) => {
+                for (name, ty) in table.fields.iter() {
+                    name.walk(f);
+                    ty.walk(f);
+                }
+            }
+            TypeInner::Nil
+            | TypeInner::Any
+            | TypeInner::Boolean
+            | TypeInner::String
+            | TypeInner::Number
+            | TypeInner::Integer
+            | TypeInner::Table
+            | TypeInner::Literal(_)
+            | TypeInner::Thread
+            | TypeInner::Userdata
+            | TypeInner::LightUserdata
+            | TypeInner::UserDefined(_)
+            | TypeInner::Generic(_) => (),
+        }
+
+        for generic in self.generics.iter() {
+            generic.walk(f);
+        }
+    }
+
+    /// Call `f` on this type and every type nested inside it, allowing them to be modified.
+    pub fn walk_mut(&mut self, f: &mut impl FnMut(&mut Type)) {
+        f(self);
+
+        match &mut self.inner {
+            TypeInner::Function { args, ret } => {
+                for (_, ty) in args {
+                    ty.walk_mut(f);
+                }
+                for (_, ty) in ret {
+                    ty.walk_mut(f);
+                }
+            }
+            TypeInner::Union(types) | TypeInner::Intersection(types) | TypeInner::Tuple(types) => {
+                for ty in types {
+                    ty.walk_mut(f);
+                }
+            }
+            TypeInner::Array(ty) => ty.walk_mut(f),
+            TypeInner::TableDef(table) => {
+                for (name, ty) in table.fields.iter_mut() {
+                    name.walk_mut(f);
+                    ty.walk_mut(f);
+                }
+            }
+            TypeInner::Nil
+            | TypeInner::Any
+            | TypeInner::Boolean
+            | TypeInner::String
+            | TypeInner::Number
+            | TypeInner::Integer
+            | TypeInner::Table
+            | TypeInner::Literal(_)
+            | TypeInner::Thread
+            | TypeInner::Userdata
+            | TypeInner::LightUserdata
+            | TypeInner::UserDefined(_)
+            | TypeInner::Generic(_) => (),
+        }
+
+        for generic in self.generics.iter_mut() {
+            generic.walk_mut(f);
+        }
+    }
+
+    /// Turn references to any of the given type parameters into [`TypeInner::Generic`]s.
+    pub fn resolve_type_params(&mut self, type_params: &[String]) {
+        self.walk_mut(&mut |ty| {
+            if let TypeInner::UserDefined(name) = &ty.inner {
+                if type_params.contains(name) {
+                    ty.inner = TypeInner::Generic(name.clone());
+                }
+            }
+        });
     }
 }
 
-impl std::fmt::Display for Type {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Type {
+    /// Format this type like its `Display` impl, passing user-defined type names
+    /// through `format_name`.
+    pub fn format_with_names(&self, format_name: &dyn Fn(&str) -> String) -> String {
         let repr = match &self.inner {
             TypeInner::Nil => "nil".into(),
             TypeInner::Any => "any".into(),
@@ -350,7 +588,10 @@ impl std::fmt::Display for Type {
             TypeInner::Function { args, ret } => {
                 let args = args
                     .iter()
-                    .map(|(name, ty)| format!("{name}: {ty}"))
+                    .map(|(name, ty)| {
+                        let nullable = ty.nullable.then_some("?").unwrap_or_default();
+                        format!("{name}{nullable}: {}", ty.format_with_names(format_name))
+                    })
                     .collect::<Vec<_>>()
                     .join(", ");
 
@@ -358,10 +599,11 @@ impl std::fmt::Display for Type {
                     .iter()
                     .map(|(name, ty)| {
                         format!(
-                            "{}{ty}",
+                            "{}{}",
                             name.as_ref()
                                 .map(|name| format!("{name}: "))
-                                .unwrap_or_default()
+                                .unwrap_or_default(),
+                            ty.format_nullable(ty.format_with_names(format_name))
                         )
                     })
                     .collect::<Vec<_>>()
@@ -378,16 +620,32 @@ impl std::fmt::Display for Type {
             TypeInner::LightUserdata => "lightuserdata".into(),
             TypeInner::Union(union) => union
                 .iter()
-                .map(|ty| ty.to_string())
+                .enumerate()
+                .map(|(i, ty)| {
+                    let formatted = ty.format_with_names(format_name);
+                    if i + 1 < union.len() {
+                        ty.parenthesize_union_member(formatted)
+                    } else {
+                        formatted
+                    }
+                })
                 .collect::<Vec<_>>()
                 .join(" | "),
+            TypeInner::Intersection(types) => types
+                .iter()
+                .map(|ty| ty.parenthesize_compound(ty.format_with_names(format_name)))
+                .collect::<Vec<_>>()
+                .join(" & "),
             TypeInner::Array(ty) => {
-                format!("{ty}[]")
+                format!(
+                    "{}[]",
+                    ty.parenthesize_compound(ty.format_with_names(format_name))
+                )
             }
             TypeInner::Tuple(tuple) => {
                 let tys = tuple
                     .iter()
-                    .map(|ty| ty.to_string())
+                    .map(|ty| ty.format_with_names(format_name))
                     .collect::<Vec<_>>()
                     .join(", ");
                 format!("[{tys}]")
@@ -397,21 +655,26 @@ impl std::fmt::Display for Type {
                     .fields
                     .iter()
                     .map(|(name, ty)| {
-                        // WARN: might be cyclic
-                        format!("{}: {ty}", name.format_as_table_field_name())
+                        // Named types are never expanded, so this can't recurse forever
+                        format!(
+                            "{}: {}",
+                            name.format_as_table_field_name(),
+                            ty.format_with_names(format_name)
+                        )
                     })
                     .collect::<Vec<_>>()
                     .join(", ");
 
                 format!("{{ {fields} }}")
             }
-            TypeInner::UserDefined(name) => name.clone(),
+            TypeInner::UserDefined(name) => format_name(name),
+            TypeInner::Generic(name) => name.clone(),
         };
 
         let mut generics = self
             .generics
             .iter()
-            .map(|ty| ty.to_string())
+            .map(|ty| ty.format_with_names(format_name))
             .collect::<Vec<_>>()
             .join(", ");
 
@@ -419,11 +682,19 @@ impl std::fmt::Display for Type {
             generics = format!("<{generics}>");
         }
 
-        write!(f, "{repr}{generics}")
+        format!("{repr}{generics}")
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format_with_names(&|name| name.to_string()))
+    }
+}
+
+/// Serialized tagged with its kind, like `{ "kind": "array", "value": { "kind": "string" } }`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
 pub enum TypeInner {
     Nil,
     Any,
@@ -434,6 +705,7 @@ pub enum TypeInner {
     Table,
     Literal(Literal),
     Function {
+        /// The names and types of the arguments. Varargs are named `...`.
         args: Vec<(String, Type)>,
         ret: Vec<(Option<String>, Type)>,
     },
@@ -441,13 +713,18 @@ pub enum TypeInner {
     Userdata,
     LightUserdata,
     Union(Vec<Type>),
+    /// Types combined with `&`, which binds tighter than `|`.
+    Intersection(Vec<Type>),
     Array(Box<Type>),
     Tuple(Vec<Type>),
     TableDef(TableDef),
     UserDefined(String),
+    /// A type parameter, like `T` in `---@alias Result<T> T | string`.
+    Generic(String),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
 pub enum Literal {
     Boolean(bool),
     String(String),
@@ -455,7 +732,40 @@ pub enum Literal {
     Integer(i64),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl Literal {
+    /// The key of this literal in [`LinkOptions::literal_links`].
+    ///
+    /// Strings are keyed by their contents in double quotes, so `'a'` and `"a"` share a key.
+    pub fn link_key(&self) -> String {
+        match self {
+            Literal::Boolean(boolean) => boolean.to_string(),
+            Literal::String(string) => format!("\"{}\"", unquote(string)),
+            Literal::Number(number) => number.to_string(),
+            Literal::Integer(integer) => integer.to_string(),
+        }
+    }
+}
+
+/// Options for [`Type::format_with_links`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LinkOptions<'a> {
+    /// Spell out details that are easy to miss, like tuples being fixed-length.
+    pub verbose: bool,
+    /// Links for literal types relative to the base url, keyed by [`Literal::link_key`].
+    pub literal_links: Option<&'a HashMap<String, String>>,
+    /// Where class, alias, and enum pages live, defaulting to [`SectionDirs::default`].
+    pub section_dirs: Option<&'a SectionDirs>,
+}
+
+/// Remove the quotes around a string literal like `"windowed"`.
+pub fn unquote(string: &str) -> &str {
+    ['"', '\'', '`']
+        .into_iter()
+        .find_map(|quote| string.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(string)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct TableDef {
     pub fields: Vec<(Type, Type)>,
 }